@@ -0,0 +1,69 @@
+// Benchmarks for the collectors. The parse benchmarks run over the committed
+// fixtures and work on any host; `--features bench-collectors` additionally
+// measures the real collectors, including /proc reads and subprocesses such as
+// the PowerShell queries on Windows.
+//
+//     cargo bench
+//     cargo bench --features bench-collectors
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+macro_rules! fixture {
+    ($distro:literal, $file:literal) => {
+        include_str!(concat!("../tests/fixtures/", $distro, "/", $file))
+    };
+}
+
+fn parsers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    
+    group.bench_function("cpuinfo", |b| {
+        b.iter(|| rustfetch::parse_cpu_model(black_box(fixture!("arch", "cpuinfo"))))
+    });
+    group.bench_function("cpu_flags", |b| {
+        b.iter(|| rustfetch::notable_cpu_features(rustfetch::parse_cpu_flags(black_box(fixture!("arch", "cpuinfo")))))
+    });
+    group.bench_function("meminfo", |b| {
+        b.iter(|| rustfetch::format_meminfo_usage(&rustfetch::parse_meminfo(black_box(fixture!("fedora", "meminfo"))), rustfetch::Precision::default()))
+    });
+    group.bench_function("uptime", |b| {
+        b.iter(|| rustfetch::parse_uptime(black_box(fixture!("arch", "uptime"))).map(|seconds| rustfetch::format_uptime(seconds, rustfetch::UptimeFormat::Verbose)))
+    });
+    group.bench_function("stat", |b| {
+        b.iter(|| rustfetch::parse_boot_time(black_box(fixture!("arch", "stat"))))
+    });
+    group.bench_function("os_release", |b| {
+        b.iter(|| rustfetch::parse_os_release(black_box(fixture!("fedora", "os-release"))))
+    });
+    group.bench_function("system_profiler_gpus", |b| {
+        b.iter(|| rustfetch::parse_macos_gpus(black_box(fixture!("macos", "SPDisplaysDataType-intel"))))
+    });
+    
+    group.finish();
+}
+
+#[cfg(feature = "bench-collectors")]
+fn collectors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collect");
+    // Subprocess-backed collectors are slow; keep the run time reasonable
+    group.sample_size(10);
+    
+    group.bench_function("os", |b| b.iter(|| rustfetch::get_os_info(rustfetch::ArchFormat::Raw)));
+    group.bench_function("kernel", |b| b.iter(|| rustfetch::get_kernel_version(false)));
+    group.bench_function("uptime", |b| b.iter(|| rustfetch::get_uptime(rustfetch::UptimeFormat::Verbose)));
+    group.bench_function("packages", |b| b.iter(|| rustfetch::get_packages(1, false, "")));
+    group.bench_function("shell", |b| b.iter(rustfetch::get_shell));
+    group.bench_function("cpu", |b| b.iter(|| rustfetch::get_cpu_info(false)));
+    group.bench_function("gpu", |b| b.iter(rustfetch::get_gpu_info));
+    group.bench_function("memory", |b| b.iter(|| rustfetch::get_memory_info(rustfetch::Precision::default())));
+    group.bench_function("swap", |b| b.iter(|| rustfetch::get_swap_info(false, rustfetch::Precision::default())));
+    group.bench_function("disk", |b| b.iter(|| rustfetch::get_disk_info(rustfetch::Precision::default())));
+    
+    group.finish();
+}
+
+#[cfg(feature = "bench-collectors")]
+criterion_group!(benches, parsers, collectors);
+#[cfg(not(feature = "bench-collectors"))]
+criterion_group!(benches, parsers);
+criterion_main!(benches);
@@ -0,0 +1,117 @@
+// Cache for the fields that can't change until the next boot (OS, host,
+// CPU and GPU), for running on every shell prompt. The file records the
+// boot it was written in and is ignored once that no longer matches.
+//
+// The format is one `<field> <value>` pair per line, after a `key` line;
+// `gpu` may repeat.
+
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticFields {
+    pub os: String,
+    pub host: String,
+    pub cpu: String,
+    pub gpu: Vec<String>,
+}
+
+// Where the cache lives: `<cache dir>/rustfetch/static`
+fn cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("rustfetch").join("static"))
+}
+
+// Identifies the current boot, or None where there's no cheap way to tell
+// (which turns caching off). Linux has a boot ID; macOS and the BSDs have
+// the boot time.
+pub fn boot_id() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        fs::read_to_string("/proc/sys/kernel/random/boot_id")
+            .ok()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+    } else if cfg!(target_os = "macos") || cfg!(target_os = "freebsd") || cfg!(target_os = "netbsd") {
+        crate::sysctl("kern.boottime")
+    } else {
+        None
+    }
+}
+
+pub fn load(key: &str) -> Option<StaticFields> {
+    parse(&fs::read_to_string(cache_path()?).ok()?, key)
+}
+
+// Failing to write the cache only costs speed next time, so errors are
+// ignored. The file is written beside the cache and renamed over it, so a
+// prompt reading it meanwhile sees the old cache or the new one, never half
+// of one.
+pub fn store(key: &str, fields: &StaticFields) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    // Per process, so two prompts writing at once don't share a temp file
+    let temp = path.with_file_name(format!("static.{}.tmp", std::process::id()));
+    if fs::write(&temp, serialize(key, fields)).is_err() || fs::rename(&temp, &path).is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+}
+
+fn serialize(key: &str, fields: &StaticFields) -> String {
+    let mut out = format!("key {}\nos {}\nhost {}\ncpu {}\n", key, fields.os, fields.host, fields.cpu);
+    for gpu in &fields.gpu {
+        out.push_str(&format!("gpu {}\n", gpu));
+    }
+    out
+}
+
+// Reads the cache back, provided it was written under the same `key`
+fn parse(contents: &str, key: &str) -> Option<StaticFields> {
+    let mut lines = contents.lines();
+    if lines.next()?.strip_prefix("key ")? != key {
+        return None;
+    }
+    let (mut os, mut host, mut cpu, mut gpu) = (None, None, None, Vec::new());
+    for line in lines {
+        let (field, value) = line.split_once(' ').unwrap_or((line, ""));
+        match field {
+            "os" => os = Some(value.to_string()),
+            "host" => host = Some(value.to_string()),
+            "cpu" => cpu = Some(value.to_string()),
+            "gpu" => gpu.push(value.to_string()),
+            _ => return None,
+        }
+    }
+    Some(StaticFields { os: os?, host: host?, cpu: cpu?, gpu })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> StaticFields {
+        StaticFields {
+            os: "Arch Linux x86_64".to_string(),
+            host: "Framework Laptop 13".to_string(),
+            cpu: "AMD Ryzen 7 7840U (16)".to_string(),
+            gpu: vec!["AMD Radeon 780M".to_string(), "NVIDIA RTX 4050".to_string()],
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let contents = serialize("boot-1 features", &fields());
+        assert_eq!(parse(&contents, "boot-1 features"), Some(fields()));
+    }
+
+    #[test]
+    fn rejects_stale_or_broken_caches() {
+        let contents = serialize("boot-1", &fields());
+        assert_eq!(parse(&contents, "boot-2"), None);
+        assert_eq!(parse("key boot-1\nos Arch\n", "boot-1"), None);
+        assert_eq!(parse("key boot-1\nos Arch\nhost X\ncpu Y\nshell zsh\n", "boot-1"), None);
+        assert_eq!(parse("", "boot-1"), None);
+    }
+}
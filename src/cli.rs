@@ -0,0 +1,376 @@
+use std::path::PathBuf;
+
+use crate::image::ImageMode;
+use crate::logos::{LogoPosition, LogoType};
+use crate::themes;
+
+// Command-line flags. Anything set here takes precedence over the config file.
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    pub help: bool,
+    pub print_config_path: bool,
+    pub color_test: bool,
+    pub list_modules: bool,
+    pub list_sensors: bool,
+    pub check: bool,
+    pub markdown: bool,
+    pub check_update: bool,
+    pub refresh_cache: bool,
+    pub motd: bool,
+    pub offline: bool,
+    pub hide_unknown: bool,
+    pub no_color: bool,
+    pub force_color: bool,
+    pub separator_length: Option<i64>,
+    pub logo_type: Option<LogoType>,
+    // Logo name or file, ahead of RUSTFETCH_LOGO and the config file
+    pub logo: Option<String>,
+    pub logo_position: Option<LogoPosition>,
+    // Image to draw instead of the logo, and how
+    pub image: Option<PathBuf>,
+    pub image_mode: Option<ImageMode>,
+    // Color theme, in place of the config file's `theme`
+    pub theme: Option<String>,
+    // `[profiles.<name>]` section of the config file to apply
+    pub profile: Option<String>,
+    // Modules to skip, one per `--hide`, on top of RUSTFETCH_DISABLE
+    pub hide: Vec<String>,
+    // Show canned info instead of this machine's (debug builds only)
+    pub mock: bool,
+    // `--dump-raw`, with the file from `--dump-raw=FILE` (stderr otherwise)
+    pub dump_raw: Option<Option<PathBuf>>,
+}
+
+// Help text for `--help`; takes the resolved config path so users know
+// where to put their config on this platform
+pub fn usage(config_path: &str) -> String {
+    let mut usage = format!(
+        "\
+Usage: rustfetch [OPTIONS]
+
+Options:
+  -h, --help                  Print this help and exit
+      --config-path           Print the config file path and exit
+      --color-test            Print the available colors and exit
+      --list-modules          Print every module, whether it's on by default,
+                              and what it shows, then exit
+      --list-sensors          Print every hwmon temperature sensor and its
+                              reading, then exit
+      --check                 Run the default modules, list the ones that came
+                              back unknown, and exit with how many did
+      --markdown              Print the info as a Markdown code block, plus
+                              every quick module in a collapsed section, for
+                              bug reports
+      --check-update          Ask crates.io whether a newer release is out, and
+                              exit (never installs anything)
+      --refresh-cache         Recompute the cached OS, host, CPU and GPU lines
+      --dump-raw[=FILE]       Also write every command output and file the
+                              modules read, and what they made of it, to
+                              stderr or FILE (for bug reports)
+      --motd                  Output suited to a login banner (no terminal
+                              detection, no network or custom modules)
+      --offline               Skip modules that may wait on the network,
+                              including custom ones
+      --no-color              Don't print color escapes
+      --force-color           Print colors even when not writing to a terminal
+      --separator-length <N>  Lengthen the line under the title by N columns,
+                              or shorten it with a negative N
+      --theme <NAME>          Use a color theme: auto, dark, light, gruvbox, nord,
+                              dracula or monochrome
+      --profile <NAME>        Apply the config file's [profiles.NAME] section
+      --hide <MODULE>         Skip a module this time; repeat for more
+      --hide-unknown          Leave out lines whose value couldn't be detected
+      --logo-type <TYPE>      Show the `os` logo (default) or the `cpu` vendor's
+      --logo <NAME|FILE>      Show a built-in or user logo by name, or a logo file
+      --logo-position <POS>   Put the logo `left` (default), `right`, `top` or
+                              `none` at all
+      --image <FILE>          Draw a PPM image at the logo's size instead of the
+                              logo (needs the `image` feature and truecolor)
+      --image-mode <MODE>     How to draw --image: `blocks` (half blocks)
+
+Config file:
+  {}
+  Command-line flags take precedence over the config file.
+
+Logo:
+  --logo, then $RUSTFETCH_LOGO, then `logo` in the config file, then the
+  logo for the detected OS (or CPU, with --logo-type cpu).
+
+Environment:
+  RUSTFETCH_LOGO              The logo to show, below --logo
+  RUSTFETCH_DISABLE           Comma-separated modules to skip, along with any
+                              --hide ones; unknown names only get a warning
+",
+        config_path
+    );
+    if cfg!(debug_assertions) {
+        usage.push_str(
+            "
+Debug builds:
+  --mock                      Show a canned machine instead of this one, for
+                              previewing themes and logos (or RUSTFETCH_MOCK=1)
+",
+        );
+    }
+    usage
+}
+
+impl Args {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // Accept both `--flag value` and `--flag=value`
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg, None),
+            };
+            let mut value = || {
+                inline_value.clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("`{}` requires a value", flag))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "--config-path" => parsed.print_config_path = true,
+                "--color-test" => parsed.color_test = true,
+                "--list-modules" => parsed.list_modules = true,
+                "--list-sensors" => parsed.list_sensors = true,
+                "--check" => parsed.check = true,
+                "--markdown" => parsed.markdown = true,
+                "--check-update" => parsed.check_update = true,
+                "--refresh-cache" => parsed.refresh_cache = true,
+                "--motd" => parsed.motd = true,
+                "--offline" => parsed.offline = true,
+                "--hide-unknown" => parsed.hide_unknown = true,
+                "--no-color" => parsed.no_color = true,
+                "--force-color" => parsed.force_color = true,
+                "--separator-length" => {
+                    let length = value()?;
+                    parsed.separator_length = Some(
+                        length.parse().map_err(|_| format!("invalid separator length `{}`", length))?,
+                    );
+                }
+                "--logo-type" => {
+                    parsed.logo_type = Some(match value()?.as_str() {
+                        "os" => LogoType::Os,
+                        "cpu" => LogoType::Cpu,
+                        other => return Err(format!("invalid logo type `{}` (expected `os` or `cpu`)", other)),
+                    });
+                }
+                "--logo" => parsed.logo = Some(value()?),
+                "--image" => parsed.image = Some(PathBuf::from(value()?)),
+                "--image-mode" => {
+                    let mode = value()?;
+                    parsed.image_mode = Some(ImageMode::from_name(&mode).ok_or_else(|| {
+                        format!("invalid image mode `{}` (expected {})", mode, ImageMode::NAMES)
+                    })?);
+                }
+                "--theme" => {
+                    let theme = value()?;
+                    if theme != "auto" && themes::preset(&theme).is_none() {
+                        let names: Vec<&str> = themes::names().collect();
+                        return Err(format!("invalid theme `{}` (expected `auto` or one of {})", theme, names.join(", ")));
+                    }
+                    parsed.theme = Some(theme);
+                }
+                "--profile" => parsed.profile = Some(value()?),
+                "--hide" => parsed.hide.push(value()?),
+                // The file is optional, so it's only taken in the `=` form
+                "--dump-raw" => parsed.dump_raw = Some(inline_value.clone().map(PathBuf::from)),
+                #[cfg(debug_assertions)]
+                "--mock" => parsed.mock = true,
+                "--logo-position" => {
+                    let position = value()?;
+                    parsed.logo_position = Some(LogoPosition::from_name(&position).ok_or_else(|| {
+                        format!("invalid logo position `{}` (expected {})", position, LogoPosition::NAMES)
+                    })?);
+                }
+                _ => return Err(format!("unknown argument `{}`", flag)),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_separator_length() {
+        assert_eq!(parse(&["--separator-length", "8"]).unwrap().separator_length, Some(8));
+        assert_eq!(parse(&["--separator-length=0"]).unwrap().separator_length, Some(0));
+        assert_eq!(parse(&["--separator-length", "-2"]).unwrap().separator_length, Some(-2));
+        assert_eq!(parse(&[]).unwrap().separator_length, None);
+    }
+
+    #[test]
+    fn parses_help() {
+        assert!(parse(&["--help"]).unwrap().help);
+        assert!(parse(&["-h"]).unwrap().help);
+        assert!(!parse(&[]).unwrap().help);
+    }
+
+    #[test]
+    fn parses_config_path() {
+        assert!(parse(&["--config-path"]).unwrap().print_config_path);
+        assert!(!parse(&[]).unwrap().print_config_path);
+    }
+
+    #[test]
+    fn parses_color_test() {
+        assert!(parse(&["--color-test"]).unwrap().color_test);
+        assert!(!parse(&[]).unwrap().color_test);
+    }
+
+    #[test]
+    fn parses_list_modules() {
+        assert!(parse(&["--list-modules"]).unwrap().list_modules);
+        assert!(!parse(&[]).unwrap().list_modules);
+    }
+
+    #[test]
+    fn parses_list_sensors() {
+        assert!(parse(&["--list-sensors"]).unwrap().list_sensors);
+        assert!(!parse(&[]).unwrap().list_sensors);
+    }
+
+    #[test]
+    fn parses_check() {
+        assert!(parse(&["--check"]).unwrap().check);
+        assert!(!parse(&[]).unwrap().check);
+    }
+
+    #[test]
+    fn parses_markdown() {
+        assert!(parse(&["--markdown"]).unwrap().markdown);
+        assert!(!parse(&[]).unwrap().markdown);
+    }
+
+    #[test]
+    fn parses_check_update() {
+        assert!(parse(&["--check-update"]).unwrap().check_update);
+        assert!(!parse(&[]).unwrap().check_update);
+    }
+
+    #[test]
+    fn parses_refresh_cache() {
+        assert!(parse(&["--refresh-cache"]).unwrap().refresh_cache);
+        assert!(!parse(&[]).unwrap().refresh_cache);
+    }
+
+    #[test]
+    fn parses_motd_flags() {
+        let args = parse(&["--motd", "--no-color"]).unwrap();
+        assert!(args.motd && args.no_color);
+        assert!(!parse(&[]).unwrap().motd);
+        assert!(parse(&["--offline"]).unwrap().offline);
+        assert!(parse(&["--force-color"]).unwrap().force_color);
+    }
+
+    #[test]
+    fn parses_logo_type() {
+        assert_eq!(parse(&["--logo-type", "cpu"]).unwrap().logo_type, Some(LogoType::Cpu));
+        assert_eq!(parse(&["--logo-type=os"]).unwrap().logo_type, Some(LogoType::Os));
+        assert_eq!(parse(&[]).unwrap().logo_type, None);
+        assert_eq!(
+            parse(&["--logo-type", "gpu"]).unwrap_err(),
+            "invalid logo type `gpu` (expected `os` or `cpu`)"
+        );
+    }
+
+    #[test]
+    fn parses_logo() {
+        assert_eq!(parse(&["--logo", "debian"]).unwrap().logo.as_deref(), Some("debian"));
+        assert_eq!(parse(&["--logo=~/art.txt"]).unwrap().logo.as_deref(), Some("~/art.txt"));
+        assert_eq!(parse(&[]).unwrap().logo, None);
+        assert_eq!(parse(&["--logo"]).unwrap_err(), "`--logo` requires a value");
+    }
+
+    #[test]
+    fn parses_logo_position() {
+        assert_eq!(parse(&["--logo-position", "top"]).unwrap().logo_position, Some(LogoPosition::Top));
+        assert_eq!(parse(&["--logo-position=none"]).unwrap().logo_position, Some(LogoPosition::None));
+        assert_eq!(parse(&[]).unwrap().logo_position, None);
+        assert_eq!(
+            parse(&["--logo-position", "bottom"]).unwrap_err(),
+            "invalid logo position `bottom` (expected `left`, `right`, `top` or `none`)"
+        );
+    }
+
+    #[test]
+    fn parses_image() {
+        let args = parse(&["--image", "logo.ppm", "--image-mode=blocks"]).unwrap();
+        assert_eq!(args.image, Some(PathBuf::from("logo.ppm")));
+        assert_eq!(args.image_mode, Some(ImageMode::Blocks));
+        assert_eq!(parse(&[]).unwrap().image, None);
+        assert_eq!(
+            parse(&["--image-mode", "sixel"]).unwrap_err(),
+            "invalid image mode `sixel` (expected `blocks`)"
+        );
+    }
+
+    #[test]
+    fn parses_theme() {
+        assert_eq!(parse(&["--theme", "nord"]).unwrap().theme.as_deref(), Some("nord"));
+        assert_eq!(parse(&["--theme=auto"]).unwrap().theme.as_deref(), Some("auto"));
+        assert_eq!(parse(&[]).unwrap().theme, None);
+        assert_eq!(
+            parse(&["--theme", "solarized"]).unwrap_err(),
+            "invalid theme `solarized` (expected `auto` or one of dark, light, gruvbox, nord, dracula, monochrome)"
+        );
+    }
+
+    #[test]
+    fn parses_profile() {
+        assert_eq!(parse(&["--profile", "minimal"]).unwrap().profile.as_deref(), Some("minimal"));
+        assert_eq!(parse(&["--profile=full"]).unwrap().profile.as_deref(), Some("full"));
+        assert_eq!(parse(&[]).unwrap().profile, None);
+        assert_eq!(parse(&["--profile"]).unwrap_err(), "`--profile` requires a value");
+    }
+
+    #[test]
+    fn parses_hide_unknown() {
+        assert!(parse(&["--hide-unknown"]).unwrap().hide_unknown);
+        assert!(!parse(&[]).unwrap().hide_unknown);
+    }
+
+    #[test]
+    fn parses_hide() {
+        assert_eq!(parse(&["--hide", "gpu", "--hide=uptime"]).unwrap().hide, ["gpu", "uptime"]);
+        assert!(parse(&[]).unwrap().hide.is_empty());
+        assert_eq!(parse(&["--hide"]).unwrap_err(), "`--hide` requires a value");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn parses_mock() {
+        assert!(parse(&["--mock"]).unwrap().mock);
+        assert!(!parse(&[]).unwrap().mock);
+    }
+
+    #[test]
+    fn parses_dump_raw() {
+        assert_eq!(parse(&["--dump-raw"]).unwrap().dump_raw, Some(None));
+        assert_eq!(parse(&["--dump-raw=raw.txt"]).unwrap().dump_raw, Some(Some(PathBuf::from("raw.txt"))));
+        // A following argument is left alone
+        let args = parse(&["--dump-raw", "--no-color"]).unwrap();
+        assert_eq!((args.dump_raw, args.no_color), (Some(None), true));
+        assert_eq!(parse(&[]).unwrap().dump_raw, None);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert_eq!(parse(&["--separator-length"]).unwrap_err(), "`--separator-length` requires a value");
+        assert_eq!(parse(&["--separator-length", "x"]).unwrap_err(), "invalid separator length `x`");
+        assert_eq!(parse(&["--nope"]).unwrap_err(), "unknown argument `--nope`");
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::BTreeMap;
+use std::env;
+
+pub const RESET: &str = "\x1b[0m";
+pub const BOLD: &str = "\x1b[1m";
+
+const NAMES: &[(&str, u8)] = &[
+    ("black", 30),
+    ("red", 31),
+    ("green", 32),
+    ("yellow", 33),
+    ("blue", 34),
+    ("magenta", 35),
+    ("cyan", 36),
+    ("white", 37),
+    ("bright_black", 90),
+    ("bright_red", 91),
+    ("bright_green", 92),
+    ("bright_yellow", 93),
+    ("bright_blue", 94),
+    ("bright_magenta", 95),
+    ("bright_cyan", 96),
+    ("bright_white", 97),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    // Basic 3/4-bit color, stored as its SGR foreground code (30-37, 90-97)
+    Named(u8),
+    // Index into the 256-color palette
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    // Accepts a color name ("red", "bright_blue"), a hex value ("#1793d1"
+    // or "#fff") or a 256-color palette index ("208")
+    pub fn parse(input: &str) -> Result<Color, String> {
+        let input = input.trim();
+        let normalized = input.to_ascii_lowercase().replace(['-', ' '], "_");
+
+        if let Some(&(_, code)) = NAMES.iter().find(|(name, _)| *name == normalized) {
+            return Ok(Color::Named(code));
+        }
+        if let Some(hex) = input.strip_prefix('#') {
+            return parse_hex(hex).ok_or_else(|| format!("invalid hex color `{}`", input));
+        }
+        if let Ok(index) = input.parse::<u8>() {
+            return Ok(Color::Indexed(index));
+        }
+        Err(format!("unknown color `{}`", input))
+    }
+
+    // The escape sequence that selects this color as the foreground. Hex
+    // colors fall back to the nearest palette entry when the terminal
+    // doesn't advertise truecolor support.
+    pub fn escape(&self, truecolor: bool) -> String {
+        match *self {
+            Color::Named(code) => format!("\x1b[{}m", code),
+            Color::Indexed(index) => format!("\x1b[38;5;{}m", index),
+            Color::Rgb(r, g, b) if truecolor => format!("\x1b[38;2;{};{};{}m", r, g, b),
+            Color::Rgb(r, g, b) => format!("\x1b[38;5;{}m", nearest_256(r, g, b)),
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        6 => Some(Color::Rgb(channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+        // "#abc" is shorthand for "#aabbcc"
+        3 => {
+            let double = |i: usize| channel(&hex[i..i + 1]).map(|v| v * 17);
+            Some(Color::Rgb(double(0)?, double(1)?, double(2)?))
+        }
+        _ => None,
+    }
+}
+
+// Maps an RGB value onto the closest entry of the 6x6x6 color cube
+// (indices 16-231) or the grayscale ramp (232-255)
+pub fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, r2) + d(g, g2) + d(b, b2)
+    };
+    let nearest_level = |v: u8| {
+        (0..6).min_by_key(|&i| (LEVELS[i] as i32 - v as i32).abs()).unwrap_or(0)
+    };
+
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+    let cube_distance = distance((LEVELS[ri], LEVELS[gi], LEVELS[bi]));
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = ((average.saturating_sub(8) + 5) / 10).min(23) as u8;
+    let gray = 8 + gray_step * 10;
+    let gray_distance = distance((gray, gray, gray));
+
+    if gray_distance < cube_distance {
+        232 + gray_step
+    } else {
+        cube_index as u8
+    }
+}
+
+// Whether to color the output. --force-color beats everything, then
+// --no-color and NO_COLOR (https://no-color.org: set and non-empty) turn it
+// off; otherwise color only when stdout is a terminal.
+pub fn color_enabled(force_color: bool, no_color: bool, no_color_env: Option<&str>, is_terminal: bool) -> bool {
+    if force_color {
+        return true;
+    }
+    if no_color || no_color_env.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    is_terminal
+}
+
+// Whether the terminal advertises 24-bit color support
+pub fn supports_truecolor() -> bool {
+    env::var("COLORTERM")
+        .map(|value| value == "truecolor" || value == "24bit")
+        .unwrap_or(false)
+}
+
+// Output for `--color-test`: every color name with a swatch, the full
+// 256-color palette and a truecolor gradient, to help with writing themes
+pub fn color_test(truecolor: bool) -> String {
+    let swatch = |color: Color| format!("{}\x1b[7m    {}", color.escape(truecolor), RESET);
+    let mut out = String::from("Named colors:\n");
+    for &(name, code) in NAMES {
+        out.push_str(&format!("  {} {}\n", swatch(Color::Named(code)), name));
+    }
+
+    out.push_str("\n256-color palette:\n");
+    // The 16 basic colors, then the 6x6x6 cube six entries per row, then
+    // the grayscale ramp
+    let rows = [0..8, 8..16]
+        .into_iter()
+        .chain((16..232).step_by(6).map(|start| start..start + 6))
+        .chain([232..244, 244..256]);
+    for row in rows {
+        out.push_str("  ");
+        for index in row {
+            out.push_str(&format!("{}\x1b[7m{:>4}{}", Color::Indexed(index as u8).escape(truecolor), index, RESET));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("\nTruecolor gradient");
+    if !truecolor {
+        out.push_str(" (approximated; COLORTERM doesn't advertise truecolor)");
+    }
+    out.push_str(":\n  ");
+    const STEPS: u32 = 64;
+    for step in 0..STEPS {
+        let (r, g, b) = hue_to_rgb(step * 360 / STEPS);
+        out.push_str(&format!("{}\x1b[7m {}", Color::Rgb(r, g, b).escape(truecolor), RESET));
+    }
+    out.push('\n');
+    out
+}
+
+// Fully saturated color for a hue in degrees, for the gradient
+fn hue_to_rgb(hue: u32) -> (u8, u8, u8) {
+    let sector = (hue % 360) / 60;
+    let rising = ((hue % 60) * 255 / 60) as u8;
+    let falling = 255 - rising;
+    match sector {
+        0 => (255, rising, 0),
+        1 => (falling, 255, 0),
+        2 => (0, 255, rising),
+        3 => (0, falling, 255),
+        4 => (rising, 0, 255),
+        _ => (255, 0, falling),
+    }
+}
+
+// Colors a numeric field by where its value falls: `colors[0]` below
+// `limits[0]`, `colors[1]` from there up to `limits[1]`, and so on, with the
+// last color for everything at or above the last limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thresholds {
+    pub colors: Vec<Color>,
+    pub limits: Vec<f64>,
+}
+
+impl Thresholds {
+    pub fn color_for(&self, value: f64) -> Color {
+        let index = self.limits.iter().take_while(|&&limit| value >= limit).count();
+        self.colors[index]
+    }
+}
+
+// The number a threshold is checked against: the first percentage in the
+// value if there is one ("6.70 GiB / 62.00 GiB (10%)" gives 10), else the
+// first number at all ("65°C" gives 65)
+pub fn field_number(value: &str) -> Option<f64> {
+    let mut numbers = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let tail = &rest[start..];
+        let end = tail.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(tail.len());
+        let number = tail[..end].trim_end_matches('.');
+        // Dotted runs like "1.2.3" aren't numbers; skip them, not the value
+        if let Ok(number) = number.parse::<f64>() {
+            numbers.push((number, tail[end..].starts_with('%')));
+        }
+        rest = &tail[end..];
+    }
+    numbers.iter()
+        .find(|(_, percent)| *percent)
+        .or(numbers.first())
+        .map(|(number, _)| *number)
+}
+
+// Config key a line's label is matched against: "CPU Temp" is `cpu_temp`,
+// and "Battery (BAT0)" or "Disk (/)" are plain `battery` and `disk`
+pub fn field_key(label: &str) -> String {
+    let label = label.split(" (").next().unwrap_or(label);
+    label.trim().to_ascii_lowercase().replace(' ', "_")
+}
+
+// Colors used for each part of the output
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colors {
+    pub title: Color,
+    pub separator: Color,
+    pub label: Color,
+    // Values are left in the terminal's default color unless set
+    pub value: Option<Color>,
+    // Replaces the logo's first color; unset keeps the logo's own colors
+    pub logo: Option<Color>,
+    // Value colors for individual fields, keyed by `field_key`
+    pub fields: BTreeMap<String, Color>,
+    // Value colors picked by number, e.g. red memory usage above 80%
+    pub thresholds: BTreeMap<String, Thresholds>,
+    // Bold titles and labels (`use_bold`)
+    pub bold: bool,
+}
+
+impl Colors {
+    // Built-in palette for dark backgrounds; also the default
+    pub fn dark() -> Colors {
+        Colors::default()
+    }
+
+    // Built-in palette for light backgrounds, avoiding the yellows and
+    // bright greens that wash out on white
+    pub fn light() -> Colors {
+        Colors {
+            title: Color::Named(34),
+            separator: Color::Named(90),
+            label: Color::Named(35),
+            ..Colors::default()
+        }
+    }
+
+    // The bold escape for titles and labels; nothing when bold is turned
+    // off or when color is off altogether
+    pub fn bold_escape(&self, color: bool) -> &'static str {
+        if color && self.bold {
+            BOLD
+        } else {
+            ""
+        }
+    }
+
+    // The color for one info line's value: a matching threshold, then a
+    // fixed per-field color, then the general value color
+    pub fn value_for(&self, label: &str, value: &str) -> Option<Color> {
+        let key = field_key(label);
+        self.thresholds.get(&key)
+            .and_then(|thresholds| field_number(value).map(|number| thresholds.color_for(number)))
+            .or_else(|| self.fields.get(&key).copied())
+            .or(self.value)
+    }
+}
+
+impl Default for Colors {
+    fn default() -> Colors {
+        Colors {
+            title: Color::Named(32),
+            separator: Color::Named(34),
+            label: Color::Named(33),
+            value: None,
+            logo: None,
+            fields: BTreeMap::new(),
+            thresholds: BTreeMap::new(),
+            bold: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composes_bold() {
+        let mut colors = Colors::default();
+        assert_eq!(colors.bold_escape(true), BOLD);
+        assert_eq!(colors.bold_escape(false), "");
+        colors.bold = false;
+        assert_eq!(colors.bold_escape(true), "");
+    }
+
+    #[test]
+    fn parses_named_colors() {
+        assert_eq!(Color::parse("red"), Ok(Color::Named(31)));
+        assert_eq!(Color::parse("Bright-Blue"), Ok(Color::Named(94)));
+        assert_eq!(Color::parse("bright white"), Ok(Color::Named(97)));
+        assert_eq!(Color::parse("purple"), Err("unknown color `purple`".to_string()));
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(Color::parse("#1793d1"), Ok(Color::Rgb(0x17, 0x93, 0xd1)));
+        assert_eq!(Color::parse("#FFF"), Ok(Color::Rgb(255, 255, 255)));
+        assert_eq!(Color::parse("#12345"), Err("invalid hex color `#12345`".to_string()));
+        assert_eq!(Color::parse("#zzzzzz"), Err("invalid hex color `#zzzzzz`".to_string()));
+    }
+
+    #[test]
+    fn parses_palette_indices() {
+        assert_eq!(Color::parse("208"), Ok(Color::Indexed(208)));
+        assert_eq!(Color::parse("0"), Ok(Color::Indexed(0)));
+        assert_eq!(Color::parse("256"), Err("unknown color `256`".to_string()));
+    }
+
+    #[test]
+    fn selects_escape_form() {
+        assert_eq!(Color::Named(31).escape(true), "\x1b[31m");
+        assert_eq!(Color::Indexed(208).escape(false), "\x1b[38;5;208m");
+        assert_eq!(Color::Rgb(23, 147, 209).escape(true), "\x1b[38;2;23;147;209m");
+        assert_eq!(Color::Rgb(255, 0, 0).escape(false), "\x1b[38;5;196m");
+    }
+
+    #[test]
+    fn color_test_covers_every_color() {
+        let out = color_test(true);
+        for (name, code) in NAMES {
+            assert!(out.contains(&format!("\x1b[{}m\x1b[7m    {} {}\n", code, RESET, name)), "missing {}", name);
+        }
+        for index in 0..=255u8 {
+            assert!(out.contains(&format!("\x1b[38;5;{}m", index)), "missing palette entry {}", index);
+        }
+        assert!(out.contains("\x1b[38;2;255;0;0m"));
+        // Without truecolor the gradient falls back to palette escapes
+        assert!(!color_test(false).contains("\x1b[38;2;"));
+    }
+
+    #[test]
+    fn converts_hues() {
+        assert_eq!(hue_to_rgb(0), (255, 0, 0));
+        assert_eq!(hue_to_rgb(120), (0, 255, 0));
+        assert_eq!(hue_to_rgb(240), (0, 0, 255));
+        assert_eq!(hue_to_rgb(30), (255, 127, 0));
+    }
+
+    #[test]
+    fn decides_whether_to_color() {
+        assert!(color_enabled(false, false, None, true));
+        assert!(!color_enabled(false, false, None, false));
+        assert!(!color_enabled(false, true, None, true));
+        assert!(!color_enabled(false, false, Some("1"), true));
+        // An empty NO_COLOR doesn't count
+        assert!(color_enabled(false, false, Some(""), true));
+        assert!(color_enabled(true, true, Some("1"), false));
+    }
+
+    #[test]
+    fn picks_threshold_colors() {
+        let thresholds = Thresholds {
+            colors: vec![Color::Named(32), Color::Named(33), Color::Named(31)],
+            limits: vec![50.0, 80.0],
+        };
+        assert_eq!(thresholds.color_for(10.0), Color::Named(32));
+        assert_eq!(thresholds.color_for(50.0), Color::Named(33));
+        assert_eq!(thresholds.color_for(79.9), Color::Named(33));
+        assert_eq!(thresholds.color_for(95.0), Color::Named(31));
+    }
+
+    #[test]
+    fn extracts_field_numbers() {
+        assert_eq!(field_number("6.70 GiB / 62.00 GiB (10%)"), Some(10.0));
+        assert_eq!(field_number("65.5°C"), Some(65.5));
+        assert_eq!(field_number("87% [On Battery]"), Some(87.0));
+        assert_eq!(field_number("unknown"), None);
+        assert_eq!(field_number("+52.0°C"), Some(52.0));
+        assert_eq!(field_number("v1.2.3 at 40%"), Some(40.0));
+        assert_eq!(field_number("1.2.3"), None);
+    }
+
+    #[test]
+    fn colors_values_per_field() {
+        let mut colors = Colors { value: Some(Color::Named(37)), ..Colors::default() };
+        colors.fields.insert("gpu".to_string(), Color::Named(35));
+        colors.thresholds.insert("battery".to_string(), Thresholds {
+            colors: vec![Color::Named(31), Color::Named(32)],
+            limits: vec![20.0],
+        });
+        assert_eq!(colors.value_for("GPU", "GPU A"), Some(Color::Named(35)));
+        assert_eq!(colors.value_for("Battery (BAT0)", " 15% [On Battery]"), Some(Color::Named(31)));
+        assert_eq!(colors.value_for("Battery (BAT0)", " 90% [AC Connected]"), Some(Color::Named(32)));
+        assert_eq!(colors.value_for("OS", "Arch Linux"), Some(Color::Named(37)));
+        assert_eq!(field_key("CPU Temp"), "cpu_temp");
+    }
+
+    #[test]
+    fn maps_rgb_to_nearest_palette_entry() {
+        assert_eq!(nearest_256(0, 0, 0), 16);
+        assert_eq!(nearest_256(255, 255, 255), 231);
+        assert_eq!(nearest_256(255, 0, 0), 196);
+        assert_eq!(nearest_256(0x17, 0x93, 0xd1), 32);
+        // Mid grays land on the grayscale ramp rather than the cube
+        assert_eq!(nearest_256(128, 128, 128), 244);
+        assert_eq!(nearest_256(18, 18, 18), 233);
+    }
+}
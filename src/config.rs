@@ -0,0 +1,1725 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::colors::{self, Color, Colors, Thresholds};
+use crate::image::ImageMode;
+use crate::logos::{LogoPosition, LogoType};
+use crate::modules;
+use crate::terminal::Background;
+use crate::themes;
+
+// A parsed config value. The config format is a small subset of TOML:
+// `key = value` pairs, `[table]` / `[dotted.table]` headers, `[[array]]`
+// tables, strings, integers, floats, booleans and arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+pub type Table = BTreeMap<String, Value>;
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::Boolean(_) => "boolean",
+            Value::Array(_) => "array",
+            Value::Table(_) => "table",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    // Columns added to (or, when negative, taken off) the title's width
+    pub separator_length: Option<i64>,
+    // Use systemd's PRETTY_HOSTNAME in the title when one is set
+    pub pretty_hostname: bool,
+    pub cpu_features: bool,
+    pub package_sizes: bool,
+    pub zram_ratio: bool,
+    // Between thousands in package counts: a literal string, or "locale"
+    pub thousands_separator: Option<String>,
+    pub display_details: bool,
+    pub display_ppi: bool,
+    // Add the EDID's preferred refresh rate to DRM display lines
+    pub display_preferred_refresh: bool,
+    // Add a "GPU (active)" line naming the GPU doing the rendering
+    pub gpu_active: bool,
+    pub gpu_displays: bool,
+    // GPUs whose names contain any of these (ignoring case) are left out;
+    // virtual and remote adapters by default
+    pub gpu_exclude: Vec<String>,
+    // When not empty, only GPUs whose names contain one of these are shown
+    pub gpu_include: Vec<String>,
+    // Append the kernel's preemption model, e.g. "(PREEMPT_RT)"
+    pub kernel_preemption: bool,
+    // hwmon driver the CPU temperature is read from, e.g. "coretemp"
+    pub cpu_temp_sensor: Option<String>,
+    // Name the window manager and its version (Hyprland, sway, i3, bspwm)
+    // instead of the session type
+    pub wm_version: bool,
+    // Flat display order from `modules = [...]`
+    pub modules: Option<Vec<String>>,
+    // Ordered groups from `[[group]]` tables; takes precedence over `modules`
+    pub groups: Vec<Vec<String>>,
+    // User-defined `[[module]]` entries backed by external commands
+    pub custom_modules: Vec<CustomModule>,
+    // Keep running the `[[module]]` commands under `--motd`
+    pub motd_custom_modules: bool,
+    // Default time limit for external commands, in milliseconds
+    pub command_timeout_ms: u64,
+    // Tries given to collectors prone to transient failures (local IP,
+    // package managers); 1 means no retrying
+    pub retry_attempts: usize,
+    pub uptime_format: UptimeFormat,
+    pub memory_format: MemoryFormat,
+    pub precision: Precision,
+    pub arch_format: ArchFormat,
+    pub colors: Colors,
+    // With `theme = "auto"`, the light palette (with `[colors]` applied too)
+    // that replaces `colors` if the terminal turns out to be light
+    pub auto_theme_light: Option<Colors>,
+    // `<module> = true/false` overrides of each module's default
+    pub module_toggles: BTreeMap<String, bool>,
+    // Kernel modules to call out by name on the kernel_modules line
+    pub kernel_modules_watch: Vec<String>,
+    // Spaces between the logo column and the info column
+    pub logo_gap: usize,
+    // Blank lines above the info column and the logo, for lining them up
+    pub info_padding_top: usize,
+    pub logo_padding_top: usize,
+    // Text printed above and below the fetch; may hold ANSI escapes and the
+    // logo's `${cN}` color markers
+    pub header: Option<String>,
+    pub footer: Option<String>,
+    pub logo_type: LogoType,
+    pub logo_position: LogoPosition,
+    // Picture drawn in the logo's place; only set from `--image`
+    pub image: Option<PathBuf>,
+    pub image_mode: ImageMode,
+    // Logo name or file to show instead of the detected one
+    pub logo: Option<String>,
+    // Kernel parameters to pick out of /proc/cmdline; empty shows all of it
+    pub cmdline_flags: Vec<String>,
+    // Longest the Cmdline value may get before it is cut off
+    pub cmdline_max_width: usize,
+    // Longest any value may get, in columns; None leaves values whole
+    pub max_value_width: Option<usize>,
+    // Keep the OS, host, CPU and GPU lines in a cache until the next boot
+    pub cache: bool,
+    // `--refresh-cache`: recompute the cached fields and rewrite the cache
+    pub refresh_cache: bool,
+    // Whether to emit color escapes at all
+    pub color: bool,
+    // Leave out lines whose value came back unknown
+    pub hide_unknown: bool,
+    // Forces truecolor on or off; None asks the terminal via COLORTERM
+    pub truecolor: Option<bool>,
+    // Battery percentage below which the battery line is highlighted
+    pub battery_warn_threshold: Option<f64>,
+    // Disk usage percentages above which disk lines turn yellow and red
+    pub disk_warn_threshold: Option<f64>,
+    pub disk_crit_threshold: Option<f64>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            separator_length: None,
+            pretty_hostname: false,
+            cpu_features: false,
+            package_sizes: false,
+            zram_ratio: false,
+            thousands_separator: None,
+            display_details: false,
+            display_ppi: false,
+            display_preferred_refresh: false,
+            gpu_active: false,
+            gpu_displays: false,
+            gpu_exclude: VIRTUAL_GPUS.iter().map(|name| name.to_string()).collect(),
+            gpu_include: Vec::new(),
+            kernel_preemption: false,
+            cpu_temp_sensor: None,
+            wm_version: false,
+            modules: None,
+            groups: Vec::new(),
+            custom_modules: Vec::new(),
+            motd_custom_modules: false,
+            command_timeout_ms: 1000,
+            retry_attempts: 1,
+            uptime_format: UptimeFormat::Verbose,
+            memory_format: MemoryFormat::Verbose,
+            precision: Precision::default(),
+            arch_format: ArchFormat::Raw,
+            colors: Colors::default(),
+            auto_theme_light: None,
+            module_toggles: BTreeMap::new(),
+            kernel_modules_watch: Vec::new(),
+            logo_gap: 3,
+            info_padding_top: 0,
+            logo_padding_top: 0,
+            header: None,
+            footer: None,
+            logo_type: LogoType::Os,
+            logo_position: LogoPosition::Left,
+            image: None,
+            image_mode: ImageMode::Blocks,
+            logo: None,
+            cmdline_flags: Vec::new(),
+            cmdline_max_width: 60,
+            max_value_width: None,
+            cache: false,
+            refresh_cache: false,
+            color: true,
+            truecolor: None,
+            hide_unknown: false,
+            battery_warn_threshold: None,
+            disk_warn_threshold: None,
+            disk_crit_threshold: None,
+        }
+    }
+}
+
+// A `[[module]]` whose value is the first line printed by `command`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomModule {
+    pub name: String,
+    pub command: String,
+    pub timeout_ms: Option<u64>,
+    pub on_error: OnError,
+}
+
+// What to show when a custom module's command fails or times out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnError {
+    Hide,
+    Unknown,
+}
+
+// "3 days, 2 hours, 5 mins" or "3d 2h 5m"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UptimeFormat {
+    Verbose,
+    Compact,
+}
+
+// "8.10 GiB / 16.00 GiB (50%)" or "8.1/16 GiB", for memory, swap and disk
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemoryFormat {
+    Verbose,
+    Short,
+}
+
+// How memory, swap and disk usage is written out: decimal places for the
+// sizes (0 to 3), and whether the percentage is rounded or cut down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Precision {
+    pub decimals: usize,
+    pub percentages: PercentRounding,
+}
+
+impl Default for Precision {
+    fn default() -> Precision {
+        Precision { decimals: 2, percentages: PercentRounding::Truncate }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PercentRounding {
+    Truncate,
+    Round,
+}
+
+// "x86_64"/"aarch64" (Rust's names) or "x64"/"arm64", on the OS line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchFormat {
+    Raw,
+    Common,
+}
+
+impl Config {
+    // Loads the user's config file, falling back to defaults when it is
+    // missing. Parse errors are reported on stderr rather than aborting.
+    // `theme` (from `--theme`) replaces the file's own `theme`, so its
+    // `[colors]` still apply on top. `profile` (from `--profile`) picks a
+    // `[profiles.<name>]` section to lay over the rest.
+    pub fn load(theme: Option<&str>, profile: Option<&str>) -> Config {
+        let mut config = Config::default();
+        let path = config_path();
+        let mut table = Table::new();
+        if let Some(path) = &path {
+            if let Ok(contents) = fs::read_to_string(path) {
+                match parse(&contents) {
+                    Ok(mut parsed) => {
+                        merge_platform_section(&mut parsed, env::consts::OS);
+                        table = parsed;
+                    }
+                    Err(err) => eprintln!("rustfetch: {}: {}", path.display(), err),
+                }
+            }
+        }
+        if let Err(err) = merge_profile(&mut table, profile, env::consts::OS) {
+            match &path {
+                Some(path) => eprintln!("rustfetch: {}: {}", path.display(), err),
+                None => eprintln!("rustfetch: {}", err),
+            }
+        }
+        if let Some(theme) = theme {
+            table.insert("theme".to_string(), Value::String(theme.to_string()));
+        }
+
+        if let Err(err) = config.apply(&table) {
+            match &path {
+                Some(path) => eprintln!("rustfetch: {}: {}", path.display(), err),
+                None => eprintln!("rustfetch: {}", err),
+            }
+        }
+        config
+    }
+
+    fn apply(&mut self, table: &Table) -> Result<(), String> {
+        // Custom modules go first so the module lists below can refer to them
+        if let Some(value) = table.get("module") {
+            self.custom_modules = expect_custom_modules(value)?;
+        }
+        // Likewise the theme, which `[colors]` then adjusts
+        if let Some(value) = table.get("theme") {
+            // The terminal is only asked which one "auto" means when the
+            // output is rendered (see `resolve_auto_theme`)
+            (self.colors, self.auto_theme_light) = match expect_string("theme", value)?.as_str() {
+                "auto" => (Colors::dark(), Some(Colors::light())),
+                name => (
+                    themes::preset(name).ok_or_else(|| {
+                        let names: Vec<String> = themes::names().map(|name| format!("\"{}\"", name)).collect();
+                        format!("`theme` must be \"auto\" or one of {}, found `{}`", names.join(", "), name)
+                    })?,
+                    None,
+                ),
+            };
+        }
+        
+        for (key, value) in table {
+            match key.as_str() {
+                "separator_length" => self.separator_length = Some(expect_integer(key, value)?),
+                "pretty_hostname" => self.pretty_hostname = expect_bool(key, value)?,
+                "cpu_features" => self.cpu_features = expect_bool(key, value)?,
+                "package_sizes" => self.package_sizes = expect_bool(key, value)?,
+                "zram_ratio" => self.zram_ratio = expect_bool(key, value)?,
+                "thousands_separator" => self.thousands_separator = Some(expect_string(key, value)?),
+                "display_details" => self.display_details = expect_bool(key, value)?,
+                "display_ppi" => self.display_ppi = expect_bool(key, value)?,
+                "display_preferred_refresh" => self.display_preferred_refresh = expect_bool(key, value)?,
+                "gpu_active" => self.gpu_active = expect_bool(key, value)?,
+                "gpu_displays" => self.gpu_displays = expect_bool(key, value)?,
+                "gpu_exclude" => self.gpu_exclude = expect_string_list(key, value)?,
+                "gpu_include" => self.gpu_include = expect_string_list(key, value)?,
+                "kernel_preemption" => self.kernel_preemption = expect_bool(key, value)?,
+                "cpu_temp_sensor" => self.cpu_temp_sensor = Some(expect_string(key, value)?),
+                "wm_version" => self.wm_version = expect_bool(key, value)?,
+                "modules" => self.modules = Some(self.expect_module_list(key, value)?),
+                "group" => self.groups = self.expect_groups(value)?,
+                "module" | "theme" => {}
+                "motd_custom_modules" => self.motd_custom_modules = expect_bool(key, value)?,
+                "command_timeout_ms" => self.command_timeout_ms = expect_usize(key, value)? as u64,
+                "retry_attempts" => {
+                    self.retry_attempts = expect_usize(key, value)?;
+                    if self.retry_attempts == 0 {
+                        return Err("`retry_attempts` must be at least 1".to_string());
+                    }
+                }
+                "uptime_format" => {
+                    self.uptime_format = match expect_string(key, value)?.as_str() {
+                        "verbose" => UptimeFormat::Verbose,
+                        "compact" => UptimeFormat::Compact,
+                        other => return Err(format!("`uptime_format` must be \"verbose\" or \"compact\", found `{}`", other)),
+                    };
+                }
+                "memory_format" => {
+                    self.memory_format = match expect_string(key, value)?.as_str() {
+                        "verbose" => MemoryFormat::Verbose,
+                        "short" => MemoryFormat::Short,
+                        other => return Err(format!("`memory_format` must be \"verbose\" or \"short\", found `{}`", other)),
+                    };
+                }
+                "precision" => {
+                    self.precision.decimals = expect_usize(key, value)?;
+                    if self.precision.decimals > 3 {
+                        return Err(format!("`precision` must be between 0 and 3, found {}", self.precision.decimals));
+                    }
+                }
+                "percent_rounding" => {
+                    self.precision.percentages = match expect_string(key, value)?.as_str() {
+                        "truncate" => PercentRounding::Truncate,
+                        "round" => PercentRounding::Round,
+                        other => return Err(format!("`percent_rounding` must be \"truncate\" or \"round\", found `{}`", other)),
+                    };
+                }
+                "arch_format" => {
+                    self.arch_format = match expect_string(key, value)?.as_str() {
+                        "raw" => ArchFormat::Raw,
+                        "common" => ArchFormat::Common,
+                        other => return Err(format!("`arch_format` must be \"raw\" or \"common\", found `{}`", other)),
+                    };
+                }
+                "colors" => self.apply_colors(value)?,
+                "kernel_modules_watch" => self.kernel_modules_watch = expect_string_list(key, value)?,
+                "logo_gap" => self.logo_gap = expect_usize(key, value)?,
+                "info_padding_top" => self.info_padding_top = expect_usize(key, value)?,
+                "logo_padding_top" => self.logo_padding_top = expect_usize(key, value)?,
+                "header" => self.header = Some(expect_string(key, value)?),
+                "footer" => self.footer = Some(expect_string(key, value)?),
+                "use_bold" => self.colors.bold = expect_bool(key, value)?,
+                "logo" => self.logo = Some(expect_string(key, value)?),
+                "logo_position" => {
+                    let position = expect_string(key, value)?;
+                    self.logo_position = LogoPosition::from_name(&position).ok_or_else(|| {
+                        format!("`logo_position` must be {}, found `{}`", LogoPosition::NAMES, position)
+                    })?;
+                }
+                "hide_unknown" => self.hide_unknown = expect_bool(key, value)?,
+                "battery_warn_threshold" => self.battery_warn_threshold = Some(expect_usize(key, value)? as f64),
+                "disk_warn_threshold" => self.disk_warn_threshold = Some(expect_usize(key, value)? as f64),
+                "disk_crit_threshold" => self.disk_crit_threshold = Some(expect_usize(key, value)? as f64),
+                "cmdline_flags" => self.cmdline_flags = expect_string_list(key, value)?,
+                "cmdline_max_width" => self.cmdline_max_width = expect_usize(key, value)?,
+                "cache" => self.cache = expect_bool(key, value)?,
+                "max_value_width" => self.max_value_width = Some(expect_usize(key, value)?),
+                name if modules::find(name).is_some() => {
+                    self.module_toggles.insert(name.to_string(), expect_bool(key, value)?);
+                }
+                _ => eprintln!("rustfetch: ignoring unknown config key `{}`", key),
+            }
+        }
+        Ok(())
+    }
+
+    // Settings for `--motd`: output that reads the same wherever the banner
+    // ends up being shown, and that doesn't wait on the network or run
+    // arbitrary commands at login
+    pub fn apply_motd_preset(&mut self) {
+        self.truecolor = Some(false);
+        self.module_toggles.insert("terminal_size".to_string(), false);
+        self.hide_network_modules();
+        if !self.motd_custom_modules {
+            self.hide_custom_modules();
+        }
+    }
+
+    // `--offline`: turns off every module that may wait on the network,
+    // custom ones included since there's no telling what their commands do
+    pub fn apply_offline(&mut self) {
+        self.hide_network_modules();
+        self.hide_custom_modules();
+    }
+
+    fn hide_network_modules(&mut self) {
+        for name in modules::NETWORK_MODULES {
+            self.module_toggles.insert(name.to_string(), false);
+        }
+    }
+
+    fn hide_custom_modules(&mut self) {
+        for module in &self.custom_modules {
+            self.module_toggles.insert(module.name.clone(), false);
+        }
+    }
+
+    // `--hide` / RUSTFETCH_DISABLE: turns off each of `modules` that
+    // exists, and hands back the names that don't
+    pub fn hide(&mut self, modules: &[String]) -> Vec<String> {
+        let mut unknown = Vec::new();
+        for name in modules {
+            if modules::find(name).is_none() && self.custom_module(name).is_none() {
+                unknown.push(name.clone());
+                continue;
+            }
+            self.module_toggles.insert(name.clone(), false);
+        }
+        unknown
+    }
+
+    // The modules to display, split into groups that are rendered with a
+    // blank line between them. Modules switched on with `<name> = true`
+    // that aren't already placed are appended to the last group.
+    pub fn layout(&self) -> Vec<Vec<String>> {
+        let mut layout = if !self.groups.is_empty() {
+            self.groups.clone()
+        } else if let Some(modules) = &self.modules {
+            vec![modules.clone()]
+        } else {
+            let mut layout = modules::default_layout();
+            layout[0].extend(self.custom_modules.iter().map(|module| module.name.clone()));
+            layout
+        };
+        
+        for group in &mut layout {
+            group.retain(|name| self.module_toggles.get(name) != Some(&false));
+        }
+        for module in modules::MODULES {
+            let enabled = self.module_toggles.get(module.name) == Some(&true);
+            let placed = layout.iter().flatten().any(|name| name == module.name);
+            if enabled && !placed {
+                if let Some(last) = layout.last_mut() {
+                    last.push(module.name.to_string());
+                }
+            }
+        }
+        layout
+    }
+
+    // The separator to group package counts with; none unless one is set
+    pub fn thousands_separator(&self) -> String {
+        match self.thousands_separator.as_deref() {
+            Some("locale") => {
+                let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+                    .iter()
+                    .find_map(|var| env::var(var).ok().filter(|value| !value.is_empty()));
+                locale_thousands_separator(locale.as_deref()).to_string()
+            }
+            Some(separator) => separator.to_string(),
+            None => String::new(),
+        }
+    }
+
+    // Whether a module will be displayed, so collectors for optional
+    // modules can skip work that won't be shown
+    pub fn is_shown(&self, module: &str) -> bool {
+        self.layout().iter().flatten().any(|name| name == module)
+    }
+
+    fn apply_colors(&mut self, value: &Value) -> Result<(), String> {
+        let Value::Table(table) = value else {
+            return Err(format!("`colors` must be a table, found {}", value.type_name()));
+        };
+        // Both of "auto"'s palettes get the same adjustments
+        let mut palettes: Vec<&mut Colors> = std::iter::once(&mut self.colors).chain(self.auto_theme_light.as_mut()).collect();
+        for (key, value) in table {
+            match key.as_str() {
+                "fields" => {
+                    let fields: BTreeMap<String, Color> = expect_table("colors.fields", value)?
+                        .iter()
+                        .map(|(field, value)| Ok((field.to_ascii_lowercase(), expect_color(&format!("fields.{}", field), value)?)))
+                        .collect::<Result<_, String>>()?;
+                    palettes.iter_mut().for_each(|colors| colors.fields = fields.clone());
+                    continue;
+                }
+                "thresholds" => {
+                    let thresholds: BTreeMap<String, Thresholds> = expect_table("colors.thresholds", value)?
+                        .iter()
+                        .map(|(field, value)| Ok((field.to_ascii_lowercase(), expect_thresholds(field, value)?)))
+                        .collect::<Result<_, String>>()?;
+                    palettes.iter_mut().for_each(|colors| colors.thresholds = thresholds.clone());
+                    continue;
+                }
+                _ => {}
+            }
+            let color = expect_color(key, value)?;
+            if !matches!(key.as_str(), "title" | "separator" | "label" | "value" | "logo") {
+                eprintln!("rustfetch: ignoring unknown color `{}`", key);
+            }
+            for colors in palettes.iter_mut() {
+                match key.as_str() {
+                    "title" => colors.title = color,
+                    "separator" => colors.separator = color,
+                    "label" => colors.label = color,
+                    "value" => colors.value = Some(color),
+                    "logo" => colors.logo = Some(color),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Settles `theme = "auto"`: the light palette if the terminal's
+    // background is light, else the dark one it started with
+    pub fn resolve_auto_theme(&mut self, background: Option<Background>) {
+        if let Some(light) = self.auto_theme_light.take() {
+            if background == Some(Background::Light) {
+                self.colors = light;
+            }
+        }
+    }
+
+    // Color for the whole battery line when the charge is low: yellow
+    // below `battery_warn_threshold`, red below half of it
+    pub fn battery_warning(&self, label: &str, value: &str) -> Option<Color> {
+        let threshold = self.battery_warn_threshold?;
+        if colors::field_key(label) != "battery" {
+            return None;
+        }
+        let percentage = colors::field_number(value)?;
+        if percentage < threshold / 2.0 {
+            Some(Color::Named(31))
+        } else if percentage < threshold {
+            Some(Color::Named(33))
+        } else {
+            None
+        }
+    }
+
+    // Color for a whole disk line that's filling up: red above
+    // `disk_crit_threshold`, yellow above `disk_warn_threshold`
+    pub fn disk_warning(&self, label: &str, value: &str) -> Option<Color> {
+        if colors::field_key(label) != "disk" {
+            return None;
+        }
+        let percentage = disk_percentage(value)?;
+        if self.disk_crit_threshold.is_some_and(|threshold| percentage > threshold) {
+            Some(Color::Named(31))
+        } else if self.disk_warn_threshold.is_some_and(|threshold| percentage > threshold) {
+            Some(Color::Named(33))
+        } else {
+            None
+        }
+    }
+
+    pub fn custom_module(&self, name: &str) -> Option<&CustomModule> {
+        self.custom_modules.iter().find(|module| module.name == name)
+    }
+
+    fn expect_module_list(&self, key: &str, value: &Value) -> Result<Vec<String>, String> {
+        let Value::Array(items) = value else {
+            return Err(format!("`{}` must be an array of module names, found {}", key, value.type_name()));
+        };
+        items.iter()
+            .map(|item| match item {
+                Value::String(name) if modules::find(name).is_some() || self.custom_module(name).is_some() => {
+                    Ok(name.clone())
+                }
+                Value::String(name) => Err(format!("unknown module `{}` in `{}`", name, key)),
+                other => Err(format!("`{}` must contain module names, found {}", key, other.type_name())),
+            })
+            .collect()
+    }
+
+    fn expect_groups(&self, value: &Value) -> Result<Vec<Vec<String>>, String> {
+        let Value::Array(groups) = value else {
+            return Err("`group` must be declared as [[group]] tables".to_string());
+        };
+        groups.iter()
+            .map(|group| {
+                let Value::Table(group) = group else {
+                    return Err("`group` must be declared as [[group]] tables".to_string());
+                };
+                // `name` is only there to make the config readable
+                for key in group.keys().filter(|key| *key != "name" && *key != "modules") {
+                    eprintln!("rustfetch: ignoring unknown group key `{}`", key);
+                }
+                match group.get("modules") {
+                    Some(list) => self.expect_module_list("group.modules", list),
+                    None => Err("each [[group]] needs a `modules` list".to_string()),
+                }
+            })
+            .collect()
+    }
+}
+
+// How full a disk line says the disk is: its percentage, or with
+// `memory_format = "short"` ("8.1/16 GiB") the ratio of the two sizes
+fn disk_percentage(value: &str) -> Option<f64> {
+    if value.contains('%') {
+        return colors::field_number(value);
+    }
+    let (used, rest) = value.trim().split_once('/')?;
+    let total = rest.split_whitespace().next()?;
+    let (used, total) = (used.parse::<f64>().ok()?, total.parse::<f64>().ok()?);
+    (total > 0.0).then(|| used / total * 100.0)
+}
+
+// Display adapters that don't stand for real hardware: Windows' fallback and
+// remote desktop drivers, and the emulated GPUs of common hypervisors
+const VIRTUAL_GPUS: &[&str] = &[
+    "Microsoft Basic Display Adapter",
+    "Microsoft Basic Render Driver",
+    "Microsoft Remote Display Adapter",
+    "Microsoft Hyper-V Video",
+    "VMware SVGA",
+    "VirtualBox Graphics Adapter",
+    "Red Hat QXL",
+    "Citrix Indirect Display",
+    "Parsec Virtual Display",
+];
+
+// Platforms that can have their own `[<os>]` section, by `env::consts::OS`
+const PLATFORM_SECTIONS: &[&str] = &["linux", "windows", "macos", "freebsd", "netbsd"];
+
+// Lays the `[<os>]` section matching this platform over the rest of the
+// config, and drops the sections for other platforms. Keys set in the
+// section win; tables like `[linux.colors]` are merged key by key.
+fn merge_platform_section(table: &mut Table, os: &str) {
+    let mut section = None;
+    for platform in PLATFORM_SECTIONS {
+        if let Some(value) = table.remove(*platform) {
+            if *platform == os {
+                section = Some(value);
+            }
+        }
+    }
+    match section {
+        Some(Value::Table(section)) => merge_layout(table, section),
+        Some(other) => eprintln!("rustfetch: `{}` must be a table, found {}", os, other.type_name()),
+        None => {}
+    }
+}
+
+// Lays the chosen `[profiles.<name>]` over the config (after the platform
+// section, so a profile wins over both) and drops the other profiles.
+// Profiles can have platform sections of their own, e.g.
+// `[profiles.full.windows]`.
+fn merge_profile(table: &mut Table, name: Option<&str>, os: &str) -> Result<(), String> {
+    let profiles = match table.remove("profiles") {
+        Some(Value::Table(profiles)) => profiles,
+        Some(other) => return Err(format!("`profiles` must be a table, found {}", other.type_name())),
+        None => Table::new(),
+    };
+    let Some(name) = name else {
+        return Ok(());
+    };
+    match profiles.get(name) {
+        Some(Value::Table(profile)) => {
+            let mut profile = profile.clone();
+            merge_platform_section(&mut profile, os);
+            merge_layout(table, profile);
+            Ok(())
+        }
+        Some(other) => Err(format!("`profiles.{}` must be a table, found {}", name, other.type_name())),
+        None => {
+            let names: Vec<String> = profiles.keys().map(|name| format!("`{}`", name)).collect();
+            if names.is_empty() {
+                Err(format!("no profile `{}`: the config defines no profiles", name))
+            } else {
+                Err(format!("no profile `{}`; the config defines {}", name, names.join(", ")))
+            }
+        }
+    }
+}
+
+// Merges a platform section or profile over the config. `[[group]]` tables
+// win over `modules`, so an override that sets only `modules` also drops
+// the groups below it; otherwise its list would be silently ignored.
+fn merge_layout(base: &mut Table, overrides: Table) {
+    if overrides.contains_key("modules") && !overrides.contains_key("group") {
+        base.remove("group");
+    }
+    merge_tables(base, overrides);
+}
+
+fn merge_tables(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge_tables(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+
+
+// Resolves where the config file lives on this platform:
+// - Linux/BSD: $XDG_CONFIG_HOME/rustfetch/config.toml, else ~/.config/rustfetch/config.toml
+// - macOS: ~/Library/Application Support/rustfetch/config.toml
+// - Windows: %APPDATA%\rustfetch\config.toml
+pub fn config_path() -> Option<PathBuf> {
+    let base = if cfg!(any(target_os = "macos", target_os = "windows")) {
+        dirs::config_dir()
+    } else {
+        xdg_config_dir()
+    };
+    base.map(|dir| dir.join("rustfetch").join("config.toml"))
+}
+
+// User logos live next to the config file, in `logos/<id>.txt`
+pub fn logos_dir() -> Option<PathBuf> {
+    config_path().and_then(|path| path.parent().map(|dir| dir.join("logos")))
+}
+
+// $XDG_CONFIG_HOME, for finding other programs' config files
+pub fn xdg_config_dir() -> Option<PathBuf> {
+    xdg_config_home(env::var_os("XDG_CONFIG_HOME"), dirs::home_dir())
+}
+
+// Per the XDG spec, relative or empty values of XDG_CONFIG_HOME are ignored
+fn xdg_config_home(xdg_config_home: Option<OsString>, home: Option<PathBuf>) -> Option<PathBuf> {
+    xdg_config_home
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| home.map(|home| home.join(".config")))
+}
+
+fn expect_integer(key: &str, value: &Value) -> Result<i64, String> {
+    match value {
+        Value::Integer(n) => Ok(*n),
+        _ => Err(format!("`{}` must be an integer, found {}", key, value.type_name())),
+    }
+}
+
+fn expect_usize(key: &str, value: &Value) -> Result<usize, String> {
+    match value {
+        Value::Integer(n) if *n >= 0 => Ok(*n as usize),
+        Value::Integer(_) => Err(format!("`{}` must not be negative", key)),
+        _ => Err(format!("`{}` must be an integer, found {}", key, value.type_name())),
+    }
+}
+
+fn expect_custom_modules(value: &Value) -> Result<Vec<CustomModule>, String> {
+    let Value::Array(entries) = value else {
+        return Err("`module` must be declared as [[module]] tables".to_string());
+    };
+    let mut custom_modules: Vec<CustomModule> = Vec::new();
+    
+    for entry in entries {
+        let Value::Table(entry) = entry else {
+            return Err("`module` must be declared as [[module]] tables".to_string());
+        };
+        let mut name = None;
+        let mut command = None;
+        let mut timeout_ms = None;
+        let mut on_error = OnError::Hide;
+        
+        for (key, value) in entry {
+            match key.as_str() {
+                "name" => name = Some(expect_string("module.name", value)?),
+                "command" => command = Some(expect_string("module.command", value)?),
+                "timeout_ms" => timeout_ms = Some(expect_usize("module.timeout_ms", value)? as u64),
+                "on_error" => {
+                    on_error = match expect_string("module.on_error", value)?.as_str() {
+                        "hide" => OnError::Hide,
+                        "unknown" => OnError::Unknown,
+                        other => return Err(format!("`module.on_error` must be \"hide\" or \"unknown\", found `{}`", other)),
+                    }
+                }
+                _ => eprintln!("rustfetch: ignoring unknown module key `{}`", key),
+            }
+        }
+        
+        let name = name.ok_or("each [[module]] needs a `name`")?;
+        let command = command.ok_or_else(|| format!("module `{}` needs a `command`", name))?;
+        if modules::find(&name).is_some() || custom_modules.iter().any(|module| module.name == name) {
+            return Err(format!("module name `{}` is already taken", name));
+        }
+        custom_modules.push(CustomModule { name, command, timeout_ms, on_error });
+    }
+    
+    Ok(custom_modules)
+}
+
+// Colors may be written as names, hex strings or bare palette indices
+fn expect_color(key: &str, value: &Value) -> Result<Color, String> {
+    match value {
+        Value::String(s) => Color::parse(s).map_err(|e| format!("`colors.{}`: {}", key, e)),
+        Value::Integer(n) => u8::try_from(*n)
+            .map(Color::Indexed)
+            .map_err(|_| format!("`colors.{}`: palette index must be 0-255", key)),
+        _ => Err(format!("`colors.{}` must be a color, found {}", key, value.type_name())),
+    }
+}
+
+fn expect_table<'a>(key: &str, value: &'a Value) -> Result<&'a Table, String> {
+    match value {
+        Value::Table(table) => Ok(table),
+        _ => Err(format!("`{}` must be a table, found {}", key, value.type_name())),
+    }
+}
+
+// Thresholds are written as colors separated by the numbers where the next
+// color takes over: ["green", 50, "yellow", 80, "red"]
+fn expect_thresholds(field: &str, value: &Value) -> Result<Thresholds, String> {
+    let invalid = || format!("`colors.thresholds.{}` must alternate colors and increasing numbers, like [\"green\", 50, \"red\"]", field);
+    let Value::Array(items) = value else {
+        return Err(invalid());
+    };
+    let mut thresholds = Thresholds { colors: Vec::new(), limits: Vec::new() };
+    for (index, item) in items.iter().enumerate() {
+        if index % 2 == 0 {
+            thresholds.colors.push(expect_color(&format!("thresholds.{}", field), item)?);
+        } else {
+            let limit = match item {
+                Value::Integer(n) => *n as f64,
+                Value::Float(n) => *n,
+                _ => return Err(invalid()),
+            };
+            if thresholds.limits.last().is_some_and(|&last| limit <= last) {
+                return Err(invalid());
+            }
+            thresholds.limits.push(limit);
+        }
+    }
+    // Must start and end with a color
+    if items.len() % 2 == 0 {
+        return Err(invalid());
+    }
+    Ok(thresholds)
+}
+
+fn expect_string_list(key: &str, value: &Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::Array(items) => items.iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s.clone()),
+                other => Err(format!("`{}` must contain strings, found {}", key, other.type_name())),
+            })
+            .collect(),
+        _ => Err(format!("`{}` must be an array of strings, found {}", key, value.type_name())),
+    }
+}
+
+fn expect_string(key: &str, value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        _ => Err(format!("`{}` must be a string, found {}", key, value.type_name())),
+    }
+}
+
+fn expect_bool(key: &str, value: &Value) -> Result<bool, String> {
+    match value {
+        Value::Boolean(b) => Ok(*b),
+        _ => Err(format!("`{}` must be a boolean, found {}", key, value.type_name())),
+    }
+}
+
+pub fn parse(contents: &str) -> Result<Table, String> {
+    let mut root = Table::new();
+    // Path of the table that bare keys are currently inserted into
+    let mut current: Vec<String> = Vec::new();
+    let mut lines = contents.lines().enumerate();
+
+    while let Some((index, raw_line)) = lines.next() {
+        let line_no = index + 1;
+        let mut line = strip_comment(raw_line).trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix("[[").and_then(|l| l.strip_suffix("]]")) {
+            let path = parse_key_path(header).map_err(|e| format!("line {}: {}", line_no, e))?;
+            push_array_table(&mut root, &path).map_err(|e| format!("line {}: {}", line_no, e))?;
+            current = path;
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let path = parse_key_path(header).map_err(|e| format!("line {}: {}", line_no, e))?;
+            table_at(&mut root, &path).map_err(|e| format!("line {}: {}", line_no, e))?;
+            current = path;
+            continue;
+        }
+
+        // Arrays may span several lines; keep reading until brackets balance
+        while !brackets_balanced(&line) {
+            match lines.next() {
+                Some((_, next)) => {
+                    line.push(' ');
+                    line.push_str(strip_comment(next).trim());
+                }
+                None => return Err(format!("line {}: unterminated array", line_no)),
+            }
+        }
+
+        let (key, value) = line.split_once('=')
+            .ok_or_else(|| format!("line {}: expected `key = value`", line_no))?;
+        let key = parse_key_path(key).map_err(|e| format!("line {}: {}", line_no, e))?;
+        let value = parse_value(value.trim()).map_err(|e| format!("line {}: {}", line_no, e))?;
+
+        let (last, parents) = key.split_last().expect("key paths are never empty");
+        let mut path = current.clone();
+        path.extend(parents.iter().cloned());
+        let table = table_at(&mut root, &path).map_err(|e| format!("line {}: {}", line_no, e))?;
+        if table.insert(last.clone(), value).is_some() {
+            return Err(format!("line {}: duplicate key `{}`", line_no, last));
+        }
+    }
+
+    Ok(root)
+}
+
+// Removes a trailing `# comment`, ignoring `#` inside strings
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+fn brackets_balanced(line: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut escaped = false;
+    for c in line.chars() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            },
+        }
+    }
+    depth <= 0
+}
+
+fn parse_key_path(key: &str) -> Result<Vec<String>, String> {
+    let parts: Vec<String> = key.split('.')
+        .map(|part| part.trim().trim_matches('"').to_string())
+        .collect();
+    let valid = |part: &String| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+    if parts.iter().all(valid) {
+        Ok(parts)
+    } else {
+        Err(format!("invalid key `{}`", key.trim()))
+    }
+}
+
+// Returns the table at `path`, creating intermediate tables as needed.
+// For arrays of tables, the most recently pushed table is used.
+fn table_at<'a>(root: &'a mut Table, path: &[String]) -> Result<&'a mut Table, String> {
+    let mut table = root;
+    for part in path {
+        let entry = table.entry(part.clone()).or_insert_with(|| Value::Table(Table::new()));
+        table = match entry {
+            Value::Table(inner) => inner,
+            Value::Array(items) => match items.last_mut() {
+                Some(Value::Table(inner)) => inner,
+                _ => return Err(format!("`{}` is not a table", part)),
+            },
+            _ => return Err(format!("`{}` is not a table", part)),
+        };
+    }
+    Ok(table)
+}
+
+fn push_array_table(root: &mut Table, path: &[String]) -> Result<(), String> {
+    let (last, parents) = path.split_last().expect("key paths are never empty");
+    let parent = table_at(root, parents)?;
+    match parent.entry(last.clone()).or_insert_with(|| Value::Array(Vec::new())) {
+        Value::Array(items) => {
+            items.push(Value::Table(Table::new()));
+            Ok(())
+        }
+        _ => Err(format!("`{}` is not an array of tables", last)),
+    }
+}
+
+fn parse_value(input: &str) -> Result<Value, String> {
+    let (value, rest) = parse_value_prefix(input)?;
+    if rest.trim().is_empty() {
+        Ok(value)
+    } else {
+        Err(format!("unexpected trailing characters `{}`", rest.trim()))
+    }
+}
+
+// Parses one value from the start of `input`, returning the remainder
+fn parse_value_prefix(input: &str) -> Result<(Value, &str), String> {
+    let input = input.trim_start();
+
+    if let Some(rest) = input.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Ok((Value::String(value), &rest[i + 1..])),
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('e') => value.push('\x1b'),
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let ch = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("invalid unicode escape `\\u{}`", hex))?;
+                        value.push(ch);
+                    }
+                    other => return Err(format!("invalid escape `\\{}`", other.unwrap_or(' '))),
+                },
+                c => value.push(c),
+            }
+        }
+        return Err("unterminated string".to_string());
+    }
+
+    if let Some(rest) = input.strip_prefix('\'') {
+        let end = rest.find('\'').ok_or("unterminated string")?;
+        return Ok((Value::String(rest[..end].to_string()), &rest[end + 1..]));
+    }
+
+    if let Some(mut rest) = input.strip_prefix('[') {
+        let mut items = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(']') {
+                return Ok((Value::Array(items), after));
+            }
+            let (item, after) = parse_value_prefix(rest)?;
+            items.push(item);
+            rest = after.trim_start();
+            if let Some(after) = rest.strip_prefix(',') {
+                rest = after;
+            } else if !rest.starts_with(']') {
+                return Err("expected `,` or `]` in array".to_string());
+            }
+        }
+    }
+
+    let end = input.find([',', ']']).unwrap_or(input.len());
+    let (token, rest) = input.split_at(end);
+    let token = token.trim();
+    let value = match token {
+        "true" => Value::Boolean(true),
+        "false" => Value::Boolean(false),
+        _ => {
+            let number = token.replace('_', "");
+            if let Ok(n) = number.parse::<i64>() {
+                Value::Integer(n)
+            } else if let Ok(n) = number.parse::<f64>() {
+                Value::Float(n)
+            } else {
+                return Err(format!("invalid value `{}`", token));
+            }
+        }
+    };
+    Ok((value, rest))
+}
+
+// The usual digit grouping for a locale like "de_DE.UTF-8", by language;
+// English-style commas for anything unlisted (and for C/POSIX)
+fn locale_thousands_separator(locale: Option<&str>) -> &'static str {
+    let language = locale.unwrap_or("").split(['_', '.', '@']).next().unwrap_or("");
+    match language {
+        "de" | "nl" | "it" | "es" | "pt" | "da" | "id" | "tr" | "el" => ".",
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "uk" | "hu" => " ",
+        _ => ",",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scalars() {
+        let table = parse(
+            "# comment\n\
+             name = \"rust # fetch\"  # trailing comment\n\
+             literal = 'C:\\path'\n\
+             count = 1_000\n\
+             ratio = 0.5\n\
+             enabled = true\n",
+        ).unwrap();
+        assert_eq!(table["name"], Value::String("rust # fetch".to_string()));
+        assert_eq!(table["literal"], Value::String("C:\\path".to_string()));
+        assert_eq!(table["count"], Value::Integer(1000));
+        assert_eq!(table["ratio"], Value::Float(0.5));
+        assert_eq!(table["enabled"], Value::Boolean(true));
+    }
+
+    #[test]
+    fn parses_string_escapes() {
+        let table = parse(r#"text = "a\tb\"c\u00e9\e[0m""#).unwrap();
+        assert_eq!(table["text"], Value::String("a\tb\"cé\x1b[0m".to_string()));
+    }
+
+    #[test]
+    fn parses_arrays() {
+        let table = parse("list = [\"a\", \"b\",\n  \"c\", # comment\n]\nnested = [[1, 2], []]").unwrap();
+        assert_eq!(
+            table["list"],
+            Value::Array(vec![
+                Value::String("a".to_string()),
+                Value::String("b".to_string()),
+                Value::String("c".to_string()),
+            ])
+        );
+        assert_eq!(
+            table["nested"],
+            Value::Array(vec![
+                Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+                Value::Array(vec![]),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_tables() {
+        let table = parse(
+            "top = 1\n\
+             [outer]\n\
+             a = 2\n\
+             [outer.inner]\n\
+             b = 3\n\
+             [[item]]\n\
+             name = \"one\"\n\
+             [[item]]\n\
+             name = \"two\"\n",
+        ).unwrap();
+        assert_eq!(table["top"], Value::Integer(1));
+        let Value::Table(outer) = &table["outer"] else { panic!("expected table") };
+        assert_eq!(outer["a"], Value::Integer(2));
+        let Value::Table(inner) = &outer["inner"] else { panic!("expected table") };
+        assert_eq!(inner["b"], Value::Integer(3));
+        let Value::Array(items) = &table["item"] else { panic!("expected array") };
+        assert_eq!(items.len(), 2);
+        let Value::Table(second) = &items[1] else { panic!("expected table") };
+        assert_eq!(second["name"], Value::String("two".to_string()));
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        assert_eq!(parse("a = 1\nb").unwrap_err(), "line 2: expected `key = value`");
+        assert_eq!(parse("a = \"open").unwrap_err(), "line 1: unterminated string");
+        assert_eq!(parse("a = 1\na = 2").unwrap_err(), "line 2: duplicate key `a`");
+        assert_eq!(parse("a = nope").unwrap_err(), "line 1: invalid value `nope`");
+        assert_eq!(parse("a = [1, 2").unwrap_err(), "line 1: unterminated array");
+    }
+
+    #[test]
+    fn resolves_xdg_config_home() {
+        let home = Some(PathBuf::from("/home/user"));
+        assert_eq!(
+            xdg_config_home(Some("/tmp/xdg".into()), home.clone()),
+            Some(PathBuf::from("/tmp/xdg"))
+        );
+        assert_eq!(xdg_config_home(None, home.clone()), Some(PathBuf::from("/home/user/.config")));
+        assert_eq!(xdg_config_home(Some("".into()), home.clone()), Some(PathBuf::from("/home/user/.config")));
+        assert_eq!(xdg_config_home(Some("relative".into()), home), Some(PathBuf::from("/home/user/.config")));
+        assert_eq!(xdg_config_home(None, None), None);
+    }
+
+    #[test]
+    fn applies_logo_position() {
+        let mut config = Config::default();
+        assert_eq!(config.logo_position, LogoPosition::Left);
+        config.apply(&parse("logo_position = \"top\"").unwrap()).unwrap();
+        assert_eq!(config.logo_position, LogoPosition::Top);
+        
+        let err = config.apply(&parse("logo_position = \"middle\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`logo_position` must be `left`, `right`, `top` or `none`, found `middle`");
+    }
+
+    #[test]
+    fn applies_retry_attempts() {
+        let mut config = Config::default();
+        assert_eq!(config.retry_attempts, 1);
+        config.apply(&parse("retry_attempts = 3").unwrap()).unwrap();
+        assert_eq!(config.retry_attempts, 3);
+        assert_eq!(config.apply(&parse("retry_attempts = 0").unwrap()).unwrap_err(), "`retry_attempts` must be at least 1");
+    }
+
+    #[test]
+    fn merges_profiles() {
+        let contents = "\
+            modules = [\"os\", \"cpu\", \"memory\"]\n\
+            logo_gap = 2\n\
+            [colors]\n\
+            title = \"red\"\n\
+            [profiles.minimal]\n\
+            modules = [\"os\"]\n\
+            logo_position = \"none\"\n\
+            [profiles.minimal.colors]\n\
+            label = \"green\"\n\
+            [profiles.minimal.windows]\n\
+            logo_gap = 8\n\
+            [profiles.full]\n\
+            cpu_cache = true\n";
+        let mut table = parse(contents).unwrap();
+        merge_profile(&mut table, Some("minimal"), "linux").unwrap();
+        let mut config = Config::default();
+        config.apply(&table).unwrap();
+        assert_eq!(config.modules, Some(vec!["os".to_string()]));
+        assert_eq!(config.logo_position, LogoPosition::None);
+        assert_eq!(config.logo_gap, 2);
+        assert_eq!(config.colors.title, Color::Named(31));
+        assert_eq!(config.colors.label, Color::Named(32));
+        
+        let mut table = parse(contents).unwrap();
+        merge_profile(&mut table, Some("minimal"), "windows").unwrap();
+        assert_eq!(table.get("logo_gap"), Some(&Value::Integer(8)));
+        
+        // Without --profile, profiles are simply left out
+        let mut table = parse(contents).unwrap();
+        merge_profile(&mut table, None, "linux").unwrap();
+        assert!(!table.contains_key("profiles"));
+        let mut config = Config::default();
+        config.apply(&table).unwrap();
+        assert_eq!(config.modules, Some(vec!["os".to_string(), "cpu".to_string(), "memory".to_string()]));
+        
+        let mut table = parse(contents).unwrap();
+        let err = merge_profile(&mut table, Some("tiny"), "linux").unwrap_err();
+        assert_eq!(err, "no profile `tiny`; the config defines `full`, `minimal`");
+        let err = merge_profile(&mut Table::new(), Some("tiny"), "linux").unwrap_err();
+        assert_eq!(err, "no profile `tiny`: the config defines no profiles");
+        
+        // A profile's `modules` replaces the base's `[[group]]` layout too
+        let contents = "\
+            [[group]]\n\
+            modules = [\"os\", \"cpu\"]\n\
+            [[group]]\n\
+            modules = [\"memory\"]\n\
+            [profiles.minimal]\n\
+            modules = [\"os\"]\n";
+        let mut table = parse(contents).unwrap();
+        merge_profile(&mut table, Some("minimal"), "linux").unwrap();
+        let mut config = Config::default();
+        config.apply(&table).unwrap();
+        assert!(config.groups.is_empty());
+        assert_eq!(config.modules, Some(vec!["os".to_string()]));
+    }
+
+    #[test]
+    fn merges_platform_sections() {
+        let contents = "\
+            modules = [\"os\", \"cpu\"]\n\
+            logo_gap = 2\n\
+            [colors]\n\
+            title = \"red\"\n\
+            label = \"blue\"\n\
+            [linux]\n\
+            modules = [\"os\", \"kernel\"]\n\
+            [linux.colors]\n\
+            label = \"green\"\n\
+            [windows]\n\
+            logo_gap = 8\n";
+        let mut table = parse(contents).unwrap();
+        merge_platform_section(&mut table, "linux");
+        let mut config = Config::default();
+        config.apply(&table).unwrap();
+        assert_eq!(config.modules, Some(vec!["os".to_string(), "kernel".to_string()]));
+        assert_eq!(config.logo_gap, 2);
+        assert_eq!(config.colors.title, Color::Named(31));
+        assert_eq!(config.colors.label, Color::Named(32));
+        
+        let mut table = parse(contents).unwrap();
+        merge_platform_section(&mut table, "macos");
+        assert!(!table.contains_key("linux") && !table.contains_key("windows"));
+        let mut config = Config::default();
+        config.apply(&table).unwrap();
+        assert_eq!(config.modules, Some(vec!["os".to_string(), "cpu".to_string()]));
+    }
+
+    #[test]
+    fn applies_max_value_width() {
+        let mut config = Config::default();
+        assert_eq!(config.max_value_width, None);
+        config.apply(&parse("max_value_width = 40").unwrap()).unwrap();
+        assert_eq!(config.max_value_width, Some(40));
+    }
+
+    #[test]
+    fn applies_use_bold() {
+        let mut config = Config::default();
+        assert!(config.colors.bold);
+        // Holds whichever way round it and `theme` are written
+        config.apply(&parse("use_bold = false\ntheme = \"nord\"").unwrap()).unwrap();
+        assert!(!config.colors.bold);
+    }
+
+    #[test]
+    fn applies_logo() {
+        let mut config = Config::default();
+        assert_eq!(config.logo, None);
+        config.apply(&parse("logo = \"debian\"").unwrap()).unwrap();
+        assert_eq!(config.logo.as_deref(), Some("debian"));
+        assert_eq!(config.apply(&parse("logo = 3").unwrap()).unwrap_err(), "`logo` must be a string, found integer");
+    }
+
+    #[test]
+    fn applies_logo_gap() {
+        let mut config = Config::default();
+        assert_eq!(config.logo_gap, 3);
+        config.apply(&parse("logo_gap = 0").unwrap()).unwrap();
+        assert_eq!(config.logo_gap, 0);
+
+        let err = config.apply(&parse("logo_gap = -2").unwrap()).unwrap_err();
+        assert_eq!(err, "`logo_gap` must not be negative");
+    }
+
+    #[test]
+    fn applies_padding_top() {
+        let mut config = Config::default();
+        assert_eq!((config.info_padding_top, config.logo_padding_top), (0, 0));
+        config.apply(&parse("info_padding_top = 2\nlogo_padding_top = 1").unwrap()).unwrap();
+        assert_eq!((config.info_padding_top, config.logo_padding_top), (2, 1));
+    }
+
+    #[test]
+    fn applies_cmdline_options() {
+        let mut config = Config::default();
+        config.apply(&parse("cmdline_flags = [\"quiet\", \"mitigations\"]\ncmdline_max_width = 40").unwrap()).unwrap();
+        assert_eq!(config.cmdline_flags, ["quiet", "mitigations"]);
+        assert_eq!(config.cmdline_max_width, 40);
+
+        let err = config.apply(&parse("cmdline_flags = \"quiet\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`cmdline_flags` must be an array of strings, found string");
+    }
+
+    #[test]
+    fn applies_gpu_filters() {
+        let mut config = Config::default();
+        assert!(config.gpu_exclude.iter().any(|name| name == "Microsoft Basic Display Adapter"));
+        assert!(config.gpu_include.is_empty());
+        config.apply(&parse("gpu_exclude = []\ngpu_include = [\"NVIDIA\"]").unwrap()).unwrap();
+        assert!(config.gpu_exclude.is_empty());
+        assert_eq!(config.gpu_include, ["NVIDIA"]);
+    }
+
+    #[test]
+    fn applies_motd_preset() {
+        let mut config = Config::default();
+        config.apply(&parse("terminal_size = true").unwrap()).unwrap();
+        config.apply_motd_preset();
+        assert_eq!(config.truecolor, Some(false));
+        assert!(!config.is_shown("terminal_size"));
+        assert!(!config.is_shown("local_ip"));
+        assert!(config.is_shown("os"));
+
+        let custom = "[[module]]\nname = \"Weather\"\ncommand = \"curl -s wttr.in\"";
+        let mut config = Config::default();
+        config.apply(&parse(custom).unwrap()).unwrap();
+        config.apply_motd_preset();
+        assert!(!config.is_shown("Weather"));
+        let mut config = Config::default();
+        config.apply(&parse(&format!("motd_custom_modules = true\n{}", custom)).unwrap()).unwrap();
+        config.apply_motd_preset();
+        assert!(config.is_shown("Weather"));
+    }
+
+    #[test]
+    fn applies_offline() {
+        let mut config = Config::default();
+        config.apply(&parse("updates = true").unwrap()).unwrap();
+        assert!(config.is_shown("updates"));
+        config.apply_offline();
+        assert!(!config.is_shown("updates"));
+        assert!(!config.is_shown("local_ip"));
+        assert!(config.is_shown("os"));
+
+        let mut config = Config::default();
+        config.apply(&parse("[[module]]\nname = \"Weather\"\ncommand = \"curl -s wttr.in\"").unwrap()).unwrap();
+        assert!(config.is_shown("Weather"));
+        config.apply_offline();
+        assert!(!config.is_shown("Weather"));
+    }
+
+    #[test]
+    fn applies_separator_length() {
+        let mut config = Config::default();
+        config.apply(&parse("separator_length = 12").unwrap()).unwrap();
+        assert_eq!(config.separator_length, Some(12));
+        config.apply(&parse("separator_length = -4").unwrap()).unwrap();
+        assert_eq!(config.separator_length, Some(-4));
+
+        let err = config.apply(&parse("separator_length = \"long\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`separator_length` must be an integer, found string");
+    }
+
+    #[test]
+    fn resolves_layout() {
+        let mut config = Config::default();
+        assert_eq!(config.layout(), modules::default_layout());
+
+        config.apply(&parse("modules = [\"os\", \"cpu\"]").unwrap()).unwrap();
+        assert_eq!(config.layout(), [vec!["os", "cpu"]]);
+
+        config.apply(&parse(
+            "[[group]]\n\
+             name = \"Software\"\n\
+             modules = [\"os\", \"kernel\", \"shell\"]\n\
+             [[group]]\n\
+             name = \"Hardware\"\n\
+             modules = [\"cpu\", \"gpu\", \"memory\"]\n",
+        ).unwrap()).unwrap();
+        assert_eq!(config.layout(), [vec!["os", "kernel", "shell"], vec!["cpu", "gpu", "memory"]]);
+    }
+
+    #[test]
+    fn toggles_modules() {
+        let mut config = Config::default();
+        assert!(!config.is_shown("kernel_modules"));
+        config.apply(&parse("kernel_modules = true\ngpu = false").unwrap()).unwrap();
+        assert!(config.is_shown("kernel_modules"));
+        assert!(!config.is_shown("gpu"));
+        assert_eq!(config.layout()[0].last().map(String::as_str), Some("kernel_modules"));
+
+        // Explicit lists keep their order; disabled entries are dropped and
+        // enabled optional modules are appended to the last group
+        config.apply(&parse("modules = [\"os\", \"gpu\", \"cpu\"]").unwrap()).unwrap();
+        assert_eq!(config.layout(), [vec!["os", "cpu", "kernel_modules"]]);
+
+        let err = config.apply(&parse("gpu = \"no\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`gpu` must be a boolean, found string");
+    }
+
+    #[test]
+    fn rejects_unknown_modules() {
+        let mut config = Config::default();
+        let err = config.apply(&parse("modules = [\"os\", \"weather\"]").unwrap()).unwrap_err();
+        assert_eq!(err, "unknown module `weather` in `modules`");
+
+        let err = config.apply(&parse("[[group]]\nname = \"Empty\"").unwrap()).unwrap_err();
+        assert_eq!(err, "each [[group]] needs a `modules` list");
+    }
+
+    #[test]
+    fn applies_custom_modules() {
+        let mut config = Config::default();
+        config.apply(&parse(
+            "command_timeout_ms = 500\n\
+             modules = [\"os\", \"Weather\"]\n\
+             [[module]]\n\
+             name = \"Weather\"\n\
+             command = \"curl -s wttr.in?format=3\"\n\
+             on_error = \"unknown\"\n\
+             [[module]]\n\
+             name = \"Todo\"\n\
+             command = \"todo count\"\n\
+             timeout_ms = 100\n",
+        ).unwrap()).unwrap();
+
+        assert_eq!(config.command_timeout_ms, 500);
+        assert_eq!(config.layout(), [vec!["os", "Weather"]]);
+        assert_eq!(
+            config.custom_module("Weather"),
+            Some(&CustomModule {
+                name: "Weather".to_string(),
+                command: "curl -s wttr.in?format=3".to_string(),
+                timeout_ms: None,
+                on_error: OnError::Unknown,
+            })
+        );
+        let todo = config.custom_module("Todo").unwrap();
+        assert_eq!((todo.timeout_ms, todo.on_error), (Some(100), OnError::Hide));
+    }
+
+    #[test]
+    fn appends_custom_modules_to_default_layout() {
+        let mut config = Config::default();
+        config.apply(&parse("[[module]]\nname = \"Weather\"\ncommand = \"true\"").unwrap()).unwrap();
+        assert_eq!(config.layout()[0].last().map(String::as_str), Some("Weather"));
+    }
+
+    #[test]
+    fn rejects_invalid_custom_modules() {
+        let mut config = Config::default();
+        let err = config.apply(&parse("[[module]]\nname = \"cpu\"\ncommand = \"true\"").unwrap()).unwrap_err();
+        assert_eq!(err, "module name `cpu` is already taken");
+
+        let err = config.apply(&parse("[[module]]\nname = \"Weather\"").unwrap()).unwrap_err();
+        assert_eq!(err, "module `Weather` needs a `command`");
+
+        let err = config.apply(&parse(
+            "[[module]]\nname = \"Weather\"\ncommand = \"true\"\non_error = \"panic\"",
+        ).unwrap()).unwrap_err();
+        assert_eq!(err, "`module.on_error` must be \"hide\" or \"unknown\", found `panic`");
+    }
+
+    #[test]
+    fn applies_colors() {
+        let mut config = Config::default();
+        config.apply(&parse(
+            "[colors]\n\
+             title = \"bright_magenta\"\n\
+             label = 208\n\
+             value = \"#ebdbb2\"\n\
+             logo = \"75\"\n",
+        ).unwrap()).unwrap();
+        assert_eq!(config.colors.title, Color::Named(95));
+        assert_eq!(config.colors.label, Color::Indexed(208));
+        assert_eq!(config.colors.value, Some(Color::Rgb(0xeb, 0xdb, 0xb2)));
+        assert_eq!(config.colors.logo, Some(Color::Indexed(75)));
+        assert_eq!(config.colors.separator, Colors::default().separator);
+
+        let err = config.apply(&parse("[colors]\nlabel = 300").unwrap()).unwrap_err();
+        assert_eq!(err, "`colors.label`: palette index must be 0-255");
+        let err = config.apply(&parse("[colors]\nlabel = \"mauve\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`colors.label`: unknown color `mauve`");
+    }
+
+    #[test]
+    fn applies_field_colors() {
+        let mut config = Config::default();
+        config.apply(&parse(
+            "[colors.fields]\n\
+             GPU = \"magenta\"\n\
+             [colors.thresholds]\n\
+             memory = [\"green\", 50, \"yellow\", 80.5, \"red\"]\n",
+        ).unwrap()).unwrap();
+        assert_eq!(config.colors.fields.get("gpu"), Some(&Color::Named(35)));
+        assert_eq!(config.colors.thresholds.get("memory"), Some(&Thresholds {
+            colors: vec![Color::Named(32), Color::Named(33), Color::Named(31)],
+            limits: vec![50.0, 80.5],
+        }));
+
+        let expected = "`colors.thresholds.memory` must alternate colors and increasing numbers, like [\"green\", 50, \"red\"]";
+        for bad in ["[\"green\", 50]", "[\"green\", 80, \"yellow\", 50, \"red\"]", "[\"green\", \"red\"]", "\"red\""] {
+            let err = config.apply(&parse(&format!("[colors.thresholds]\nmemory = {}", bad)).unwrap()).unwrap_err();
+            assert_eq!(err, expected, "{}", bad);
+        }
+        let err = config.apply(&parse("[colors.fields]\ngpu = \"mauve\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`colors.fields.gpu`: unknown color `mauve`");
+    }
+
+    #[test]
+    fn warns_about_low_battery() {
+        let mut config = Config::default();
+        assert_eq!(config.battery_warning("Battery (BAT0)", " 5% [On Battery]"), None);
+
+        config.apply(&parse("battery_warn_threshold = 20").unwrap()).unwrap();
+        assert_eq!(config.battery_warning("Battery (BAT0)", " 5% [On Battery]"), Some(Color::Named(31)));
+        assert_eq!(config.battery_warning("Battery (BAT0)", " 15% [On Battery]"), Some(Color::Named(33)));
+        assert_eq!(config.battery_warning("Battery (BAT0)", " 20% [AC Connected]"), None);
+        assert_eq!(config.battery_warning("Memory", " 1 GiB / 8 GiB (5%)"), None);
+    }
+
+    #[test]
+    fn warns_about_full_disks() {
+        let mut config = Config::default();
+        assert_eq!(config.disk_warning("Disk (/)", " 95.00 GiB / 100.00 GiB (95%) - ext4"), None);
+
+        config.apply(&parse("disk_warn_threshold = 80\ndisk_crit_threshold = 90").unwrap()).unwrap();
+        assert_eq!(config.disk_warning("Disk (/)", " 95.00 GiB / 100.00 GiB (95%) - ext4"), Some(Color::Named(31)));
+        assert_eq!(config.disk_warning("Disk (C:)", " 85.00 GiB / 100.00 GiB (85%) - NTFS"), Some(Color::Named(33)));
+        assert_eq!(config.disk_warning("Disk (/)", " 80.00 GiB / 100.00 GiB (80%) - ext4"), None);
+        assert_eq!(config.disk_warning("Disk (/home)", " 460/500 GiB - btrfs"), Some(Color::Named(31)));
+        assert_eq!(config.disk_warning("Disk (D:)", " disabled"), None);
+        assert_eq!(config.disk_warning("Memory", " 15 GiB / 16 GiB (94%)"), None);
+    }
+
+    #[test]
+    fn applies_memory_format() {
+        let mut config = Config::default();
+        assert_eq!(config.memory_format, MemoryFormat::Verbose);
+        config.apply(&parse("memory_format = \"short\"").unwrap()).unwrap();
+        assert_eq!(config.memory_format, MemoryFormat::Short);
+        
+        let err = config.apply(&parse("memory_format = \"tiny\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`memory_format` must be \"verbose\" or \"short\", found `tiny`");
+    }
+
+    #[test]
+    fn applies_precision() {
+        let mut config = Config::default();
+        assert_eq!(config.precision, Precision { decimals: 2, percentages: PercentRounding::Truncate });
+        config.apply(&parse("precision = 0\npercent_rounding = \"round\"").unwrap()).unwrap();
+        assert_eq!(config.precision, Precision { decimals: 0, percentages: PercentRounding::Round });
+
+        let err = config.apply(&parse("precision = 4").unwrap()).unwrap_err();
+        assert_eq!(err, "`precision` must be between 0 and 3, found 4");
+        let err = config.apply(&parse("percent_rounding = \"floor\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`percent_rounding` must be \"truncate\" or \"round\", found `floor`");
+    }
+
+    #[test]
+    fn applies_arch_format() {
+        let mut config = Config::default();
+        assert_eq!(config.arch_format, ArchFormat::Raw);
+        config.apply(&parse("arch_format = \"common\"").unwrap()).unwrap();
+        assert_eq!(config.arch_format, ArchFormat::Common);
+        
+        let err = config.apply(&parse("arch_format = \"x64\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`arch_format` must be \"raw\" or \"common\", found `x64`");
+    }
+
+    #[test]
+    fn applies_uptime_format() {
+        let mut config = Config::default();
+        assert_eq!(config.uptime_format, UptimeFormat::Verbose);
+        config.apply(&parse("uptime_format = \"compact\"").unwrap()).unwrap();
+        assert_eq!(config.uptime_format, UptimeFormat::Compact);
+
+        let err = config.apply(&parse("uptime_format = \"short\"").unwrap()).unwrap_err();
+        assert_eq!(err, "`uptime_format` must be \"verbose\" or \"compact\", found `short`");
+    }
+
+    #[test]
+    fn applies_theme() {
+        let mut config = Config::default();
+        config.apply(&parse("theme = \"light\"").unwrap()).unwrap();
+        assert_eq!(config.colors, Colors::light());
+
+        // [colors] adjusts the theme regardless of key order
+        config.apply(&parse("theme = \"dark\"\n[colors]\nlabel = \"red\"").unwrap()).unwrap();
+        assert_eq!(config.colors, Colors { label: Color::Named(31), ..Colors::dark() });
+
+        // "auto" keeps the dark palette unless the terminal says it's light
+        let auto = parse("theme = \"auto\"\n[colors]\nlabel = \"red\"").unwrap();
+        for (background, expected) in [
+            (Some(Background::Light), Colors::light()),
+            (Some(Background::Dark), Colors::dark()),
+            (None, Colors::dark()),
+        ] {
+            let mut config = Config::default();
+            config.apply(&auto).unwrap();
+            config.resolve_auto_theme(background);
+            assert_eq!(config.colors, Colors { label: Color::Named(31), ..expected });
+            assert_eq!(config.auto_theme_light, None);
+        }
+
+        // Presets can be adjusted the same way
+        config.apply(&parse("theme = \"nord\"\n[colors]\nlabel = \"red\"").unwrap()).unwrap();
+        assert_eq!(config.colors.label, Color::Named(31));
+        assert_eq!(config.colors.title, themes::preset("nord").unwrap().title);
+        
+        let err = config.apply(&parse("theme = \"solarized\"").unwrap()).unwrap_err();
+        assert_eq!(
+            err,
+            "`theme` must be \"auto\" or one of \"dark\", \"light\", \"gruvbox\", \"nord\", \"dracula\", \"monochrome\", found `solarized`"
+        );
+    }
+
+    #[test]
+    fn picks_thousands_separators() {
+        let mut config = Config::default();
+        assert_eq!(config.thousands_separator(), "");
+        config.apply(&parse("thousands_separator = \"'\"").unwrap()).unwrap();
+        assert_eq!(config.thousands_separator(), "'");
+        
+        assert_eq!(locale_thousands_separator(Some("en_US.UTF-8")), ",");
+        assert_eq!(locale_thousands_separator(Some("de_DE.UTF-8")), ".");
+        assert_eq!(locale_thousands_separator(Some("fr_FR@euro")), " ");
+        assert_eq!(locale_thousands_separator(Some("C")), ",");
+        assert_eq!(locale_thousands_separator(None), ",");
+    }
+
+    #[test]
+    fn applies_cpu_features() {
+        let mut config = Config::default();
+        assert!(!config.cpu_features);
+        config.apply(&parse("cpu_features = true").unwrap()).unwrap();
+        assert!(config.cpu_features);
+
+        let err = config.apply(&parse("cpu_features = 1").unwrap()).unwrap_err();
+        assert_eq!(err, "`cpu_features` must be a boolean, found integer");
+    }
+}
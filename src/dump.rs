@@ -0,0 +1,82 @@
+// `--dump-raw`: records what the collectors read (each command's output
+// and each text file's contents, in the order they were read) so a "wrong
+// value" report can show exactly what a parser was given. Recording is off
+// unless `enable` was called, and then costs nothing but a flag check.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RECORDS: Mutex<Vec<Record>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    // "command: uname -r", "file: /proc/loadavg", ...
+    pub source: String,
+    // What came back, or why nothing did
+    pub raw: String,
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+// Notes one read. `source` is only built while recording.
+pub fn record(source: impl FnOnce() -> String, raw: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let record = Record { source: source(), raw: raw.to_string() };
+    RECORDS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(record);
+}
+
+// Everything recorded so far, oldest first
+pub fn records() -> Vec<Record> {
+    RECORDS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+}
+
+// The dump: every raw read under a `==> <source>` heading, then what each
+// module made of them
+pub fn format(records: &[Record], modules: &[(String, Vec<String>)]) -> String {
+    let mut out = format!("rustfetch {} raw dump\n", env!("CARGO_PKG_VERSION"));
+    for record in records {
+        out.push_str(&format!("\n==> {}\n", record.source));
+        if !record.raw.is_empty() {
+            out.push_str(record.raw.trim_end_matches('\n'));
+            out.push('\n');
+        }
+    }
+    for (module, lines) in modules {
+        out.push_str(&format!("\n==> module: {}\n", module));
+        for line in lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        if lines.is_empty() {
+            out.push_str("(nothing shown)\n");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_dumps() {
+        let records = [
+            Record { source: "command: uname -r".to_string(), raw: "6.9.7-arch1-1\n".to_string() },
+            Record { source: "file: /etc/timezone".to_string(), raw: "(failed: No such file or directory)".to_string() },
+            Record { source: "command: true".to_string(), raw: String::new() },
+        ];
+        let modules = [
+            ("kernel".to_string(), vec!["Kernel: 6.9.7-arch1-1".to_string()]),
+            ("timezone".to_string(), Vec::new()),
+        ];
+        let dump = format(&records, &modules);
+        assert!(dump.starts_with(&format!("rustfetch {} raw dump\n\n==> command: uname -r\n6.9.7-arch1-1\n\n", env!("CARGO_PKG_VERSION"))));
+        assert!(dump.contains("\n==> file: /etc/timezone\n(failed: No such file or directory)\n\n==> command: true\n\n"));
+        assert!(dump.ends_with("\n==> module: kernel\nKernel: 6.9.7-arch1-1\n\n==> module: timezone\n(nothing shown)\n"));
+    }
+}
@@ -0,0 +1,235 @@
+// Images in place of the ASCII logo (`--image`), drawn with half blocks:
+// each cell is a "▀" whose foreground is one pixel and background the one
+// below it, so any truecolor terminal can show them without a graphics
+// protocol. Decoding is behind the `ppm` feature and reads only netpbm
+// (PPM) files, which any image tool can write (`magick logo.png logo.ppm`).
+
+#[cfg(feature = "ppm")]
+use std::fs;
+#[cfg(feature = "ppm")]
+use std::path::Path;
+
+// How `--image` is drawn; half blocks are the only way so far
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageMode {
+    Blocks,
+}
+
+impl ImageMode {
+    pub const NAMES: &'static str = "`blocks`";
+
+    pub fn from_name(name: &str) -> Option<ImageMode> {
+        match name {
+            "blocks" => Some(ImageMode::Blocks),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    // Row by row, `width * height` of them
+    pixels: Vec<[u8; 3]>,
+}
+
+#[cfg(feature = "ppm")]
+pub fn load(path: &Path) -> Result<Image, String> {
+    let bytes = fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    parse_ppm(&bytes).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+#[cfg(not(feature = "ppm"))]
+pub fn load(_path: &std::path::Path) -> Result<Image, String> {
+    Err("--image needs rustfetch built with the `ppm` feature".to_string())
+}
+
+// Binary (P6) or plain (P3) PPM. Samples over 8 bits are scaled down.
+#[cfg_attr(not(feature = "ppm"), allow(dead_code))]
+fn parse_ppm(bytes: &[u8]) -> Result<Image, String> {
+    let mut pos = 0;
+    // Whitespace-separated header fields; `#` starts a comment to the end
+    // of the line
+    let token = |pos: &mut usize| -> Option<String> {
+        loop {
+            match bytes.get(*pos)? {
+                b'#' => {
+                    while bytes.get(*pos).is_some_and(|&b| b != b'\n') {
+                        *pos += 1;
+                    }
+                }
+                b if b.is_ascii_whitespace() => *pos += 1,
+                _ => break,
+            }
+        }
+        let start = *pos;
+        while bytes.get(*pos).is_some_and(|b| !b.is_ascii_whitespace() && *b != b'#') {
+            *pos += 1;
+        }
+        Some(String::from_utf8_lossy(&bytes[start..*pos]).into_owned())
+    };
+    let magic = token(&mut pos).ok_or("empty file")?;
+    if magic != "P6" && magic != "P3" {
+        return Err("not a PPM image (convert it with e.g. `magick logo.png logo.ppm`)".to_string());
+    }
+    let number = |pos: &mut usize, what: &str| -> Result<usize, String> {
+        token(pos)
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| format!("bad {} in the PPM header", what))
+    };
+    let width = number(&mut pos, "width")?;
+    let height = number(&mut pos, "height")?;
+    let max = number(&mut pos, "maximum value")?;
+    if width == 0 || height == 0 || !(1..=65535).contains(&max) {
+        return Err("bad size in the PPM header".to_string());
+    }
+    let count = width.checked_mul(height).ok_or("image too large")?;
+    let needed = count.checked_mul(3).ok_or("image too large")?;
+    let scale = |sample: usize| (sample.min(max) * 255 / max) as u8;
+    let cut_short = || "image data is cut short".to_string();
+
+    let pixels = if magic == "P6" {
+        // Exactly one whitespace byte separates the header from the data,
+        // with one byte per sample up to a maximum of 255 and two above it
+        let data = bytes.get(pos + 1..).unwrap_or(&[]);
+        let sample_bytes = if max < 256 { 1 } else { 2 };
+        if data.len() / sample_bytes < needed {
+            return Err(cut_short());
+        }
+        if sample_bytes == 1 {
+            data[..needed].chunks_exact(3)
+                .map(|rgb| [scale(rgb[0] as usize), scale(rgb[1] as usize), scale(rgb[2] as usize)])
+                .collect()
+        } else {
+            data[..needed * 2].chunks_exact(6)
+                .map(|rgb| {
+                    let sample = |i: usize| u16::from_be_bytes([rgb[i * 2], rgb[i * 2 + 1]]) as usize;
+                    [scale(sample(0)), scale(sample(1)), scale(sample(2))]
+                })
+                .collect()
+        }
+    } else {
+        let mut samples = Vec::new();
+        while let Some(token) = token(&mut pos).filter(|token| !token.is_empty()) {
+            samples.push(token.parse().map_err(|_| format!("bad sample `{}`", token))?);
+        }
+        if samples.len() < needed {
+            return Err(cut_short());
+        }
+        samples.chunks_exact(3)
+            .take(count)
+            .map(|rgb| [scale(rgb[0]), scale(rgb[1]), scale(rgb[2])])
+            .collect()
+    };
+    Ok(Image { width, height, pixels })
+}
+
+// Shrinks (or grows) the image to fit `columns` by `rows` cells, keeping its
+// shape, and draws it two pixel rows per line
+pub fn render_blocks(image: &Image, columns: usize, rows: usize) -> Vec<String> {
+    let (width, height) = fit(image.width, image.height, columns, rows * 2);
+    let resized = resize(image, width, height);
+    let pixel = |x: usize, y: usize| resized[y * width + x];
+
+    (0..height.div_ceil(2))
+        .map(|row| {
+            let mut line = String::new();
+            for x in 0..width {
+                let [r, g, b] = pixel(x, row * 2);
+                line.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+                match (row * 2 + 1 < height).then(|| pixel(x, row * 2 + 1)) {
+                    Some([r, g, b]) => line.push_str(&format!("\x1b[48;2;{};{};{}m▀", r, g, b)),
+                    // An odd last row leaves the lower halves to the terminal
+                    None => line.push_str("\x1b[49m▀"),
+                }
+            }
+            line.push_str("\x1b[0m");
+            line
+        })
+        .collect()
+}
+
+// The largest size with the image's aspect ratio that fits the box, at
+// least one pixel each way
+fn fit(width: usize, height: usize, max_width: usize, max_height: usize) -> (usize, usize) {
+    let (max_width, max_height) = (max_width.max(1), max_height.max(1));
+    if width * max_height > height * max_width {
+        (max_width, (height * max_width / width).max(1))
+    } else {
+        ((width * max_height / height).max(1), max_height)
+    }
+}
+
+// Averages the source pixels under each target pixel (or picks the nearest
+// one when growing)
+fn resize(image: &Image, width: usize, height: usize) -> Vec<[u8; 3]> {
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let (top, bottom) = (y * image.height / height, ((y + 1) * image.height).div_ceil(height));
+        for x in 0..width {
+            let (left, right) = (x * image.width / width, ((x + 1) * image.width).div_ceil(width));
+            let mut sum = [0usize; 3];
+            for source_y in top..bottom.max(top + 1) {
+                for source_x in left..right.max(left + 1) {
+                    let pixel = image.pixels[source_y * image.width + source_x];
+                    for channel in 0..3 {
+                        sum[channel] += pixel[channel] as usize;
+                    }
+                }
+            }
+            let count = (bottom.max(top + 1) - top) * (right.max(left + 1) - left);
+            pixels.push(sum.map(|total| (total / count) as u8));
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ppm() {
+        let plain = b"P3\n# a comment\n2 1\n255\n255 0 0  0 0 255\n";
+        assert_eq!(
+            parse_ppm(plain),
+            Ok(Image { width: 2, height: 1, pixels: vec![[255, 0, 0], [0, 0, 255]] })
+        );
+        let mut binary = b"P6 1 2 15\n".to_vec();
+        binary.extend_from_slice(&[15, 0, 0, 0, 15, 0]);
+        assert_eq!(
+            parse_ppm(&binary),
+            Ok(Image { width: 1, height: 2, pixels: vec![[255, 0, 0], [0, 255, 0]] })
+        );
+        let mut wide = b"P6 1 1 65535\n".to_vec();
+        wide.extend_from_slice(&[0xff, 0xff, 0x00, 0x00, 0x80, 0x00]);
+        assert_eq!(parse_ppm(&wide), Ok(Image { width: 1, height: 1, pixels: vec![[255, 0, 127]] }));
+        assert_eq!(parse_ppm(b"P6 1 1 65535\n\0\0\0"), Err("image data is cut short".to_string()));
+        assert!(parse_ppm(b"\x89PNG\r\n").is_err());
+        assert_eq!(parse_ppm(b"P6 2 2 255\n\0\0\0"), Err("image data is cut short".to_string()));
+        assert_eq!(parse_ppm(b"P3 0 2 255\n"), Err("bad size in the PPM header".to_string()));
+        // Width times height fits in a usize, but not times three samples
+        assert_eq!(parse_ppm(b"P6 6148914691236517206 1 255\n\0\0\0"), Err("image too large".to_string()));
+    }
+
+    #[test]
+    fn renders_half_blocks() {
+        let image = Image { width: 1, height: 2, pixels: vec![[255, 0, 0], [0, 0, 255]] };
+        assert_eq!(render_blocks(&image, 1, 1), ["\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m▀\x1b[0m"]);
+        let image = Image { width: 1, height: 1, pixels: vec![[1, 2, 3]] };
+        assert_eq!(render_blocks(&image, 1, 1), ["\x1b[38;2;1;2;3m\x1b[49m▀\x1b[0m"]);
+    }
+
+    #[test]
+    fn fits_and_resizes() {
+        assert_eq!(fit(100, 100, 40, 40), (40, 40));
+        assert_eq!(fit(200, 100, 40, 40), (40, 20));
+        assert_eq!(fit(100, 200, 40, 40), (20, 40));
+        assert_eq!(fit(1000, 1, 10, 10), (10, 1));
+
+        let image = Image { width: 2, height: 2, pixels: vec![[0, 0, 0], [100, 100, 100], [200, 200, 200], [100, 100, 100]] };
+        assert_eq!(resize(&image, 1, 1), [[100, 100, 100]]);
+        assert_eq!(resize(&image, 4, 4).len(), 16);
+    }
+}
@@ -0,0 +1,5770 @@
+// rustfetch's implementation; src/main.rs just calls `run`. The parsers and
+// collectors are public so the benchmarks in benches/ can drive them.
+
+mod cache;
+mod cli;
+mod colors;
+mod config;
+mod dump;
+mod image;
+mod logos;
+#[cfg(any(test, debug_assertions))]
+mod mock;
+mod modules;
+mod terminal;
+#[cfg(test)]
+mod test_dir;
+mod text;
+mod themes;
+
+use std::env;
+use std::fs;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{mpsc, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cache::StaticFields;
+use cli::Args;
+use colors::{Color, RESET};
+use config::{Config, OnError};
+pub use config::{ArchFormat, MemoryFormat, PercentRounding, Precision, UptimeFormat};
+use image::ImageMode;
+use logos::{Logo, LogoPosition, LogoType};
+
+#[derive(Default)]
+struct SystemInfo {
+    username: String,
+    hostname: String,
+    os: String,
+    host: String,
+    kernel: String,
+    uptime: String,
+    // 1, 5 and 15 minute load averages, "0.52, 0.61, 0.48"
+    load: Option<String>,
+    packages: String,
+    shell: String,
+    display: Vec<String>,
+    de: String,
+    wm: String,
+    wm_theme: String,
+    icons: String,
+    font: String,
+    cursor: String,
+    terminal: String,
+    cpu: String,
+    gpu: Vec<String>,
+    active_gpu: Option<String>,
+    // "GPU 1 (NVIDIA): DP-1, HDMI-A-1", one per DRM card
+    gpu_displays: Vec<String>,
+    gpu_clock: Option<String>,
+    // VRAM in use and in total, in bytes
+    gpu_vram_used: Option<(u64, u64)>,
+    cpu_cache: Option<String>,
+    // Degrees Celsius
+    cpu_temp: Option<f64>,
+    memory: String,
+    swap: String,
+    disk: Vec<String>,
+    local_ip: String,
+    // Active VPN interfaces; None when they can't be listed
+    vpn: Option<Vec<String>>,
+    // Branch of the repository around the working directory, with " *" when
+    // it has uncommitted changes
+    git: Option<String>,
+    battery: String,
+    locale: String,
+    timezone: Option<String>,
+    kernel_modules: Option<String>,
+    terminal_size: Option<String>,
+    terminal_theme: Option<terminal::Background>,
+    display_count: Option<usize>,
+    audio_device: Option<String>,
+    secure_boot: Option<bool>,
+    boot_mode: Option<&'static str>,
+    root_fs: Option<String>,
+    power_source: Option<&'static str>,
+    install_date: Option<String>,
+    updates: Option<usize>,
+    tpm: Option<String>,
+    cmdline: Option<String>,
+    tearing: Option<bool>,
+    // Workspace count and the active workspace's name
+    workspaces: Option<(usize, String)>,
+    // Logged-in sessions
+    users: Option<usize>,
+    // "us (dvorak), de"
+    keyboard_layout: Option<String>,
+    // vm.swappiness, and vm.vfs_cache_pressure when it's readable
+    swappiness: Option<(u32, Option<u32>)>,
+    // Number of memory slots, and the size in bytes of each installed module
+    ram_slots: Option<(usize, Vec<u64>)>,
+    // Output of each `[[module]]` command, keyed by module name; `None` when
+    // the command failed or timed out
+    custom: HashMap<String, Option<String>>,
+}
+
+pub fn run() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("rustfetch: {}", err);
+        process::exit(2);
+    });
+    
+    if args.help {
+        let path = config::config_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "(no config directory found)".to_string());
+        print!("{}", cli::usage(&path));
+        return;
+    }
+    
+    if args.print_config_path {
+        match config::config_path() {
+            Some(path) => println!("{}", path.display()),
+            None => {
+                eprintln!("rustfetch: could not determine the config directory");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    if args.list_modules {
+        print!("{}", modules::list());
+        return;
+    }
+    
+    if args.list_sensors {
+        let sensors = read_sensors(Path::new(HWMON_DIR));
+        if sensors.is_empty() {
+            eprintln!("rustfetch: no hwmon temperature sensors found");
+            process::exit(1);
+        }
+        print!("{}", format_sensor_list(&sensors));
+        return;
+    }
+    
+    if args.color_test {
+        print!("{}", colors::color_test(colors::supports_truecolor()));
+        return;
+    }
+    
+    if args.check {
+        // The defaults rather than the user's config, so results compare
+        // across machines
+        let config = Config::default();
+        let info = gather_system_info(&config);
+        if let Some(note) = proc_missing_note(&info, &config, Path::new("/proc")) {
+            eprintln!("rustfetch: {}", note);
+        }
+        let layout = modules::default_layout();
+        let modules: Vec<&str> = layout.iter().flatten().map(String::as_str).collect();
+        let unknown = unknown_modules(&info, &config, &modules);
+        for module in &unknown {
+            println!("unknown: {}", module);
+        }
+        println!("{} of {} modules unknown", unknown.len(), modules.len());
+        process::exit(unknown.len().min(255) as i32);
+    }
+    
+    if args.check_update {
+        if args.offline {
+            eprintln!("rustfetch: --check-update needs the network, which --offline rules out");
+            process::exit(1);
+        }
+        let current = env!("CARGO_PKG_VERSION");
+        match latest_version() {
+            Some(latest) if version_is_newer(&latest, current) => {
+                println!("rustfetch {} is available (you have {})", latest, current);
+            }
+            Some(_) => println!("rustfetch {} is up to date", current),
+            None => {
+                eprintln!("rustfetch: could not reach crates.io to check for updates");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    let mut config = Config::load(args.theme.as_deref(), args.profile.as_deref());
+    if args.separator_length.is_some() {
+        config.separator_length = args.separator_length;
+    }
+    if let Some(logo_type) = args.logo_type {
+        config.logo_type = logo_type;
+    }
+    if let Some(logo) = logo_override(args.logo, env::var("RUSTFETCH_LOGO").ok()) {
+        config.logo = Some(logo);
+    }
+    if let Some(logo_position) = args.logo_position {
+        config.logo_position = logo_position;
+    }
+    if args.image.is_some() {
+        config.image = args.image;
+    }
+    if let Some(image_mode) = args.image_mode {
+        config.image_mode = image_mode;
+    }
+    if args.hide_unknown {
+        config.hide_unknown = true;
+    }
+    config.refresh_cache = args.refresh_cache;
+    // A stale name in the environment shouldn't break every run, unlike a
+    // typo on the command line
+    for name in config.hide(&disabled_modules(env::var("RUSTFETCH_DISABLE").ok())) {
+        eprintln!("rustfetch: ignoring unknown module `{}` in RUSTFETCH_DISABLE", name);
+    }
+    if let Some(name) = config.hide(&args.hide).first() {
+        eprintln!("rustfetch: unknown module `{}` in --hide", name);
+        process::exit(2);
+    }
+    if args.motd {
+        config.apply_motd_preset();
+    }
+    if args.offline {
+        config.apply_offline();
+    }
+    config.color = colors::color_enabled(
+        args.force_color,
+        args.no_color,
+        env::var("NO_COLOR").ok().as_deref(),
+        std::io::stdout().is_terminal(),
+    );
+    
+    let mock = args.mock || env::var("RUSTFETCH_MOCK").is_ok_and(|value| !value.is_empty() && value != "0");
+    if args.markdown {
+        let dump = markdown_dump_config(&config);
+        let info = collect_info(&dump, mock);
+        print!("{}", render_markdown(&info, &config, &dump));
+        return;
+    }
+    
+    if args.dump_raw.is_some() {
+        dump::enable();
+        // Cached fields would skip the reads the dump is for
+        config.cache = false;
+    }
+    let info = collect_info(&config, mock);
+    if let Some(note) = proc_missing_note(&info, &config, Path::new("/proc")).filter(|_| !mock) {
+        eprintln!("rustfetch: {}", note);
+        dump::record(|| "note".to_string(), &note);
+    }
+    // Only worth asking the terminal about its background when colors
+    // actually go to it
+    if config.color && config.auto_theme_light.is_some() && std::io::stdout().is_terminal() {
+        config.resolve_auto_theme(terminal::background());
+    }
+    display_info(&info, &config);
+    if let Some(target) = args.dump_raw {
+        write_raw_dump(&info, &config, target.as_deref());
+    }
+}
+
+// `--dump-raw`: the recorded reads and every displayed module's lines, to
+// `path` or stderr
+fn write_raw_dump(info: &SystemInfo, config: &Config, path: Option<&Path>) {
+    let modules: Vec<(String, Vec<String>)> = config.layout()
+        .into_iter()
+        .flatten()
+        .map(|module| {
+            let lines = module_lines(info, config, &module);
+            (module, lines)
+        })
+        .collect();
+    let dump = dump::format(&dump::records(), &modules);
+    match path {
+        Some(path) => {
+            if let Err(err) = fs::write(path, dump) {
+                eprintln!("rustfetch: could not write the dump to {}: {}", path.display(), err);
+                process::exit(1);
+            }
+        }
+        None => eprint!("{}", dump),
+    }
+}
+
+// The real system's info, or with `mock` (debug builds only) the canned
+// machine from `mock::system_info`
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+fn collect_info(config: &Config, mock: bool) -> SystemInfo {
+    #[cfg(debug_assertions)]
+    if mock {
+        return mock::system_info();
+    }
+    gather_system_info(config)
+}
+
+// The config behind `--markdown`'s full dump: every cheap module, after the
+// user's own. Network and costly ones only come along if the user has them on.
+fn markdown_dump_config(config: &Config) -> Config {
+    let mut modules: Vec<String> = config.layout().into_iter().flatten().collect();
+    for module in modules::MODULES {
+        let cheap = !modules::NETWORK_MODULES.contains(&module.name) && !modules::COSTLY_MODULES.contains(&module.name);
+        let wanted = cheap || config.is_shown(module.name);
+        if wanted && !modules.iter().any(|name| name == module.name) {
+            modules.push(module.name.to_string());
+        }
+    }
+    Config { modules: Some(modules), groups: Vec::new(), ..config.clone() }
+}
+
+// `--markdown`: the usual info as a code block, without colors or logo, then
+// every line of `dump`'s layout in a collapsed <details>
+fn render_markdown(info: &SystemInfo, config: &Config, dump: &Config) -> String {
+    let mut out = format!("```text\n{}\n", title(&info.username, &info.hostname));
+    for (index, group) in config.layout().iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        for module in group {
+            for line in module_lines(info, config, module) {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+    }
+    out.push_str("```\n\n<details>\n<summary>All modules</summary>\n\n```text\n");
+    for module in dump.layout().iter().flatten() {
+        for line in module_lines(info, dump, module) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out.push_str("```\n\n</details>\n");
+    out
+}
+
+// How long `--check-update` waits for crates.io
+const UPDATE_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The newest stable release on crates.io, fetched with curl (which Windows
+// 10 and later ship too). crates.io turns away requests without a
+// User-Agent.
+fn latest_version() -> Option<String> {
+    let mut command = Command::new("curl");
+    command.args([
+        "--silent",
+        "--fail",
+        "--user-agent",
+        concat!("rustfetch/", env!("CARGO_PKG_VERSION"), " (", env!("CARGO_PKG_REPOSITORY"), ")"),
+        concat!("https://crates.io/api/v1/crates/", env!("CARGO_PKG_NAME")),
+    ]);
+    let response = command_with_timeout(command, UPDATE_CHECK_TIMEOUT)?;
+    json_value(&response, "max_stable_version").and_then(json_string)
+}
+
+// Whether `latest` is a later "X.Y.Z" than `current`; unparseable versions
+// never count as newer
+fn version_is_newer(latest: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        version.split('.').map(|part| part.parse().ok()).collect()
+    };
+    match (parse(latest), parse(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+// Modules among `modules` whose collectors fell back to a placeholder:
+// nothing at all, or only "unknown"/"Unknown ..." values
+fn unknown_modules<'a>(info: &SystemInfo, config: &Config, modules: &[&'a str]) -> Vec<&'a str> {
+    modules.iter()
+        .copied()
+        .filter(|module| {
+            let lines = module_lines(info, config, module);
+            lines.iter().all(|line| is_unknown_line(line))
+        })
+        .collect()
+}
+
+// Whether a line's value is only a placeholder: nothing, "unknown", or an
+// "Unknown ..." fallback like "Unknown GPU"
+fn is_unknown_line(line: &str) -> bool {
+    let value = line.split_once(": ").map_or(line, |(_, value)| value).trim();
+    value.is_empty() || value.eq_ignore_ascii_case("unknown") || value.starts_with("Unknown")
+}
+
+// `--logo` beats RUSTFETCH_LOGO, which beats the config file's `logo`. An
+// empty variable counts as unset, so dotfiles can clear it with `=`.
+fn logo_override(flag: Option<String>, env_var: Option<String>) -> Option<String> {
+    flag.or(env_var.filter(|logo| !logo.trim().is_empty()))
+}
+
+// The modules listed in RUSTFETCH_DISABLE ("gpu,uptime")
+fn disabled_modules(env_var: Option<String>) -> Vec<String> {
+    env_var.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// PowerShell executables in order of preference: PowerShell 7 installs as
+// `pwsh`, while Windows PowerShell 5.1 is `powershell` (and may be disabled)
+const POWERSHELL_EXECUTABLES: &[&str] = &["pwsh", "powershell"];
+
+// The PowerShell executable that last launched successfully; `Some(None)`
+// once every candidate has failed to start
+static POWERSHELL: OnceLock<Option<&'static str>> = OnceLock::new();
+
+// Helper function to execute PowerShell commands on Windows
+fn powershell_command(command: &str) -> Option<String> {
+    if !cfg!(target_os = "windows") {
+        return None;
+    }
+    
+    // Windows PowerShell writes in the console's code page (OEM 437, 850,
+    // 1252, ...), which turns localized names into mojibake; ask for UTF-8
+    let script = format!("[Console]::OutputEncoding = [System.Text.Encoding]::UTF8; {}", command);
+    let run = |executable: &str| {
+        Command::new(executable)
+            .args(["-NoProfile", "-Command", &script])
+            .output()
+    };
+    
+    let output = match POWERSHELL.get() {
+        Some(executable) => run((*executable)?).ok()?,
+        None => {
+            // Probe with the real command so the first call costs nothing extra
+            let found = POWERSHELL_EXECUTABLES.iter()
+                .find_map(|executable| run(executable).ok().map(|output| (*executable, output)));
+            let _ = POWERSHELL.set(found.as_ref().map(|(executable, _)| *executable));
+            found?.1
+        }
+    };
+    
+    let decoded = decode_powershell_output(&output.stdout);
+    dump::record(|| format!("powershell: {}", command), &decoded);
+    Some(decoded.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+// PowerShell's output as text: UTF-8, minus the byte order mark Windows
+// PowerShell puts in front once the encoding is switched, or UTF-16LE when
+// it comes with that BOM instead (redirected output on some hosts)
+fn decode_powershell_output(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xff, 0xfe]) {
+        let units: Vec<u16> = utf16.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let text = String::from_utf8_lossy(bytes);
+    text.strip_prefix('\u{feff}').unwrap_or(&text).to_string()
+}
+
+// Runs a command, giving up (and killing it) if it doesn't finish in time.
+// Output is read on a separate thread so a full pipe can't stall the wait.
+fn command_with_timeout(command: Command, timeout: Duration) -> Option<String> {
+    command_status_with_timeout(command, timeout)
+        .and_then(|(code, output)| (code == Some(0)).then_some(output))
+}
+
+// Like `command_with_timeout`, but hands back the exit code (None when a
+// signal ended the command) and the output of failed runs too, for tools
+// whose exit code means something other than failure
+fn command_status_with_timeout(mut command: Command, timeout: Duration) -> Option<(Option<i32>, String)> {
+    // Taken up front, since spawning borrows the command
+    let command_line = std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| part.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(err) => {
+            dump::record(|| format!("command: {}", command_line), &format!("(failed to start: {})", err));
+            return None;
+        }
+    };
+    let mut stdout = child.stdout.take()?;
+    
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        let _ = sender.send(output);
+    });
+    
+    match receiver.recv_timeout(timeout) {
+        Ok(output) => {
+            let status = child.wait().ok()?;
+            let output = String::from_utf8_lossy(&output);
+            let raw = if status.success() { output.to_string() } else { format!("({})\n{}", status, output) };
+            dump::record(|| format!("command: {}", command_line), &raw);
+            Some((status.code(), output.trim().to_string()))
+        }
+        Err(_) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            dump::record(|| format!("command: {}", command_line), &format!("(timed out after {:?})", timeout));
+            None
+        }
+    }
+}
+
+// Runs a user-supplied command line through the platform shell
+fn user_command(command_line: &str, timeout: Duration) -> Option<String> {
+    let command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", command_line]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", command_line]);
+        command
+    };
+    command_with_timeout(command, timeout)
+}
+
+// Pause before the second try; each later try waits one step longer
+const RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+// Why one try of a retried collector came back empty
+#[derive(Debug, PartialEq)]
+enum Failure {
+    // Another try may work (a busy package database, a network still coming up)
+    Transient,
+    // Another try won't, e.g. the program isn't installed
+    Permanent,
+}
+
+// Calls `attempt` until it succeeds, at most `attempts` times, backing off
+// a little more after each transient failure. One attempt (the default)
+// never sleeps, and a permanent failure ends the retrying at once.
+fn with_retry<T>(attempts: usize, mut attempt: impl FnMut() -> Result<T, Failure>) -> Option<T> {
+    for n in 1..=attempts {
+        match attempt() {
+            Ok(value) => return Some(value),
+            Err(Failure::Permanent) => return None,
+            Err(Failure::Transient) if n < attempts => thread::sleep(RETRY_BACKOFF * n as u32),
+            Err(Failure::Transient) => {}
+        }
+    }
+    None
+}
+
+// Helper function to execute shell commands
+fn shell_command(command: &str, args: &[&str]) -> Option<String> {
+    try_shell_command(command, args).ok()
+}
+
+// `shell_command` for `with_retry`: a program that isn't installed is a
+// permanent failure, anything else a transient one
+fn try_shell_command(command: &str, args: &[&str]) -> Result<String, Failure> {
+    let output = Command::new(command).args(args).output();
+    record_output(|| std::iter::once(command).chain(args.iter().copied()).collect::<Vec<_>>().join(" "), &output);
+    match output {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(Failure::Permanent),
+        output => output.ok()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|s| !s.is_empty())
+            .ok_or(Failure::Transient),
+    }
+}
+
+// Notes a finished command's output for `--dump-raw`
+fn record_output(command_line: impl FnOnce() -> String, output: &std::io::Result<std::process::Output>) {
+    let raw = match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).into_owned(),
+        Ok(output) => format!("({})\n{}", output.status, String::from_utf8_lossy(&output.stdout)),
+        Err(err) => format!("(failed to start: {})", err),
+    };
+    dump::record(|| format!("command: {}", command_line()), &raw);
+}
+
+// fs::read_to_string, noted for `--dump-raw`. Collectors read text files
+// through this; binary ones (EDID, utmp) aren't recorded.
+fn read_file<P: AsRef<Path>>(path: P) -> std::io::Result<String> {
+    let contents = fs::read_to_string(&path);
+    let raw = match &contents {
+        Ok(contents) => contents.clone(),
+        Err(err) => format!("(failed: {})", err),
+    };
+    dump::record(|| format!("file: {}", path.as_ref().display()), &raw);
+    contents
+}
+
+// OS, host, CPU and GPU, which only change across reboots: read from the
+// cache when `cache = true` and it was written this boot
+fn static_fields(config: &Config) -> StaticFields {
+    let collect = || StaticFields {
+        os: get_os_info(config.arch_format),
+        host: get_host_info(),
+        cpu: get_cpu_info(config.cpu_features),
+        gpu: get_gpu_info(),
+    };
+    if !config.cache && !config.refresh_cache {
+        return collect();
+    }
+    let Some(boot_id) = cache::boot_id() else {
+        return collect();
+    };
+    // cpu_features and arch_format change what the CPU and OS lines say
+    let key = format!("{} cpu_features={} arch_format={:?}", boot_id, config.cpu_features, config.arch_format);
+    if !config.refresh_cache {
+        if let Some(fields) = cache::load(&key) {
+            return fields;
+        }
+    }
+    let fields = collect();
+    cache::store(&key, &fields);
+    fields
+}
+
+fn gather_system_info(config: &Config) -> SystemInfo {
+    let static_fields = static_fields(config);
+    SystemInfo {
+        // Basic info
+        username: env::var("USER")
+            .or_else(|_| env::var("USERNAME"))
+            .unwrap_or_else(|_| "unknown".to_string()),
+        hostname: get_hostname(config.pretty_hostname),
+        os: static_fields.os,
+        host: static_fields.host,
+        kernel: get_kernel_version(config.kernel_preemption),
+        uptime: get_uptime(config.uptime_format),
+        load: config.is_shown("load")
+            .then(get_load_average)
+            .flatten(),
+        packages: get_packages(config.retry_attempts, config.package_sizes, &config.thousands_separator()),
+        shell: get_shell(),
+        display: get_display_info(config.display_details, config.display_ppi, config.display_preferred_refresh),
+        de: get_desktop_environment(),
+        wm: get_window_manager(config.wm_version),
+        wm_theme: get_wm_theme(),
+        icons: get_icons(),
+        font: get_font(),
+        cursor: get_cursor(),
+        terminal: get_terminal(),
+        cpu: static_fields.cpu,
+        gpu: static_fields.gpu,
+        active_gpu: (config.gpu_active && config.is_shown("gpu"))
+            .then(get_active_gpu)
+            .flatten(),
+        gpu_displays: if config.gpu_displays && config.is_shown("gpu") {
+            get_gpu_displays(Path::new("/sys/class/drm"))
+        } else {
+            Vec::new()
+        },
+        memory: get_memory_info(config.precision),
+        swap: get_swap_info(config.zram_ratio, config.precision),
+        disk: get_disk_info(config.precision),
+        git: config.is_shown("git")
+            .then(get_git_status)
+            .flatten(),
+        swappiness: config.is_shown("swappiness")
+            .then(|| get_swappiness(Path::new("/proc/sys/vm")))
+            .flatten(),
+        keyboard_layout: config.is_shown("keyboard_layout")
+            .then(get_keyboard_layout)
+            .flatten(),
+        users: config.is_shown("users")
+            .then(get_logged_in_users)
+            .flatten(),
+        vpn: config.is_shown("vpn")
+            .then(get_vpn_interfaces)
+            .flatten(),
+        local_ip: if config.is_shown("local_ip") { get_local_ip(config.retry_attempts) } else { String::new() },
+        battery: get_battery_info(),
+        locale: get_locale(),
+        kernel_modules: config.is_shown("kernel_modules")
+            .then(|| get_kernel_modules(&config.kernel_modules_watch))
+            .flatten(),
+        terminal_size: config.is_shown("terminal_size")
+            .then(get_terminal_size)
+            .flatten(),
+        terminal_theme: config.is_shown("terminal_theme")
+            .then(terminal::background)
+            .flatten(),
+        display_count: config.is_shown("display_count")
+            .then(get_display_count)
+            .flatten(),
+        audio_device: config.is_shown("audio_device")
+            .then(get_audio_device)
+            .flatten(),
+        secure_boot: config.is_shown("secure_boot")
+            .then(get_secure_boot)
+            .flatten(),
+        boot_mode: config.is_shown("boot_mode")
+            .then(get_boot_mode)
+            .flatten(),
+        root_fs: config.is_shown("root_fs")
+            .then(get_root_fs)
+            .flatten(),
+        power_source: config.is_shown("power_source")
+            .then(get_power_source)
+            .flatten(),
+        timezone: config.is_shown("timezone")
+            .then(get_timezone)
+            .flatten(),
+        install_date: config.is_shown("install_date")
+            .then(get_install_date)
+            .flatten(),
+        updates: config.is_shown("updates")
+            .then(get_updates)
+            .flatten(),
+        tpm: config.is_shown("tpm")
+            .then(get_tpm)
+            .flatten(),
+        tearing: config.is_shown("tearing")
+            .then(get_tearing)
+            .flatten(),
+        workspaces: config.is_shown("workspaces")
+            .then(get_workspaces)
+            .flatten(),
+        cmdline: config.is_shown("cmdline")
+            .then(|| get_cmdline(&config.cmdline_flags, config.cmdline_max_width))
+            .flatten(),
+        cpu_temp: config.is_shown("cpu_temp")
+            .then(|| get_cpu_temp(config.cpu_temp_sensor.as_deref()))
+            .flatten(),
+        cpu_cache: config.is_shown("cpu_cache")
+            .then(get_cpu_cache)
+            .flatten(),
+        gpu_vram_used: config.is_shown("gpu_vram_used")
+            .then(get_gpu_vram_used)
+            .flatten(),
+        ram_slots: config.is_shown("ram_slots")
+            .then(get_ram_slots)
+            .flatten(),
+        gpu_clock: config.is_shown("gpu_clock")
+            .then(get_gpu_clock)
+            .flatten(),
+        custom: get_custom_modules(config),
+    }
+}
+
+// Runs each shown `[[module]]` command, keeping the first line of its output
+fn get_custom_modules(config: &Config) -> HashMap<String, Option<String>> {
+    config.custom_modules.iter()
+        .filter(|module| config.is_shown(&module.name))
+        .map(|module| {
+            let timeout = Duration::from_millis(module.timeout_ms.unwrap_or(config.command_timeout_ms));
+            let value = user_command(&module.command, timeout)
+                .and_then(|output| output.lines().next().map(|line| line.trim().to_string()))
+                .filter(|line| !line.is_empty());
+            (module.name.clone(), value)
+        })
+        .collect()
+}
+
+fn get_hostname(prefer_pretty: bool) -> String {
+    if prefer_pretty && cfg!(target_os = "linux") {
+        // Set with `hostnamectl set-hostname --pretty`; may contain spaces
+        // and characters a DNS hostname can't
+        if let Some(pretty) = read_file("/etc/machine-info")
+            .ok()
+            .and_then(|contents| parse_os_release(&contents).remove("PRETTY_HOSTNAME"))
+            .filter(|name| !name.is_empty())
+        {
+            return pretty;
+        }
+    }
+    if cfg!(target_os = "windows") {
+        env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown".to_string())
+    } else {
+        shell_command("hostname", &[]).unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+pub fn get_os_info(arch_format: ArchFormat) -> String {
+    let arch = format_arch(env::consts::ARCH, arch_format);
+    if cfg!(target_os = "windows") {
+        powershell_command(
+            "(Get-CimInstance -ClassName Win32_OperatingSystem).Caption.Replace('Microsoft ', '')"
+        )
+            // Without PowerShell, the edition is in the registry
+            .or_else(|| {
+                shell_command("reg", &["query", r"HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion"])
+                    .and_then(|output| windows_product_name(&parse_reg_values(&output)))
+            })
+            .map(|name| format!("{} {}", name, arch))
+            .unwrap_or_else(|| format!("{} {}", env::consts::OS, arch))
+    } else if cfg!(target_os = "linux") || cfg!(target_os = "freebsd") || cfg!(target_os = "netbsd") {
+        // FreeBSD 13+ ships os-release too; older releases and NetBSD fall
+        // back below
+        read_file("/etc/os-release")
+            .or_else(|_| read_file("/usr/lib/os-release"))
+            .ok()
+            .and_then(|contents| parse_os_release(&contents).remove("PRETTY_NAME"))
+            .or_else(|| {
+                if cfg!(target_os = "freebsd") {
+                    shell_command("freebsd-version", &[]).map(|version| format!("FreeBSD {}", version))
+                } else if cfg!(target_os = "netbsd") {
+                    sysctl("kern.osrelease").map(|version| format!("NetBSD {}", version))
+                } else {
+                    None
+                }
+            })
+            .map(|name| format!("{} {}", name, arch))
+            .unwrap_or_else(|| format!("{} {}", env::consts::OS, arch))
+    } else {
+        format!("{} {}", env::consts::OS, arch)
+    }
+}
+
+// Spells an architecture name the configured way. Accepts the spellings
+// other tools use too (Windows' AMD64, the BSDs' amd64 and arm64), so the
+// same machine reads the same everywhere; unknown names pass through.
+pub fn format_arch(arch: &str, format: ArchFormat) -> String {
+    let raw = match arch.to_ascii_lowercase().as_str() {
+        "x86_64" | "amd64" | "x64" => "x86_64",
+        "aarch64" | "arm64" => "aarch64",
+        "x86" | "i386" | "i586" | "i686" => "x86",
+        "arm" | "armv7" | "armv7l" | "armhf" => "arm",
+        _ => return arch.to_string(),
+    };
+    let name = match (format, raw) {
+        (ArchFormat::Raw, _) => raw,
+        (ArchFormat::Common, "x86_64") => "x64",
+        (ArchFormat::Common, "aarch64") => "arm64",
+        (ArchFormat::Common, "arm") => "arm32",
+        (ArchFormat::Common, other) => other,
+    };
+    name.to_string()
+}
+
+// Parses os-release into a key/value map, stripping optional quotes
+pub fn parse_os_release(contents: &str) -> HashMap<String, String> {
+    contents.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect()
+}
+
+fn get_host_info() -> String {
+    if cfg!(target_os = "windows") {
+        powershell_command(
+            "$cs = Get-CimInstance -ClassName Win32_ComputerSystem; \
+             $bios = Get-CimInstance -ClassName Win32_BIOS; \
+             '{0} ({1})' -f $bios.SerialNumber, $cs.Model"
+        ).filter(|s| s != " ()")
+        .unwrap_or_else(|| "Unknown".to_string())
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+// With `show_preemption`, the preemption model from the kernel's build
+// string is appended where it has one ("6.9.0-rt5 (PREEMPT_RT)")
+pub fn get_kernel_version(show_preemption: bool) -> String {
+    if cfg!(target_os = "windows") {
+        let base = powershell_command(
+            "$os = Get-CimInstance -ClassName Win32_OperatingSystem; \
+             'WIN32_NT {0}' -f $os.Version"
+        );
+        
+        if let Some(mut result) = base {
+            // Check for dev build
+            if let Some(build) = powershell_command(
+                "$os = Get-CimInstance -ClassName Win32_OperatingSystem; \
+                 $os.BuildNumber"
+            ) {
+                if build.parse::<u32>().unwrap_or(0) > 22000 {
+                    result.push_str(" (Dev)");
+                }
+            }
+            result
+        } else {
+            "unknown".to_string()
+        }
+    } else {
+        let Some(mut release) = shell_command("uname", &["-r"]) else {
+            return "unknown".to_string();
+        };
+        let version = show_preemption.then(|| shell_command("uname", &["-v"])).flatten();
+        if let Some(model) = version.as_deref().and_then(preemption_model) {
+            release.push_str(&format!(" ({})", model));
+        }
+        release
+    }
+}
+
+// The preemption marker in `uname -v` ("#1 SMP PREEMPT_DYNAMIC Thu ..."),
+// if any. Older RT kernels print "PREEMPT RT" as two words.
+fn preemption_model(version: &str) -> Option<&'static str> {
+    let words: Vec<&str> = version.split_whitespace().collect();
+    if words.contains(&"PREEMPT_RT") || (words.contains(&"PREEMPT") && words.contains(&"RT")) {
+        Some("PREEMPT_RT")
+    } else if words.contains(&"PREEMPT_DYNAMIC") {
+        Some("PREEMPT_DYNAMIC")
+    } else if words.contains(&"PREEMPT") {
+        Some("PREEMPT")
+    } else {
+        None
+    }
+}
+
+pub fn get_uptime(format: UptimeFormat) -> String {
+    if cfg!(target_os = "linux") {
+        read_file("/proc/uptime")
+            .ok()
+            .and_then(|contents| parse_uptime(&contents))
+            .or_else(|| {
+                // Fall back to the boot time recorded in /proc/stat
+                let boot_time = read_file("/proc/stat")
+                    .ok()
+                    .and_then(|contents| parse_boot_time(&contents))?;
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+                now.checked_sub(boot_time)
+            })
+            .or_else(|| kernel_sysinfo().map(|counts| counts.uptime))
+            .map(|seconds| format_uptime(seconds, format))
+            .unwrap_or_else(|| "unknown".to_string())
+    } else if cfg!(target_os = "windows") {
+        powershell_command(
+            "[long]((Get-Date) - (Get-CimInstance Win32_OperatingSystem).LastBootUpTime).TotalSeconds"
+        )
+        .and_then(|seconds| seconds.parse().ok())
+        .map(|seconds| format_uptime(seconds, format))
+        .unwrap_or_else(|| "unknown".to_string())
+    } else if cfg!(target_os = "freebsd") {
+        sysctl("kern.boottime")
+            .and_then(|output| parse_kern_boottime(&output))
+            .and_then(|boot_time| {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+                now.checked_sub(boot_time)
+            })
+            .map(|seconds| format_uptime(seconds, format))
+            .unwrap_or_else(|| "unknown".to_string())
+    } else {
+        "unknown".to_string()
+    }
+}
+
+// Parses the boot timestamp out of `sysctl -n kern.boottime`, e.g.
+// "{ sec = 1700000000, usec = 123456 } Tue Nov 14 22:13:20 2023"
+fn parse_kern_boottime(output: &str) -> Option<u64> {
+    let (_, rest) = output.split_once("sec =")?;
+    rest.split([',', '}'])
+        .next()
+        .and_then(|seconds| seconds.trim().parse().ok())
+}
+
+// Parses the first field of /proc/uptime (seconds since boot)
+pub fn parse_uptime(contents: &str) -> Option<u64> {
+    contents.split_whitespace()
+        .next()
+        .and_then(|seconds| seconds.parse::<f64>().ok())
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(|seconds| seconds as u64)
+}
+
+// Parses the `btime` line of /proc/stat (boot time as a Unix timestamp)
+pub fn parse_boot_time(contents: &str) -> Option<u64> {
+    contents.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|value| value.trim().parse::<u64>().ok())
+}
+
+pub fn format_uptime(seconds: u64, format: UptimeFormat) -> String {
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let minutes = (seconds % 3600) / 60;
+    
+    if format == UptimeFormat::Compact {
+        let parts: Vec<String> = [(days, 'd'), (hours, 'h'), (minutes, 'm')].iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, unit)| format!("{}{}", count, unit))
+            .collect();
+        return if parts.is_empty() { "0m".to_string() } else { parts.join(" ") };
+    }
+    
+    let mut parts = Vec::new();
+    
+    if days > 0 {
+        parts.push(format!("{} day{}", days, if days == 1 { "" } else { "s" }));
+    }
+    if hours > 0 {
+        parts.push(format!("{} hour{}", hours, if hours == 1 { "" } else { "s" }));
+    }
+    if minutes > 0 {
+        parts.push(format!("{} min{}", minutes, if minutes == 1 { "" } else { "s" }));
+    }
+    
+    if parts.is_empty() {
+        "less than a minute".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+pub fn get_packages(attempts: usize, with_sizes: bool, separator: &str) -> String {
+    if cfg!(target_os = "linux") {
+        let mut counts = Vec::new();
+        // The distro's own package manager first, read from its database
+        // where that's cheaper than running it
+        let native = [
+            ("pacman", count_pacman_packages(Path::new("/var/lib/pacman/local"))),
+            ("dpkg", read_file("/var/lib/dpkg/status").ok().map(|status| count_dpkg_packages(&status))),
+            ("rpm", with_retry(attempts, || try_shell_command("rpm", &["-qa"])).map(|output| count_nonempty_lines(&output, 0))),
+        ];
+        for (manager, count) in native {
+            if let Some(count) = count.filter(|&count| count > 0) {
+                counts.push(format_package_count(count, manager, None, separator));
+            }
+        }
+        // Apps and runtimes alike, one ref per line
+        if let Some(output) = with_retry(attempts, || try_shell_command("flatpak", &["list", "--columns=ref"])) {
+            let count = count_nonempty_lines(&output, 0);
+            if count > 0 {
+                let size = with_sizes.then(flatpak_size).flatten();
+                counts.push(format_package_count(count, "flatpak", size, separator));
+            }
+        }
+        if let Some(output) = with_retry(attempts, || try_shell_command("snap", &["list"])) {
+            // Skip the "Name  Version  Rev ..." header
+            let count = count_nonempty_lines(&output, 1);
+            if count > 0 {
+                let size = with_sizes.then(snap_size).flatten();
+                counts.push(format_package_count(count, "snap", size, separator));
+            }
+        }
+        if !counts.is_empty() {
+            return counts.join(", ");
+        }
+    } else if cfg!(target_os = "windows") {
+        // Try chocolatey first
+        if let Some(output) = with_retry(attempts, || try_shell_command("choco", &["list", "--local-only"])) {
+            let count = count_choco_packages(&output);
+            if count > 0 {
+                return format_package_count(count, "choco", None, separator);
+            }
+        }
+        
+        // Try winget
+        if let Some(output) = with_retry(attempts, || try_shell_command("winget", &["list"])) {
+            let count = count_winget_packages(&output);
+            if count > 0 {
+                return format_package_count(count, "winget", None, separator);
+            }
+        }
+    }
+    "0".to_string()
+}
+
+// One directory per installed package, next to an ALPM_DB_VERSION file
+fn count_pacman_packages(local_db: &Path) -> Option<usize> {
+    let entries = fs::read_dir(local_db).ok()?;
+    Some(entries.flatten().filter(|entry| entry.path().is_dir()).count())
+}
+
+// Stanzas of /var/lib/dpkg/status for packages that are installed, not
+// just removed with their config files left behind
+fn count_dpkg_packages(status: &str) -> usize {
+    status.lines().filter(|line| line.trim_end() == "Status: install ok installed").count()
+}
+
+// Non-blank lines after the first `header_lines`
+fn count_nonempty_lines(output: &str, header_lines: usize) -> usize {
+    output.lines().skip(header_lines).filter(|line| !line.trim().is_empty()).count()
+}
+
+// choco ends its list with "N packages installed."; when that's missing or
+// unreadable, count the lines between it and the "Chocolatey vX" banner
+fn count_choco_packages(output: &str) -> usize {
+    let summary = output.lines()
+        .find_map(|line| line.trim().strip_suffix("packages installed.")?.trim().parse().ok());
+    summary.unwrap_or_else(|| {
+        output.lines()
+            .filter(|line| !line.trim().is_empty() && !line.contains("packages installed"))
+            .count()
+            .saturating_sub(1)
+    })
+}
+
+// Rows under winget's "Name  Id  Version ..." header and its "----" rule
+fn count_winget_packages(output: &str) -> usize {
+    output.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("Name") && !line.starts_with('-'))
+        .count()
+}
+
+// "12 (flatpak)", or "12 (flatpak, 3.2 GiB)" with a size
+fn format_package_count(count: usize, manager: &str, size: Option<u64>, separator: &str) -> String {
+    let count = group_thousands(count, separator);
+    match size {
+        Some(bytes) => format!("{} ({}, {:.1} GiB)", count, manager, bytes as f64 / (1024.0 * 1024.0 * 1024.0)),
+        None => format!("{} ({})", count, manager),
+    }
+}
+
+// 12345 -> "12,345" with "," as the separator; an empty one leaves
+// the digits alone
+fn group_thousands(number: usize, separator: &str) -> String {
+    let digits = number.to_string();
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+// Disk used by the system and per-user flatpak installations. Walking them
+// takes seconds on a cold cache, hence `package_sizes` being opt-in.
+fn flatpak_size() -> Option<u64> {
+    let mut roots = vec!["/var/lib/flatpak".to_string()];
+    if let Some(data_dir) = dirs::data_dir() {
+        roots.push(data_dir.join("flatpak").to_string_lossy().into_owned());
+    }
+    let roots: Vec<&str> = roots.iter().map(String::as_str).filter(|dir| Path::new(dir).is_dir()).collect();
+    if roots.is_empty() {
+        return None;
+    }
+    let args: Vec<&str> = ["-s", "-b", "-c"].into_iter().chain(roots).collect();
+    parse_du_total(&shell_command("du", &args)?)
+}
+
+// The "total" line `du -c` ends with
+fn parse_du_total(output: &str) -> Option<u64> {
+    output.lines().last()?.split_whitespace().next()?.parse().ok()
+}
+
+// Installed snaps are squashfs images, one file per revision kept
+fn snap_size() -> Option<u64> {
+    let entries = fs::read_dir("/var/lib/snapd/snaps").ok()?;
+    let total = entries.flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "snap"))
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    Some(total).filter(|&total| total > 0)
+}
+
+// Refreshing package databases can take a while on a slow mirror
+const UPDATES_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Pending updates from the distro's package manager, picked by os-release
+// ID (then ID_LIKE). Each tool signals "nothing to update" its own way.
+fn get_updates() -> Option<usize> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let ids = logo_ids();
+    let run = |program: &str, args: &[&str]| {
+        let mut command = Command::new(program);
+        command.args(args);
+        let (code, output) = command_status_with_timeout(command, UPDATES_TIMEOUT)?;
+        pending_updates(program, code, &output)
+    };
+    ids.iter().find_map(|id| match id.as_str() {
+        "arch" => run("checkupdates", &[]),
+        "debian" | "ubuntu" => run("apt", &["list", "--upgradable"]),
+        "fedora" | "rhel" => run("dnf", &["check-update", "--quiet"]),
+        _ => None,
+    })
+}
+
+// Reads a package manager's exit code along with its output, so a check
+// that failed (no network, a locked database) gives None rather than 0.
+// checkupdates exits 2 when everything is current; dnf exits 100 when
+// there are updates and 0 when there are none.
+fn pending_updates(program: &str, code: Option<i32>, output: &str) -> Option<usize> {
+    match (program, code?) {
+        ("checkupdates", 0) => Some(count_checkupdates(output)),
+        ("checkupdates", 2) => Some(0),
+        ("apt", 0) => Some(count_apt_upgradable(output)),
+        ("dnf", 100) => Some(count_dnf_updates(output)),
+        ("dnf", 0) => Some(0),
+        _ => None,
+    }
+}
+
+// One package per line
+fn count_checkupdates(output: &str) -> usize {
+    output.lines().filter(|line| !line.trim().is_empty()).count()
+}
+
+// Skips apt's "Listing..." header and its CLI stability warning
+fn count_apt_upgradable(output: &str) -> usize {
+    output.lines().filter(|line| line.contains("[upgradable from")).count()
+}
+
+// Package lines are "<name>.<arch> <version> <repo>"; anything after an
+// "Obsoleting Packages" header repeats packages already counted
+fn count_dnf_updates(output: &str) -> usize {
+    output.lines()
+        .take_while(|line| !line.starts_with("Obsoleting"))
+        .filter(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            fields.len() == 3 && fields[0].contains('.') && !line.starts_with("Last metadata")
+        })
+        .count()
+}
+
+pub fn get_shell() -> String {
+    if cfg!(target_os = "windows") {
+        if let Some(version) = powershell_command("$PSVersionTable.PSVersion.ToString()") {
+            // Only 5.1 and earlier are branded "Windows PowerShell"
+            return if POWERSHELL.get() == Some(&Some("pwsh")) {
+                format!("PowerShell {}", version)
+            } else {
+                format!("Windows PowerShell {}", version)
+            };
+        }
+    }
+    
+    let shell_path = env::var("SHELL")
+        .or_else(|_| env::var("ComSpec"))
+        .ok()
+        .or_else(|| {
+            // zsh has been the default login shell since macOS Catalina
+            cfg!(target_os = "macos").then(|| "/bin/zsh".to_string())
+        });
+    let Some(shell_path) = shell_path else {
+        return "unknown".to_string();
+    };
+    
+    let name = shell_path.split(['/', '\\'])
+        .next_back()
+        .unwrap_or("unknown")
+        .to_string();
+    
+    if cfg!(target_os = "windows") {
+        return name;
+    }
+    
+    shell_command(&shell_path, &["--version"])
+        .and_then(|output| parse_shell_version(&output))
+        .map(|version| format!("{} {}", name, version))
+        .unwrap_or(name)
+}
+
+// Extracts the version number from `<shell> --version` output, e.g.
+// "zsh 5.9 (x86_64-apple-darwin23.0)" or
+// "GNU bash, version 5.2.15(1)-release (x86_64-pc-linux-gnu)"
+pub fn parse_shell_version(output: &str) -> Option<String> {
+    output.lines()
+        .next()?
+        .split_whitespace()
+        .map(|token| {
+            token.chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect::<String>()
+                .trim_end_matches('.')
+                .to_string()
+        })
+        .find(|version| version.contains('.') && version.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+fn get_display_info(show_details: bool, show_ppi: bool, show_preferred_refresh: bool) -> Vec<String> {
+    if cfg!(target_os = "windows") {
+        // WMI sizes monitors in whole centimetres, listed in the same order
+        // as the adapters driving them on typical one-monitor-per-output setups
+        let sizes: Vec<(u32, u32)> = if show_ppi {
+            powershell_command(
+                "Get-CimInstance -Namespace root\\wmi -ClassName WmiMonitorBasicDisplayParams | ForEach-Object { \
+                    '{0} {1}' -f $_.MaxHorizontalImageSize, $_.MaxVerticalImageSize \
+                }"
+            )
+                .map(|output| output.lines().filter_map(parse_windows_monitor_size).collect())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        // WMI doesn't expose the HDR state, so only the color depth is reported
+        if let Some(output) = powershell_command(
+            "Get-CimInstance -ClassName Win32_VideoController | Where-Object {$_.CurrentHorizontalResolution -gt 0} | ForEach-Object { \
+                '{0}x{1} {2} {3}' -f $_.CurrentHorizontalResolution, $_.CurrentVerticalResolution, $_.CurrentRefreshRate, $_.CurrentBitsPerPixel \
+            }"
+        ) {
+            return output.lines()
+                .filter_map(|line| {
+                    let mut fields = line.split_whitespace();
+                    let resolution = fields.next()?;
+                    let refresh = fields.next().and_then(|hz| hz.parse().ok());
+                    let depth = fields.next()
+                        .and_then(|bpp| bpp.parse().ok())
+                        .and_then(windows_color_depth);
+                    let details = if show_details { DisplayDetails { depth, ..DisplayDetails::default() } } else { DisplayDetails::default() };
+                    Some((resolution, refresh, details))
+                })
+                .zip(sizes.into_iter().map(Some).chain(std::iter::repeat(None)))
+                .map(|((resolution, refresh, details), size_mm)| {
+                    format_display(None, resolution, refresh, &DisplayDetails { size_mm, ..details })
+                })
+                .collect();
+        }
+        Vec::new()
+    } else if cfg!(target_os = "linux") {
+        get_drm_displays(show_details, show_ppi, show_preferred_refresh)
+    } else if cfg!(target_os = "macos") {
+        shell_command("system_profiler", &["SPDisplaysDataType"])
+            .map(|output| parse_macos_displays(&output, show_details))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    }
+}
+
+// Bits per channel from Win32_VideoController's bits per pixel: 32 bpp is
+// 8-bit color plus an alpha or padding byte, not 10-bit
+fn windows_color_depth(bpp: u32) -> Option<u8> {
+    match bpp {
+        24 | 32 => Some(8),
+        30 => Some(10),
+        _ => None,
+    }
+}
+
+// "60 34" (centimetres) as millimetres; zeros mean WMI doesn't know
+fn parse_windows_monitor_size(line: &str) -> Option<(u32, u32)> {
+    let (width, height) = line.trim().split_once(' ')?;
+    let (width, height): (u32, u32) = (width.parse().ok()?, height.trim().parse().ok()?);
+    (width > 0 && height > 0).then_some((width * 10, height * 10))
+}
+
+// Number of connected displays, without reading modes or EDIDs
+fn get_display_count() -> Option<usize> {
+    if cfg!(target_os = "linux") {
+        drm_connected_connectors(Path::new("/sys/class/drm")).map(|connectors| connectors.len())
+    } else if cfg!(target_os = "windows") {
+        powershell_command(
+            "@(Get-CimInstance -ClassName Win32_VideoController | Where-Object {$_.CurrentHorizontalResolution -gt 0}).Count"
+        ).and_then(|count| count.trim().parse().ok())
+    } else if cfg!(target_os = "macos") {
+        shell_command("system_profiler", &["SPDisplaysDataType"])
+            .map(|output| parse_macos_displays(&output, false).len())
+    } else {
+        None
+    }
+}
+
+// Reads the "Displays:" sections of `system_profiler SPDisplaysDataType`.
+// Each display reports its native resolution ("3456 x 2234 Retina"); the
+// refresh rate only appears on the scaled "UI Looks like" line.
+fn parse_macos_displays(output: &str, show_details: bool) -> Vec<String> {
+    #[derive(Default)]
+    struct Display {
+        resolution: Option<String>,
+        refresh: Option<u32>,
+        details: DisplayDetails,
+    }
+    
+    let finish = |display: Display, lines: &mut Vec<String>| {
+        if let Some(resolution) = &display.resolution {
+            lines.push(format_display(None, resolution, display.refresh, &display.details));
+        }
+    };
+    
+    let mut lines = Vec::new();
+    let mut section_indent = None;
+    let mut current: Option<Display> = None;
+    
+    for line in output.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed == "Displays:" {
+            section_indent = Some(indent);
+            continue;
+        }
+        let Some(section) = section_indent else {
+            continue;
+        };
+        if indent <= section {
+            // Back out at the GPU level; the next GPU may have its own section
+            section_indent = None;
+            if let Some(display) = current.take() {
+                finish(display, &mut lines);
+            }
+            continue;
+        }
+        
+        match trimmed.split_once(": ") {
+            None if trimmed.ends_with(':') => {
+                if let Some(display) = current.replace(Display::default()) {
+                    finish(display, &mut lines);
+                }
+            }
+            Some((key, value)) => {
+                let Some(display) = current.as_mut() else {
+                    continue;
+                };
+                match key {
+                    "Resolution" => {
+                        let mut numbers = value.split(" x ");
+                        let width = numbers.next().unwrap_or("").trim();
+                        let height = numbers.next().unwrap_or("").split_whitespace().next().unwrap_or("");
+                        if !width.is_empty() && !height.is_empty() {
+                            display.resolution = Some(format!("{}x{}", width, height));
+                        }
+                        display.details.retina = value.contains("Retina");
+                    }
+                    "UI Looks like" => {
+                        display.refresh = value.split_once('@')
+                            .and_then(|(_, hz)| hz.trim().trim_end_matches("Hz").parse::<f64>().ok())
+                            .map(|hz| hz.round() as u32);
+                    }
+                    "Framebuffer Depth" if show_details => {
+                        display.details.depth = value.split('-').next()
+                            .and_then(|bits| bits.parse::<u8>().ok())
+                            .map(|bits| if bits >= 30 { 10 } else { 8 });
+                    }
+                    _ => {}
+                }
+            }
+            None => {}
+        }
+    }
+    if let Some(display) = current {
+        finish(display, &mut lines);
+    }
+    lines
+}
+
+#[derive(Default)]
+struct DisplayDetails {
+    // Bits per color channel
+    depth: Option<u8>,
+    hdr: bool,
+    // macOS's HiDPI scaling
+    retina: bool,
+    // Physical width and height, for the PPI
+    size_mm: Option<(u32, u32)>,
+    // The EDID's preferred rate, where the current one is unknown
+    preferred_refresh: Option<u32>,
+}
+
+// Renders e.g. "Display (DP-1): 3840x2160 @ 120 Hz (10-bit, HDR)"
+fn format_display(connector: Option<&str>, resolution: &str, refresh: Option<u32>, details: &DisplayDetails) -> String {
+    let mut line = match connector {
+        Some(connector) => format!("Display ({}): {}", connector, resolution),
+        None => format!("Display: {}", resolution),
+    };
+    if let Some(refresh) = refresh.filter(|hz| *hz > 0) {
+        line.push_str(&format!(" @ {} Hz", refresh));
+    }
+    
+    let mut notes = Vec::new();
+    if let Some(refresh) = details.preferred_refresh {
+        notes.push(format!("preferred {} Hz", refresh));
+    }
+    if details.retina {
+        notes.push("Retina".to_string());
+    }
+    if let Some(depth) = details.depth {
+        notes.push(format!("{}-bit", depth));
+    }
+    if details.hdr {
+        notes.push("HDR".to_string());
+    }
+    if let Some(ppi) = details.size_mm.and_then(|(width, height)| display_ppi(resolution, width, height)) {
+        notes.push(format!("{} PPI", ppi));
+    }
+    if !notes.is_empty() {
+        line.push_str(&format!(" ({})", notes.join(", ")));
+    }
+    line
+}
+
+// Pixels per inch along the diagonal, from a "WIDTHxHEIGHT" resolution and
+// the panel's size in millimetres. Projectors and some TVs report no size
+// (or a made-up tiny one), which gives no PPI.
+fn display_ppi(resolution: &str, width_mm: u32, height_mm: u32) -> Option<u32> {
+    let (width, height) = resolution.split_once('x')?;
+    let (width, height): (f64, f64) = (width.trim().parse().ok()?, height.trim().parse().ok()?);
+    if width_mm < 10 || height_mm < 10 {
+        return None;
+    }
+    let diagonal_px = width.hypot(height);
+    let diagonal_in = (width_mm as f64).hypot(height_mm as f64) / 25.4;
+    Some((diagonal_px / diagonal_in).round() as u32)
+}
+
+// Connector directories under `drm_dir` (normally /sys/class/drm) with a
+// display plugged in, sorted by name; None when the directory is unreadable
+fn drm_connected_connectors(drm_dir: &Path) -> Option<Vec<PathBuf>> {
+    let mut connectors: Vec<_> = fs::read_dir(drm_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            read_file(path.join("status"))
+                .map(|status| status.trim() == "connected")
+                .unwrap_or(false)
+        })
+        .collect();
+    connectors.sort();
+    Some(connectors)
+}
+
+// Lists connected monitors from the DRM connectors in sysfs, which works
+// without X11/Wayland (e.g. on a TTY). Sysfs has no current refresh rate;
+// `show_preferred_refresh` adds the EDID's preferred one, labelled as such,
+// when the preferred resolution is the mode listed first.
+fn get_drm_displays(show_details: bool, show_ppi: bool, show_preferred_refresh: bool) -> Vec<String> {
+    let connectors = drm_connected_connectors(Path::new("/sys/class/drm")).unwrap_or_default();
+    
+    connectors.iter()
+        .filter_map(|path| {
+            let file_name = path.file_name()?.to_str()?;
+            // Connector directories are named "card0-DP-1"
+            let (_, connector) = file_name.split_once('-')?;
+            let modes = read_file(path.join("modes")).ok()?;
+            let resolution = modes.lines().next()?.trim();
+            let details = if show_details || show_ppi || show_preferred_refresh {
+                let edid = fs::read(path.join("edid")).unwrap_or_default();
+                let parsed = parse_edid_details(&edid);
+                DisplayDetails {
+                    depth: parsed.depth.filter(|_| show_details),
+                    hdr: parsed.hdr && show_details,
+                    size_mm: parsed.size_mm.filter(|_| show_ppi),
+                    preferred_refresh: edid_preferred_mode(&edid)
+                        .filter(|&(width, height, _)| show_preferred_refresh && format!("{}x{}", width, height) == resolution)
+                        .map(|(_, _, refresh)| refresh),
+                    ..DisplayDetails::default()
+                }
+            } else {
+                DisplayDetails::default()
+            };
+            Some(format_display(Some(connector), resolution, None, &details))
+        })
+        .collect()
+}
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+
+// Reads the color depth from the EDID base block and HDR support from the
+// HDR static metadata block of a CTA-861 extension. Missing or truncated
+// EDIDs simply yield no details.
+fn parse_edid_details(edid: &[u8]) -> DisplayDetails {
+    let mut details = DisplayDetails::default();
+    if edid.len() < 128 || edid[..8] != EDID_HEADER {
+        return details;
+    }
+    
+    // Video input definition: bit 7 set for digital inputs, bits 6-4 encode
+    // the bit depth (EDID 1.4)
+    let input = edid[20];
+    if input & 0x80 != 0 {
+        details.depth = match (input >> 4) & 0x07 {
+            1 => Some(6),
+            2 => Some(8),
+            3 => Some(10),
+            4 => Some(12),
+            5 => Some(14),
+            6 => Some(16),
+            _ => None,
+        };
+    }
+    
+    // The first detailed timing descriptor has the size in millimetres (12
+    // bits each); the base block only has whole centimetres
+    let timing = &edid[54..72];
+    if timing[0] != 0 || timing[1] != 0 {
+        let width = timing[12] as u32 | ((timing[14] as u32 >> 4) << 8);
+        let height = timing[13] as u32 | ((timing[14] as u32 & 0x0f) << 8);
+        details.size_mm = Some((width, height)).filter(|&(width, height)| width > 0 && height > 0);
+    }
+    if details.size_mm.is_none() && edid[21] > 0 && edid[22] > 0 {
+        details.size_mm = Some((edid[21] as u32 * 10, edid[22] as u32 * 10));
+    }
+    
+    for block in edid[128..].chunks_exact(128) {
+        // CTA-861 extension with a data block collection
+        if block[0] != 0x02 {
+            continue;
+        }
+        let end = (block[2] as usize).min(127);
+        let mut offset = 4;
+        while offset < end {
+            let tag = block[offset] >> 5;
+            let length = (block[offset] & 0x1f) as usize;
+            // Extended tag 6 is the HDR static metadata block; byte 2 lists
+            // the supported transfer functions (bit 2 = SMPTE ST 2084/PQ, bit 3 = HLG)
+            if tag == 7 && length >= 2 && offset + 2 < end && block[offset + 1] == 0x06 {
+                details.hdr = block[offset + 2] & 0x0c != 0;
+            }
+            offset += length + 1;
+        }
+    }
+    
+    details
+}
+
+// The first detailed timing descriptor is the display's preferred mode:
+// its resolution and refresh rate, the pixel clock (in 10 kHz units) over
+// the pixels in a frame including blanking. None without one.
+fn edid_preferred_mode(edid: &[u8]) -> Option<(u32, u32, u32)> {
+    if edid.len() < 128 || edid[..8] != EDID_HEADER {
+        return None;
+    }
+    let timing = &edid[54..72];
+    let clock = (timing[0] as u32 | (timing[1] as u32) << 8) * 10_000;
+    let width = timing[2] as u32 | ((timing[4] as u32 >> 4) << 8);
+    let h_blank = timing[3] as u32 | ((timing[4] as u32 & 0x0f) << 8);
+    let height = timing[5] as u32 | ((timing[7] as u32 >> 4) << 8);
+    let v_blank = timing[6] as u32 | ((timing[7] as u32 & 0x0f) << 8);
+    let pixels = (width + h_blank) * (height + v_blank);
+    if clock == 0 || pixels == 0 {
+        return None;
+    }
+    Some((width, height, (clock as f64 / pixels as f64).round() as u32))
+}
+
+fn get_desktop_environment() -> String {
+    if cfg!(target_os = "windows") {
+        "Fluent".to_string()
+    } else {
+        env::var("XDG_CURRENT_DESKTOP")
+            .or_else(|_| env::var("DESKTOP_SESSION"))
+            .unwrap_or_else(|_| "unknown".to_string())
+    }
+}
+
+fn get_window_manager(with_version: bool) -> String {
+    if cfg!(target_os = "windows") {
+        powershell_command(
+            "$os = Get-CimInstance -ClassName Win32_OperatingSystem; \
+             'Desktop Window Manager {0}' -f $os.Version"
+        ).unwrap_or_else(|| "Desktop Window Manager".to_string())
+    } else {
+        let known = with_version.then(|| {
+            known_window_manager(|name| env::var_os(name).is_some(), env::var("XDG_CURRENT_DESKTOP").ok().as_deref())
+        }).flatten();
+        if let Some(wm) = known {
+            // Fall back to the bare name when the WM's binary can't be run
+            return shell_command(wm.command, wm.args)
+                .and_then(|output| parse_shell_version(&output))
+                .map(|version| format!("{} {}", wm.name, version))
+                .unwrap_or_else(|| wm.name.to_string());
+        }
+        env::var("XDG_SESSION_TYPE").unwrap_or_else(|_| "unknown".to_string())
+    }
+}
+
+struct KnownWm {
+    name: &'static str,
+    // Set by the WM for its clients, when it has such a variable
+    env_var: Option<&'static str>,
+    // How the WM appears in XDG_CURRENT_DESKTOP
+    desktop: &'static str,
+    command: &'static str,
+    args: &'static [&'static str],
+}
+
+// Window managers whose version can be asked for
+const KNOWN_WMS: &[KnownWm] = &[
+    KnownWm { name: "Hyprland", env_var: Some("HYPRLAND_INSTANCE_SIGNATURE"), desktop: "hyprland", command: "hyprctl", args: &["version"] },
+    KnownWm { name: "sway", env_var: Some("SWAYSOCK"), desktop: "sway", command: "sway", args: &["--version"] },
+    KnownWm { name: "i3", env_var: Some("I3SOCK"), desktop: "i3", command: "i3", args: &["--version"] },
+    KnownWm { name: "bspwm", env_var: None, desktop: "bspwm", command: "bspwm", args: &["-v"] },
+];
+
+// The running WM, by the variables it exports or else XDG_CURRENT_DESKTOP
+// (a colon-separated list)
+fn known_window_manager(is_set: impl Fn(&str) -> bool, current_desktop: Option<&str>) -> Option<&'static KnownWm> {
+    KNOWN_WMS.iter()
+        .find(|wm| wm.env_var.is_some_and(&is_set))
+        .or_else(|| {
+            let desktops: Vec<String> = current_desktop?.split(':').map(str::to_ascii_lowercase).collect();
+            KNOWN_WMS.iter().find(|wm| desktops.iter().any(|desktop| desktop == wm.desktop))
+        })
+}
+
+// Whether the compositor lets games present immediately (tearing) rather
+// than waiting for vblank. Only Hyprland and picom can be asked.
+fn get_tearing() -> Option<bool> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        return shell_command("hyprctl", &["getoption", "general:allow_tearing"])
+            .and_then(|output| parse_hyprctl_bool(&output));
+    }
+    // picom on X: tearing is allowed unless its config turns vsync on
+    if env::var_os("WAYLAND_DISPLAY").is_none() && shell_command("pgrep", &["-x", "picom"]).is_some() {
+        let config_dir = config::xdg_config_dir()?;
+        let vsync = [config_dir.join("picom.conf"), config_dir.join("picom").join("picom.conf")]
+            .iter()
+            .find_map(|path| read_file(path).ok())
+            .and_then(|contents| parse_picom_vsync(&contents))
+            .unwrap_or(false);
+        return Some(!vsync);
+    }
+    None
+}
+
+// Workspaces of the running tiling WM. Other setups have no cheap, common
+// way to ask, so the module is left out there.
+fn get_workspaces() -> Option<(usize, String)> {
+    if !cfg!(target_os = "linux") && !cfg!(target_os = "freebsd") && !cfg!(target_os = "netbsd") {
+        return None;
+    }
+    let wm = known_window_manager(|name| env::var_os(name).is_some(), env::var("XDG_CURRENT_DESKTOP").ok().as_deref())?;
+    match wm.name {
+        "Hyprland" => {
+            let workspaces = shell_command("hyprctl", &["workspaces"])?;
+            let active = shell_command("hyprctl", &["activeworkspace"])?;
+            let count = parse_hyprland_workspaces(&workspaces).len();
+            let active = parse_hyprland_workspaces(&active).into_iter().next()?;
+            Some((count, active))
+        }
+        "sway" => parse_i3_workspaces(&shell_command("swaymsg", &["-t", "get_workspaces"])?),
+        "i3" => parse_i3_workspaces(&shell_command("i3-msg", &["-t", "get_workspaces"])?),
+        _ => None,
+    }
+}
+
+// Names from `hyprctl workspaces`/`activeworkspace`, whose blocks start
+// with "workspace ID 2 (web) on monitor DP-1:"
+fn parse_hyprland_workspaces(output: &str) -> Vec<String> {
+    output.lines()
+        .filter(|line| line.starts_with("workspace ID "))
+        .filter_map(|line| {
+            let (_, rest) = line.split_once('(')?;
+            let (name, _) = rest.rsplit_once(") on ")?;
+            Some(name.to_string())
+        })
+        .collect()
+}
+
+// i3 and sway answer get_workspaces with the same JSON: an array with one
+// object per workspace, each with a "name" and a "focused" flag. Objects
+// nest ("rect"), so workspaces are told apart by brace depth.
+fn parse_i3_workspaces(json: &str) -> Option<(usize, String)> {
+    let workspaces = json_objects(json);
+    let active = workspaces.iter().find(|workspace| json_value(workspace, "focused") == Some("true"))?;
+    let name = json_string(json_value(active, "name")?)?;
+    Some((workspaces.len(), name))
+}
+
+// Windows keyboard layout IDs (the part of an input method tip after the
+// colon) of common layouts, for naming them next to the language
+const WINDOWS_KEYBOARD_LAYOUTS: &[(&str, &str)] = &[
+    ("00000409", "US"),
+    ("00010409", "Dvorak"),
+    ("00020409", "US International"),
+    ("00000809", "UK"),
+    ("00000407", "German"),
+    ("0000040C", "French"),
+    ("0000080C", "Belgian French"),
+    ("0000040A", "Spanish"),
+    ("00000410", "Italian"),
+    ("00000816", "Portuguese"),
+    ("00000416", "Portuguese (Brazil ABNT)"),
+    ("00000419", "Russian"),
+    ("00000411", "Japanese"),
+];
+
+// The keyboard layouts in use: from Hyprland or sway when running them,
+// from the X server on X11, and from localectl's system default otherwise.
+// Windows gives the first language and its layout ("en-US (Dvorak)").
+fn get_keyboard_layout() -> Option<String> {
+    if cfg!(target_os = "windows") {
+        let output = powershell_command(
+            "$lang = (Get-WinUserLanguageList)[0]; $lang.LanguageTag + [char]9 + $lang.InputMethodTips[0]"
+        )?;
+        return Some(format_windows_keyboard(&output));
+    }
+    if !cfg!(target_os = "linux") && !cfg!(target_os = "freebsd") && !cfg!(target_os = "netbsd") {
+        return None;
+    }
+    let wm = known_window_manager(|name| env::var_os(name).is_some(), env::var("XDG_CURRENT_DESKTOP").ok().as_deref());
+    let from_compositor = match wm.map(|wm| wm.name) {
+        Some("Hyprland") => shell_command("hyprctl", &["devices"]).and_then(|output| parse_hyprctl_keyboard(&output)),
+        Some("sway") => shell_command("swaymsg", &["-t", "get_inputs"]).and_then(|output| parse_sway_keyboard(&output)),
+        _ => None,
+    };
+    from_compositor
+        .or_else(|| {
+            // Under Wayland, setxkbmap only sees XWayland's idea of the layout
+            if env::var_os("WAYLAND_DISPLAY").is_some() || env::var_os("DISPLAY").is_none() {
+                return None;
+            }
+            parse_xkb_settings(&shell_command("setxkbmap", &["-query"])?, "layout:", "variant:")
+        })
+        .or_else(|| parse_xkb_settings(&shell_command("localectl", &["status"])?, "X11 Layout:", "X11 Variant:"))
+}
+
+// Pulls the comma-separated layouts and variants out of `setxkbmap -query`
+// ("layout:     us,de") or `localectl status` ("X11 Layout: us,de")
+fn parse_xkb_settings(output: &str, layout_key: &str, variant_key: &str) -> Option<String> {
+    let field = |key: &str| {
+        output.lines()
+            .find_map(|line| line.trim().strip_prefix(key))
+            .map(str::trim)
+            .unwrap_or("")
+    };
+    format_keyboard_layouts(field(layout_key), field(variant_key))
+}
+
+// Layouts with their variants, if any: "us,de" and "dvorak," give
+// "us (dvorak), de"
+fn format_keyboard_layouts(layouts: &str, variants: &str) -> Option<String> {
+    let mut variants = variants.split(',');
+    let formatted: Vec<String> = layouts.split(',')
+        .map(str::trim)
+        .filter(|layout| !layout.is_empty())
+        .map(|layout| match variants.next().map(str::trim).filter(|variant| !variant.is_empty()) {
+            Some(variant) => format!("{} ({})", layout, variant),
+            None => layout.to_string(),
+        })
+        .collect();
+    (!formatted.is_empty()).then(|| formatted.join(", "))
+}
+
+// The main keyboard's `rules:` line in `hyprctl devices`, which reads
+// `rules: r "", m "", l "us,de", v "dvorak,", o ""`; the first keyboard
+// when none is marked main
+fn parse_hyprctl_keyboard(output: &str) -> Option<String> {
+    let quoted = |rules: &str, key: &str| -> String {
+        rules.split(", ")
+            .find_map(|part| part.trim().strip_prefix(key))
+            .map(|value| value.trim().trim_matches('"').to_string())
+            .unwrap_or_default()
+    };
+    let mut first = None;
+    let mut current = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(rules) = line.strip_prefix("rules:") {
+            current = format_keyboard_layouts(&quoted(rules, "l "), &quoted(rules, "v "));
+            first = first.or(current.clone());
+        } else if line == "main: yes" && current.is_some() {
+            return current;
+        }
+    }
+    first
+}
+
+// The active layout of the first keyboard in `swaymsg -t get_inputs`. sway
+// only reports its description ("English (Dvorak)"), not the XKB names.
+fn parse_sway_keyboard(json: &str) -> Option<String> {
+    json_objects(json)
+        .into_iter()
+        .filter(|input| json_value(input, "type") == Some("\"keyboard\""))
+        .find_map(|input| json_value(input, "xkb_active_layout_name"))
+        .and_then(json_string)
+        .filter(|name| !name.is_empty())
+}
+
+// "en-US\t0409:00010409" as "en-US (Dvorak)"; unknown layouts leave just
+// the language
+fn format_windows_keyboard(output: &str) -> String {
+    let (language, tip) = output.split_once('\t').unwrap_or((output, ""));
+    let layout = tip.split_once(':').map_or("", |(_, layout)| layout.trim());
+    match WINDOWS_KEYBOARD_LAYOUTS.iter().find(|(id, _)| id.eq_ignore_ascii_case(layout)) {
+        Some((_, name)) => format!("{} ({})", language.trim(), name),
+        None => language.trim().to_string(),
+    }
+}
+
+// The outermost objects in `json`, such as the elements of an array of
+// objects, told apart by brace depth (braces in strings don't count)
+fn json_objects(json: &str) -> Vec<&str> {
+    let mut objects = Vec::new();
+    let (mut depth, mut start, mut in_string, mut escaped) = (0, 0, false, false);
+    for (index, c) in json.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => {
+                if depth == 0 {
+                    start = index;
+                }
+                depth += 1;
+            }
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    objects.push(&json[start..=index]);
+                }
+            }
+            _ => {}
+        }
+    }
+    objects
+}
+
+// The raw value of the first `"key": value` in `json` (strings keep their
+// quotes and escapes; see `json_string`). Only good for scalars, which is
+// all the callers need.
+fn json_value<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let (_, rest) = json.split_once(&format!("\"{}\"", key))?;
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+    if let Some(string) = rest.strip_prefix('"') {
+        let mut escaped = false;
+        let (end, _) = string.char_indices().find(|&(_, c)| {
+            let closes = c == '"' && !escaped;
+            escaped = c == '\\' && !escaped;
+            closes
+        })?;
+        return Some(&rest[..end + 2]);
+    }
+    let end = rest.find([',', '}']).unwrap_or(rest.len());
+    Some(rest[..end].trim_end())
+}
+
+// The text of a quoted JSON string from `json_value`, escapes undone.
+// None for anything that isn't a string.
+fn json_string(raw: &str) -> Option<String> {
+    let mut chars = raw.strip_prefix('"')?.strip_suffix('"')?.chars();
+    let mut text = String::new();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => text.push('\n'),
+            't' => text.push('\t'),
+            'r' => text.push('\r'),
+            'u' => {
+                let code: String = chars.by_ref().take(4).collect();
+                text.push(u32::from_str_radix(&code, 16).ok().and_then(char::from_u32).unwrap_or('\u{fffd}'));
+            }
+            // \" \\ \/ stand for themselves
+            other => text.push(other),
+        }
+    }
+    Some(text)
+}
+
+// `hyprctl getoption` prints the value with its type, as "int: 1" on older
+// releases and "bool: true" on newer ones
+fn parse_hyprctl_bool(output: &str) -> Option<bool> {
+    output.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(kind, _)| matches!(kind.trim(), "int" | "bool"))
+        .and_then(|(_, value)| match value.trim() {
+            "1" | "true" => Some(true),
+            "0" | "false" => Some(false),
+            _ => None,
+        })
+}
+
+// Reads `vsync = true;` out of a picom config
+fn parse_picom_vsync(contents: &str) -> Option<bool> {
+    contents.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "vsync")
+        .and_then(|(_, value)| match value.trim().trim_end_matches(';').trim() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        })
+}
+
+fn get_wm_theme() -> String {
+    if cfg!(target_os = "windows") {
+        powershell_command(
+            "try { \
+                $theme = Get-ItemProperty -Path 'HKCU:\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize' -Name 'SystemUsesLightTheme' -ErrorAction Stop; \
+                $appTheme = Get-ItemProperty -Path 'HKCU:\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize' -Name 'AppsUseLightTheme' -ErrorAction Stop; \
+                $systemMode = if ($theme.SystemUsesLightTheme -eq 0) {'Dark'} else {'Light'}; \
+                $appMode = if ($appTheme.AppsUseLightTheme -eq 0) {'Dark'} else {'Light'}; \
+                'Custom - Blue (System: {0}, Apps: {1})' -f $systemMode, $appMode \
+            } catch { 'Custom - Blue' }"
+        ).unwrap_or_else(|| "unknown".to_string())
+    } else {
+        "unknown".to_string()
+    }
+}
+
+fn get_icons() -> String {
+    "".to_string()
+}
+
+fn get_font() -> String {
+    if cfg!(target_os = "windows") {
+        "Segoe UI (12pt) [Caption / Menu / Message / Status]".to_string()
+    } else {
+        "unknown".to_string()
+    }
+}
+
+fn get_cursor() -> String {
+    if cfg!(target_os = "windows") {
+        "Windows Default (32px)".to_string()
+    } else {
+        "unknown".to_string()
+    }
+}
+
+fn get_terminal() -> String {
+    env::var("TERM_PROGRAM")
+        .or_else(|_| env::var("TERMINAL_EMULATOR"))
+        .ok()
+        .or_else(default_terminal)
+        .unwrap_or_else(|| {
+            if cfg!(target_os = "windows") {
+                "Windows Terminal".to_string()
+            } else {
+                "unknown".to_string()
+            }
+        })
+}
+
+// The terminal the user has set up as their default, for when we can't tell
+// which one we're running in (e.g. over SSH, or from a cron job). Checks
+// $TERMINAL (as read by i3-sensible-terminal and friends), then
+// Debian's x-terminal-emulator alternative, then the xdg-mime handler.
+fn default_terminal() -> Option<String> {
+    if !cfg!(unix) || cfg!(target_os = "macos") {
+        return None;
+    }
+    env::var("TERMINAL")
+        .ok()
+        .filter(|terminal| !terminal.trim().is_empty())
+        .and_then(|terminal| terminal_from_path(&terminal))
+        .or_else(|| {
+            fs::canonicalize("/usr/bin/x-terminal-emulator")
+                .ok()
+                .and_then(|path| terminal_from_path(&path.to_string_lossy()))
+        })
+        .or_else(|| {
+            shell_command("xdg-mime", &["query", "default", "x-scheme-handler/terminal"])
+                .and_then(|desktop_file| terminal_from_desktop_file(&desktop_file))
+        })
+}
+
+// "/usr/bin/gnome-terminal.wrapper" -> "gnome-terminal"; $TERMINAL may
+// carry arguments too ("kitty --single-instance")
+fn terminal_from_path(path: &str) -> Option<String> {
+    let program = path.split_whitespace().next()?;
+    let name = Path::new(program).file_name()?.to_string_lossy();
+    let name = name.strip_suffix(".wrapper").unwrap_or(&name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+// "kitty.desktop" -> "kitty", "org.wezfurlong.wezterm.desktop" -> "wezterm"
+fn terminal_from_desktop_file(desktop_file: &str) -> Option<String> {
+    let id = desktop_file.trim().strip_suffix(".desktop")?;
+    let name = id.rsplit('.').next()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+// Reports the terminal's dimensions as "COLSxROWS". Omitted when stdout is
+// redirected, since the size of whatever we're piped into is meaningless.
+fn get_terminal_size() -> Option<String> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_dimensions().map(|(columns, rows)| format!("{}x{}", columns, rows))
+}
+
+#[cfg(unix)]
+fn terminal_dimensions() -> Option<(u16, u16)> {
+    // SAFETY: TIOCGWINSZ only writes a `winsize` into the pointer we pass
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    (result == 0 && size.ws_col > 0).then_some((size.ws_col, size.ws_row))
+}
+
+#[cfg(not(unix))]
+fn terminal_dimensions() -> Option<(u16, u16)> {
+    let output = powershell_command("$size = $Host.UI.RawUI.WindowSize; '{0} {1}' -f $size.Width, $size.Height")?;
+    let (columns, rows) = output.split_once(' ')?;
+    Some((columns.parse().ok()?, rows.parse().ok()?)).filter(|(columns, _)| *columns > 0)
+}
+
+const HWMON_DIR: &str = "/sys/class/hwmon";
+
+// hwmon drivers for CPU sensors, in the order they're preferred when
+// `cpu_temp_sensor` isn't set
+const CPU_SENSOR_CHIPS: &[&str] = &["k10temp", "zenpower", "coretemp", "cpu_thermal", "soc_thermal"];
+
+// Labels of the reading that stands for the whole package, best first.
+// k10temp's Tctl carries a fan-control offset on some Ryzens; Tdie doesn't.
+const CPU_PACKAGE_LABELS: &[&str] = &["Package id 0", "Tdie", "Tctl"];
+
+// One temperature input of an hwmon chip
+#[derive(Debug, PartialEq)]
+struct Sensor {
+    // The driver's `name`, e.g. "k10temp"
+    chip: String,
+    // `tempN_label` when the driver has one, else "tempN"
+    label: String,
+    celsius: f64,
+}
+
+// Every temperature reading under `hwmon_dir`, by chip then input number
+fn read_sensors(hwmon_dir: &Path) -> Vec<Sensor> {
+    let Ok(entries) = fs::read_dir(hwmon_dir) else {
+        return Vec::new();
+    };
+    let mut chips: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    chips.sort();
+    
+    let mut sensors = Vec::new();
+    for chip_dir in chips {
+        let Ok(chip) = read_file(chip_dir.join("name")) else {
+            continue;
+        };
+        let Ok(files) = fs::read_dir(&chip_dir) else {
+            continue;
+        };
+        let mut inputs: Vec<u32> = files.flatten()
+            .filter_map(|file| file.file_name().to_str()?.strip_prefix("temp")?.strip_suffix("_input")?.parse().ok())
+            .collect();
+        inputs.sort_unstable();
+        for n in inputs {
+            let read = |file: String| read_file(chip_dir.join(file)).ok().map(|value| value.trim().to_string());
+            let Some(millidegrees) = read(format!("temp{}_input", n)).and_then(|value| value.parse::<i64>().ok()) else {
+                continue;
+            };
+            sensors.push(Sensor {
+                chip: chip.trim().to_string(),
+                label: read(format!("temp{}_label", n)).unwrap_or_else(|| format!("temp{}", n)),
+                celsius: millidegrees as f64 / 1000.0,
+            });
+        }
+    }
+    sensors
+}
+
+// The CPU temperature from the hwmon sensors (Linux; other platforms have
+// no hwmon). `chip` names the driver to read from; otherwise the first
+// known CPU driver present is used.
+fn get_cpu_temp(chip: Option<&str>) -> Option<f64> {
+    pick_cpu_temp(&read_sensors(Path::new(HWMON_DIR)), chip).map(|sensor| sensor.celsius)
+}
+
+// Within the chosen chip, the package reading if it reports one, else its
+// first input
+fn pick_cpu_temp<'a>(sensors: &'a [Sensor], chip: Option<&str>) -> Option<&'a Sensor> {
+    let chip = match chip {
+        Some(chip) => chip,
+        None => CPU_SENSOR_CHIPS.iter().find(|chip| sensors.iter().any(|sensor| sensor.chip == **chip))?,
+    };
+    let readings: Vec<&Sensor> = sensors.iter().filter(|sensor| sensor.chip == chip).collect();
+    CPU_PACKAGE_LABELS.iter()
+        .find_map(|label| readings.iter().find(|sensor| sensor.label == *label))
+        .or_else(|| readings.first())
+        .copied()
+}
+
+// `--list-sensors`: one "chip  label  reading" row per input, in columns
+fn format_sensor_list(sensors: &[Sensor]) -> String {
+    let chip_width = sensors.iter().map(|sensor| sensor.chip.len()).max().unwrap_or(0);
+    let label_width = sensors.iter().map(|sensor| sensor.label.len()).max().unwrap_or(0);
+    sensors.iter()
+        .map(|sensor| {
+            format!(
+                "{:chip_width$}  {:label_width$}  {:.1}°C\n",
+                sensor.chip,
+                sensor.label,
+                sensor.celsius,
+                chip_width = chip_width,
+                label_width = label_width,
+            )
+        })
+        .collect()
+}
+
+// One cache as described by sysfs (or WMI). The same cache shows up under
+// every CPU sharing it, which `shared_cpus` tells apart.
+struct CpuCache {
+    level: u8,
+    kind: String,
+    size_kib: u64,
+    shared_cpus: String,
+}
+
+// Total cache per level across all cores, e.g. "L1 512 KiB, L2 8 MiB, L3 32 MiB"
+fn get_cpu_cache() -> Option<String> {
+    let mut caches = Vec::new();
+    if cfg!(target_os = "linux") {
+        let cpus = fs::read_dir("/sys/devices/system/cpu").ok()?;
+        for cpu in cpus.flatten() {
+            let name = cpu.file_name();
+            let name = name.to_string_lossy();
+            if !name.strip_prefix("cpu").is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())) {
+                continue;
+            }
+            let Ok(indexes) = fs::read_dir(cpu.path().join("cache")) else {
+                continue;
+            };
+            for index in indexes.flatten() {
+                let read = |file: &str| read_file(index.path().join(file)).ok().map(|value| value.trim().to_string());
+                let (Some(level), Some(kind), Some(size)) = (read("level"), read("type"), read("size")) else {
+                    continue;
+                };
+                if let (Ok(level), Some(size_kib)) = (level.parse(), parse_cache_size(&size)) {
+                    let shared_cpus = read("shared_cpu_list").unwrap_or_else(|| name.to_string());
+                    caches.push(CpuCache { level, kind, size_kib, shared_cpus });
+                }
+            }
+        }
+    } else if cfg!(target_os = "windows") {
+        // Levels 3, 4 and 5 are primary, secondary and tertiary (L1-L3);
+        // each row is already a whole cache
+        let output = powershell_command(
+            "Get-CimInstance -ClassName Win32_CacheMemory | ForEach-Object { '{0} {1} {2}' -f $_.Level, $_.InstalledSize, $_.DeviceID }"
+        )?;
+        for line in output.lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(level), Some(size), Some(id)) = (fields.next(), fields.next(), fields.next()) else {
+                continue;
+            };
+            if let (Ok(level @ 3..=5), Ok(size_kib)) = (level.parse::<u8>(), size.parse()) {
+                caches.push(CpuCache { level: level - 2, kind: "Unified".to_string(), size_kib, shared_cpus: id.to_string() });
+            }
+        }
+    }
+    format_cpu_caches(&caches)
+}
+
+// sysfs cache sizes look like "48K" or "32768K"
+fn parse_cache_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    if let Some(kib) = size.strip_suffix('K') {
+        kib.parse().ok()
+    } else if let Some(mib) = size.strip_suffix('M') {
+        mib.parse::<u64>().ok().map(|mib| mib * 1024)
+    } else {
+        size.parse::<u64>().ok().map(|bytes| bytes / 1024)
+    }
+}
+
+// Sums each level, counting every shared cache once. L1's data and
+// instruction caches are added together.
+fn format_cpu_caches(caches: &[CpuCache]) -> Option<String> {
+    let mut seen = HashSet::new();
+    let mut totals: BTreeMap<u8, u64> = BTreeMap::new();
+    for cache in caches {
+        if seen.insert((cache.level, cache.kind.as_str(), cache.shared_cpus.as_str())) {
+            *totals.entry(cache.level).or_default() += cache.size_kib;
+        }
+    }
+    if totals.is_empty() {
+        return None;
+    }
+    Some(totals.iter()
+        .map(|(level, &kib)| {
+            let size = if kib >= 1024 {
+                let mib = format!("{:.2}", kib as f64 / 1024.0);
+                format!("{} MiB", mib.trim_end_matches('0').trim_end_matches('.'))
+            } else {
+                format!("{} KiB", kib)
+            };
+            format!("L{} {}", level, size)
+        })
+        .collect::<Vec<_>>()
+        .join(", "))
+}
+
+pub fn get_cpu_info(show_features: bool) -> String {
+    if cfg!(target_os = "linux") {
+        if let Ok(cpuinfo) = read_file("/proc/cpuinfo") {
+            if let Some(cpu_name) = parse_cpu_model(&cpuinfo) {
+                let mut cpu = format!("{} ({})", cpu_name, num_cpus::get());
+                if show_features {
+                    let features = notable_cpu_features(parse_cpu_flags(&cpuinfo));
+                    if !features.is_empty() {
+                        cpu.push_str(&format!(" [{}]", features.join(", ")));
+                    }
+                }
+                return cpu;
+            }
+        }
+    } else if cfg!(target_os = "windows") {
+        if let Some(output) = powershell_command(
+            "$cpu = Get-CimInstance -ClassName Win32_Processor | Select-Object -First 1; \
+             '{0} ({1}) @ {2:F2} GHz' -f $cpu.Name, $cpu.NumberOfLogicalProcessors, ($cpu.MaxClockSpeed / 1000)"
+        ) {
+            return output;
+        }
+        
+        // Without PowerShell, the processor description is still in the environment
+        if let Ok(identifier) = env::var("PROCESSOR_IDENTIFIER") {
+            return format!("{} ({})", identifier, num_cpus::get());
+        }
+    } else if cfg!(target_os = "freebsd") || cfg!(target_os = "netbsd") {
+        if let Some(model) = sysctl("hw.model") {
+            let threads = sysctl("hw.ncpu").unwrap_or_else(|| num_cpus::get().to_string());
+            return format!("{} ({})", model, threads);
+        }
+    }
+    
+    format!("Unknown ({} cores)", num_cpus::get())
+}
+
+// Extracts the first `model name` entry from /proc/cpuinfo
+pub fn parse_cpu_model(cpuinfo: &str) -> Option<String> {
+    cpuinfo.lines()
+        .filter(|line| line.starts_with("model name"))
+        .find_map(|line| line.split_once(':'))
+        .map(|(_, name)| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+// Returns the feature flags of the first CPU: `flags` on x86, `Features` on ARM
+pub fn parse_cpu_flags(cpuinfo: &str) -> &str {
+    cpuinfo.lines()
+        .filter(|line| line.starts_with("flags") || line.starts_with("Features"))
+        .find_map(|line| line.split_once(':'))
+        .map(|(_, flags)| flags.trim())
+        .unwrap_or("")
+}
+
+// Picks out the vector extensions enthusiasts care about. Each group is
+// ordered best-first and only the best supported entry is reported, since
+// e.g. every AVX-512 CPU also supports AVX2.
+pub fn notable_cpu_features(flags: &str) -> Vec<&'static str> {
+    const GROUPS: &[&[(&str, &str)]] = &[
+        &[("avx512f", "AVX-512"), ("avx2", "AVX2")],
+        &[("sve2", "SVE2"), ("sve", "SVE")],
+    ];
+    
+    let flags: Vec<&str> = flags.split_whitespace().collect();
+    GROUPS.iter()
+        .filter_map(|group| {
+            group.iter()
+                .find(|(flag, _)| flags.contains(flag))
+                .map(|(_, name)| *name)
+        })
+        .collect()
+}
+
+// Every GPU found, or "Unknown GPU" when detection fails. The name filters
+// are applied when rendering (see `gpu_lines`).
+pub fn get_gpu_info() -> Vec<String> {
+    let gpus = collect_gpus();
+    if gpus.is_empty() {
+        return vec!["Unknown GPU".to_string()];
+    }
+    gpus
+}
+
+// A GPU is kept when it matches `include` (if set) and nothing in
+// `exclude`; both hold case-insensitive substrings of the name
+fn gpu_matches(gpu: &str, include: &[String], exclude: &[String]) -> bool {
+    let gpu = gpu.to_ascii_lowercase();
+    let matches = |pattern: &String| gpu.contains(&pattern.to_ascii_lowercase());
+    (include.is_empty() || include.iter().any(matches)) && !exclude.iter().any(matches)
+}
+
+fn collect_gpus() -> Vec<String> {
+    if cfg!(target_os = "windows") {
+        if let Some(output) = powershell_command(
+            "Get-CimInstance -ClassName Win32_VideoController | Where-Object {$_.Name -ne $null} | ForEach-Object { \
+                $memGB = if ($_.AdapterRAM -gt 0) { [math]::Round($_.AdapterRAM / 1GB, 2) } else { 0 }; \
+                $memStr = if ($memGB -eq 0) { 'Unknown' } else { '{0:F2} GiB' -f $memGB }; \
+                $type = if ($_.AdapterRAM -lt 2GB -or $_.Name -like '*Intel*') {'[Integrated]'} else {'[Discrete]'}; \
+                ('{0} ({1}) {2}' + [char]9 + '{3}') -f $_.Name, $memStr, $type, $_.DriverVersion \
+            }"
+        ) {
+            return output.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(format_windows_gpu)
+                .collect();
+        }
+        // Without PowerShell, each display adapter's driver key names it
+        if let Some(output) = shell_command("reg", &["query", WINDOWS_DISPLAY_CLASS_KEY, "/s", "/v", "DriverDesc"]) {
+            let mut gpus: Vec<String> = Vec::new();
+            for (_, name) in parse_reg_values(&output) {
+                if !gpus.iter().any(|gpu| gpu == name) {
+                    gpus.push(name.to_string());
+                }
+            }
+            return gpus;
+        }
+    } else if cfg!(target_os = "macos") {
+        if let Some(output) = shell_command("system_profiler", &["SPDisplaysDataType"]) {
+            return parse_macos_gpus(&output);
+        }
+    }
+    
+    Vec::new()
+}
+
+// The display adapter device class, one numbered subkey per adapter
+const WINDOWS_DISPLAY_CLASS_KEY: &str = r"HKLM\SYSTEM\CurrentControlSet\Control\Class\{4d36e968-e325-11ce-bfc1-08002be10318}";
+
+// The `name    REG_SZ    data` lines of `reg query` output, as name and
+// data; key names and blank lines are skipped
+fn parse_reg_values(output: &str) -> Vec<(&str, &str)> {
+    output.lines()
+        .filter_map(|line| {
+            let (name, rest) = line.trim().split_once("    REG_")?;
+            let (_, data) = rest.split_once("    ").unwrap_or((rest, ""));
+            Some((name.trim(), data.trim()))
+        })
+        .collect()
+}
+
+// "Windows 11 Pro" from the CurrentVersion key. ProductName still says
+// Windows 10 on Windows 11, which only the build number (22000 and up)
+// gives away.
+fn windows_product_name(values: &[(&str, &str)]) -> Option<String> {
+    let value = |wanted: &str| values.iter().find(|(name, _)| *name == wanted).map(|(_, data)| *data);
+    let name = value("ProductName")?.trim_start_matches("Microsoft ");
+    let build: u32 = value("CurrentBuild").and_then(|build| build.parse().ok()).unwrap_or(0);
+    Some(if build >= 22000 { name.replacen("Windows 10", "Windows 11", 1) } else { name.to_string() })
+}
+
+// Turns "<name> (<memory>) <type>\t<DriverVersion>" into the GPU line,
+// appending the driver version when WMI has one
+fn format_windows_gpu(line: &str) -> String {
+    let (gpu, driver) = line.split_once('\t').unwrap_or((line, ""));
+    let (gpu, driver) = (gpu.trim(), driver.trim());
+    if driver.is_empty() {
+        return gpu.to_string();
+    }
+    let driver = if gpu.to_ascii_uppercase().contains("NVIDIA") {
+        nvidia_driver_version(driver).unwrap_or_else(|| driver.to_string())
+    } else {
+        driver.to_string()
+    };
+    format!("{} - {}", gpu, driver)
+}
+
+// NVIDIA's own version number is hidden in the last five digits of the
+// Windows driver version: 31.0.15.5222 is driver 552.22
+fn nvidia_driver_version(version: &str) -> Option<String> {
+    let digits: String = version.split('.').skip(2).collect();
+    if digits.len() < 5 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let tail = &digits[digits.len() - 5..];
+    Some(format!("{}.{}", &tail[..3], &tail[3..]))
+}
+
+// Which displays each GPU drives, from the connectors listed under its DRM
+// card: "card1-DP-1" belongs to card1. GPUs driving nothing (render-only
+// GPUs on PRIME laptops) say so rather than vanishing. Linux only; the
+// directory doesn't exist elsewhere.
+fn get_gpu_displays(drm_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(drm_dir) else {
+        return Vec::new();
+    };
+    let mut cards: Vec<(u32, String)> = entries.flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let number = name.strip_prefix("card")?.parse().ok()?;
+            Some((number, name))
+        })
+        .collect();
+    cards.sort();
+    let connectors = drm_connected_connectors(drm_dir).unwrap_or_default();
+    
+    cards.iter()
+        .enumerate()
+        .map(|(index, (_, card))| {
+            let vendor = read_file(drm_dir.join(card).join("device").join("vendor"))
+                .map(|vendor| pci_vendor_name(vendor.trim()))
+                .unwrap_or_else(|_| "unknown".to_string());
+            let displays: Vec<&str> = connectors.iter()
+                .filter_map(|path| path.file_name()?.to_str()?.strip_prefix(card.as_str())?.strip_prefix('-'))
+                .collect();
+            let displays = if displays.is_empty() { "no displays".to_string() } else { displays.join(", ") };
+            format!("GPU {} ({}): {}", index + 1, vendor, displays)
+        })
+        .collect()
+}
+
+// Vendor name for a PCI vendor ID like "0x10de"; other IDs as they are
+fn pci_vendor_name(id: &str) -> String {
+    match id.to_ascii_lowercase().as_str() {
+        "0x10de" => "NVIDIA",
+        "0x1002" => "AMD",
+        "0x8086" => "Intel",
+        "0x1af4" => "virtio",
+        "0x15ad" => "VMware",
+        _ => id,
+    }
+    .to_string()
+}
+
+// The GPU the session renders on, which on PRIME/Optimus laptops isn't
+// obvious from the list of GPUs. glxinfo honours DRI_PRIME and
+// __GLX_VENDOR_LIBRARY_NAME just like the apps it stands in for.
+fn get_active_gpu() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        shell_command("glxinfo", &["-B"])
+            .and_then(|output| parse_glx_renderer(&output))
+    } else {
+        None
+    }
+}
+
+// Extracts the GPU name from glxinfo's "OpenGL renderer string", dropping
+// the bus/instruction-set suffix NVIDIA appends ("/PCIe/SSE2") and the
+// driver details Mesa puts in parentheses
+pub fn parse_glx_renderer(output: &str) -> Option<String> {
+    let renderer = output.lines()
+        .find_map(|line| line.trim().strip_prefix("OpenGL renderer string:"))?
+        .trim();
+    // Cut at whichever suffix comes first; a bare '/' may be part of the
+    // name itself, as in "Radeon RX 6800/6800 XT"
+    let end = ["/PCI", "/AGP", " ("].iter()
+        .filter_map(|suffix| renderer.find(suffix))
+        .min()
+        .unwrap_or(renderer.len());
+    let renderer = &renderer[..end];
+    Some(renderer.trim().to_string()).filter(|name| !name.is_empty())
+}
+
+// Current core and memory clocks of the first GPU that reports them
+fn get_gpu_clock() -> Option<String> {
+    if !cfg!(target_os = "linux") && !cfg!(target_os = "windows") {
+        return None;
+    }
+    if let Some((core, memory)) = shell_command(
+        "nvidia-smi",
+        &["--query-gpu=clocks.gr,clocks.mem", "--format=csv,noheader,nounits"],
+    )
+    .and_then(|output| parse_nvidia_smi_clocks(&output))
+    {
+        return Some(format_gpu_clock(core, Some(memory)));
+    }
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    
+    // amdgpu lists its DPM states with the active one starred
+    let mut cards: Vec<_> = fs::read_dir("/sys/class/drm").ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("device"))
+        .filter(|device| device.join("pp_dpm_sclk").exists())
+        .collect();
+    cards.sort();
+    cards.iter().find_map(|device| {
+        let core = read_file(device.join("pp_dpm_sclk")).ok()
+            .and_then(|states| parse_dpm_clock(&states))?;
+        let memory = read_file(device.join("pp_dpm_mclk")).ok()
+            .and_then(|states| parse_dpm_clock(&states));
+        Some(format_gpu_clock(core, memory))
+    })
+}
+
+// First line of `nvidia-smi --query-gpu=clocks.gr,clocks.mem
+// --format=csv,noheader,nounits`, e.g. "2520, 10501"
+fn parse_nvidia_smi_clocks(output: &str) -> Option<(u32, u32)> {
+    let (core, memory) = output.lines().next()?.split_once(',')?;
+    Some((core.trim().parse().ok()?, memory.trim().parse().ok()?))
+}
+
+// Picks the active state out of pp_dpm_sclk/pp_dpm_mclk:
+// "0: 500Mhz\n1: 2100Mhz *\n" gives 2100
+fn parse_dpm_clock(states: &str) -> Option<u32> {
+    states.lines()
+        .find(|line| line.trim_end().ends_with('*'))
+        .and_then(|line| line.split_once(':'))
+        .and_then(|(_, clock)| {
+            let clock = clock.trim().trim_end_matches('*').trim();
+            clock.to_ascii_lowercase().strip_suffix("mhz")?.trim().parse().ok()
+        })
+}
+
+fn format_gpu_clock(core: u32, memory: Option<u32>) -> String {
+    match memory {
+        Some(memory) => format!("{} MHz core / {} MHz mem", core, memory),
+        None => format!("{} MHz core", core),
+    }
+}
+
+// VRAM use of the first GPU that reports it: NVIDIA through nvidia-smi,
+// AMD through amdgpu's sysfs counters
+fn get_gpu_vram_used() -> Option<(u64, u64)> {
+    if !cfg!(target_os = "linux") && !cfg!(target_os = "windows") {
+        return None;
+    }
+    if let Some(vram) = shell_command(
+        "nvidia-smi",
+        &["--query-gpu=memory.used,memory.total", "--format=csv,noheader,nounits"],
+    )
+    .and_then(|output| parse_nvidia_smi_memory(&output))
+    {
+        return Some(vram);
+    }
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    
+    let mut cards: Vec<_> = fs::read_dir("/sys/class/drm").ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("device"))
+        .filter(|device| device.join("mem_info_vram_used").exists())
+        .collect();
+    cards.sort();
+    cards.iter().find_map(|device| {
+        let read = |name: &str| read_file(device.join(name)).ok()?.trim().parse::<u64>().ok();
+        Some((read("mem_info_vram_used")?, read("mem_info_vram_total")?)).filter(|&(_, total)| total > 0)
+    })
+}
+
+// First line of `nvidia-smi --query-gpu=memory.used,memory.total
+// --format=csv,noheader,nounits`, in MiB ("3277, 12282"), as bytes
+fn parse_nvidia_smi_memory(output: &str) -> Option<(u64, u64)> {
+    let (used, total) = output.lines().next()?.split_once(',')?;
+    let mib = |value: &str| value.trim().parse::<u64>().ok().map(|value| value * 1024 * 1024);
+    Some((mib(used)?, mib(total)?)).filter(|&(_, total)| total > 0)
+}
+
+// "3.2 GiB / 12 GiB (27%)"
+fn format_vram(used: u64, total: u64) -> String {
+    let gib = |bytes: u64| {
+        let formatted = format!("{:.1}", bytes as f64 / (1024.0 * 1024.0 * 1024.0));
+        formatted.strip_suffix(".0").map(str::to_string).unwrap_or(formatted)
+    };
+    let total_or_one = total.max(1);
+    let percent = (used * 100 + total_or_one / 2) / total_or_one;
+    format!("{} GiB / {} GiB ({}%)", gib(used), gib(total), percent)
+}
+
+// Parses `system_profiler SPDisplaysDataType` into one line per GPU.
+// Apple Silicon reports its GPU core count, which differs between the
+// base/Pro/Max variants, so prefer it over VRAM when present.
+pub fn parse_macos_gpus(output: &str) -> Vec<String> {
+    struct Gpu<'a> {
+        name: &'a str,
+        cores: Option<&'a str>,
+        vram: Option<&'a str>,
+        integrated: bool,
+    }
+    
+    let mut gpus: Vec<Gpu> = Vec::new();
+    
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        
+        if key == "Chipset Model" {
+            gpus.push(Gpu { name: value, cores: None, vram: None, integrated: false });
+            continue;
+        }
+        
+        let Some(gpu) = gpus.last_mut() else {
+            continue;
+        };
+        match key {
+            "Total Number of Cores" => gpu.cores = Some(value),
+            "Bus" => gpu.integrated = value == "Built-In",
+            key if key.starts_with("VRAM") => gpu.vram = Some(value),
+            _ => {}
+        }
+    }
+    
+    gpus.iter()
+        .map(|gpu| {
+            let detail = match (gpu.cores, gpu.vram) {
+                (Some(cores), _) => format!(" ({} cores)", cores),
+                (None, Some(vram)) => format!(" ({})", vram),
+                (None, None) => String::new(),
+            };
+            let kind = if gpu.integrated { "[Integrated]" } else { "[Discrete]" };
+            format!("{}{} {}", gpu.name, detail, kind)
+        })
+        .collect()
+}
+
+// What sysinfo(2) reports, in bytes and seconds
+struct SysinfoCounts {
+    uptime: u64,
+    total_ram: u64,
+    // Free plus buffers: sysinfo has no page cache figure, so this is lower
+    // than /proc/meminfo's MemAvailable
+    available_ram: u64,
+    total_swap: u64,
+    free_swap: u64,
+}
+
+// Memory and uptime straight from the kernel, for chroots and containers
+// where /proc isn't mounted
+#[cfg(target_os = "linux")]
+fn kernel_sysinfo() -> Option<SysinfoCounts> {
+    // SAFETY: sysinfo only writes the struct we pass
+    let mut info: libc::sysinfo = unsafe { std::mem::zeroed() };
+    if unsafe { libc::sysinfo(&mut info) } != 0 {
+        return None;
+    }
+    let unit = (info.mem_unit as u64).max(1);
+    Some(SysinfoCounts {
+        uptime: info.uptime.max(0) as u64,
+        total_ram: info.totalram as u64 * unit,
+        available_ram: (info.freeram as u64 + info.bufferram as u64) * unit,
+        total_swap: info.totalswap as u64 * unit,
+        free_swap: info.freeswap as u64 * unit,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn kernel_sysinfo() -> Option<SysinfoCounts> {
+    None
+}
+
+// Whether `proc_dir` has procfs mounted on it
+fn proc_mounted(proc_dir: &Path) -> bool {
+    proc_dir.join("self/stat").exists()
+}
+
+// Modules whose Linux collectors read /proc and have nothing to fall back on
+const PROC_MODULES: &[&str] = &["cpu", "load", "root_fs", "kernel_modules", "cmdline", "swappiness"];
+
+// Why so much reads "unknown" in a chroot or minimal container, said once
+// rather than by every collector reading from /proc: the shown modules
+// that came up empty without it. None when /proc is there (or off Linux).
+fn proc_missing_note(info: &SystemInfo, config: &Config, proc_dir: &Path) -> Option<String> {
+    if !cfg!(target_os = "linux") || proc_mounted(proc_dir) {
+        return None;
+    }
+    let shown: Vec<&str> = PROC_MODULES.iter().copied().filter(|module| config.is_shown(module)).collect();
+    let lost = unknown_modules(info, config, &shown);
+    let fallback = "memory, swap and uptime come from sysinfo(2) instead";
+    Some(if lost.is_empty() {
+        format!("/proc is not mounted; {}", fallback)
+    } else {
+        format!("/proc is not mounted, so {} came up unknown; {}", lost.join(", "), fallback)
+    })
+}
+
+pub fn get_memory_info(precision: Precision) -> String {
+    if cfg!(target_os = "linux") {
+        if let Ok(meminfo) = read_file("/proc/meminfo") {
+            let mut mem_data = parse_meminfo(&meminfo);
+            // The kernel counts ZFS's ARC as used, but most of it is handed
+            // back under memory pressure just like the page cache
+            if let Some(reclaimable) = read_file("/proc/spl/kstat/zfs/arcstats")
+                .ok()
+                .and_then(|arcstats| parse_arc_reclaimable(&arcstats))
+            {
+                mem_data.entry("MemAvailable").and_modify(|available| *available += reclaimable);
+            }
+            if let Some(memory) = format_meminfo_usage(&mem_data, precision) {
+                return memory;
+            }
+        }
+        if let Some(counts) = kernel_sysinfo() {
+            return format_usage(counts.total_ram.saturating_sub(counts.available_ram), counts.total_ram, precision);
+        }
+    } else if cfg!(target_os = "windows") {
+        if let Some(output) = powershell_command(
+            "$mem = Get-CimInstance -ClassName Win32_ComputerSystem; \
+             $avail = (Get-Counter '\\Memory\\Available Bytes').CounterSamples[0].CookedValue; \
+             $total = $mem.TotalPhysicalMemory; \
+             $used = $total - $avail; \
+             '{0} {1}' -f [uint64]$used, [uint64]$total"
+        ) {
+            if let Some((used, total)) = parse_byte_pair(&output) {
+                return format_usage(used, total, precision);
+            }
+        }
+        // Without PowerShell, wmic (where Windows still ships it) has both
+        if let Some((used, total)) = shell_command("wmic", &["OS", "get", "FreePhysicalMemory,TotalVisibleMemorySize", "/value"])
+            .and_then(|output| parse_wmic_memory(&output))
+        {
+            return format_usage(used, total, precision);
+        }
+    } else if cfg!(target_os = "freebsd") {
+        // Free and inactive pages can be handed out without swapping,
+        // which is what Linux's MemAvailable approximates
+        let value = |name: &str| sysctl(name).and_then(|value| value.parse::<u64>().ok());
+        if let (Some(total), Some(page_size), Some(free), Some(inactive)) = (
+            value("hw.physmem"),
+            value("hw.pagesize"),
+            value("vm.stats.vm.v_free_count"),
+            value("vm.stats.vm.v_inactive_count"),
+        ) {
+            let available = (free + inactive) * page_size;
+            return format_usage(total.saturating_sub(available), total, precision);
+        }
+    }
+    
+    "unknown".to_string()
+}
+
+// Used and total bytes from wmic's "FreePhysicalMemory=..." and
+// "TotalVisibleMemorySize=..." lines, both in KiB
+fn parse_wmic_memory(output: &str) -> Option<(u64, u64)> {
+    let value = |name: &str| {
+        output.lines()
+            .find_map(|line| line.trim().strip_prefix(name)?.strip_prefix('='))
+            .and_then(|kib| kib.trim().parse::<u64>().ok())
+            .and_then(|kib| kib.checked_mul(1024))
+    };
+    let (free, total) = (value("FreePhysicalMemory")?, value("TotalVisibleMemorySize")?);
+    Some((total.saturating_sub(free), total))
+}
+
+// Parses /proc/meminfo into a map of field name to bytes
+pub fn parse_meminfo(meminfo: &str) -> HashMap<&str, u64> {
+    let mut mem_data = HashMap::new();
+    
+    for line in meminfo.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            if let Some(value_str) = value.split_whitespace().next() {
+                // Convert KB to bytes, skipping values too large to be real
+                if let Some(value) = value_str.parse::<u64>().ok().and_then(|value| value.checked_mul(1024)) {
+                    mem_data.insert(key.trim(), value);
+                }
+            }
+        }
+    }
+    
+    mem_data
+}
+
+// How much of the ZFS ARC could be reclaimed: everything above its
+// minimum size (`c_min`), which the ARC never shrinks below
+pub fn parse_arc_reclaimable(arcstats: &str) -> Option<u64> {
+    let field = |name: &str| -> Option<u64> {
+        arcstats.lines().find_map(|line| {
+            let mut columns = line.split_whitespace();
+            if columns.next()? != name {
+                return None;
+            }
+            // Rows are "<name> <type> <data>"
+            columns.nth(1)?.parse().ok()
+        })
+    };
+    let (size, minimum) = (field("size")?, field("c_min")?);
+    Some(size.saturating_sub(minimum))
+}
+
+pub fn format_meminfo_usage(mem_data: &HashMap<&str, u64>, precision: Precision) -> Option<String> {
+    let (&total, &available) = (mem_data.get("MemTotal")?, mem_data.get("MemAvailable")?);
+    Some(format_usage(total.saturating_sub(available), total, precision))
+}
+
+pub fn format_meminfo_swap(mem_data: &HashMap<&str, u64>, precision: Precision) -> Option<String> {
+    let (&total, &free) = (mem_data.get("SwapTotal")?, mem_data.get("SwapFree")?);
+    if total == 0 {
+        return Some("No swap".to_string());
+    }
+    Some(format_usage(total.saturating_sub(free), total, precision))
+}
+
+// How well the zram devices used as swap compress, as original size over
+// compressed size across all of them. None without zram swap, or before
+// anything has been swapped out.
+fn get_zram_ratio() -> Option<f64> {
+    let swaps = read_file("/proc/swaps").ok()?;
+    let (original, compressed) = swaps.lines()
+        .filter_map(|line| line.split_whitespace().next()?.strip_prefix("/dev/"))
+        .filter(|device| device.starts_with("zram"))
+        .filter_map(|device| read_file(format!("/sys/block/{}/mm_stat", device)).ok())
+        .filter_map(|mm_stat| parse_zram_mm_stat(&mm_stat))
+        .fold((0u64, 0u64), |(original, compressed), (orig, compr)| {
+            (original.saturating_add(orig), compressed.saturating_add(compr))
+        });
+    (compressed > 0).then(|| original as f64 / compressed as f64)
+}
+
+// mm_stat starts with orig_data_size and compr_data_size, in bytes
+fn parse_zram_mm_stat(contents: &str) -> Option<(u64, u64)> {
+    let mut fields = contents.split_whitespace();
+    Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+}
+
+// /sys/module/zswap/parameters/enabled holds "Y" or "N" (older kernels
+// used "1"/"0")
+fn zswap_enabled(contents: &str) -> bool {
+    matches!(contents.trim(), "Y" | "1")
+}
+
+// Formats a used/total pair of byte counts as "X GiB / Y GiB (N%)", or
+// "disabled" when there's nothing to divide by (swap callers say "No swap")
+fn format_usage(used: u64, total: u64, precision: Precision) -> String {
+    if total == 0 {
+        return "disabled".to_string();
+    }
+    let percentage = (used as f64 / total as f64) * 100.0;
+    let percentage = match precision.percentages {
+        PercentRounding::Truncate => percentage.trunc(),
+        PercentRounding::Round => percentage.round(),
+    };
+    format!(
+        "{} / {} ({}%)",
+        format_bytes_gib(used, precision.decimals),
+        format_bytes_gib(total, precision.decimals),
+        percentage as u8,
+    )
+}
+
+// Parses the "<used> <total>" byte counts the PowerShell collectors print
+fn parse_byte_pair(output: &str) -> Option<(u64, u64)> {
+    let mut numbers = output.split_whitespace().map(|number| number.parse().ok());
+    Some((numbers.next()??, numbers.next()??))
+}
+
+// The DIMMs in the machine: slot count and installed module sizes, from
+// SMBIOS. On Linux that takes dmidecode, which needs root to read the
+// tables; without it the module is left out.
+fn get_ram_slots() -> Option<(usize, Vec<u64>)> {
+    if cfg!(target_os = "linux") {
+        shell_command("dmidecode", &["-t", "memory"]).and_then(|output| parse_dmidecode_memory(&output))
+    } else if cfg!(target_os = "windows") {
+        powershell_command(
+            "$slots = (Get-CimInstance -ClassName Win32_PhysicalMemoryArray | Measure-Object -Property MemoryDevices -Sum).Sum; \
+             $sizes = Get-CimInstance -ClassName Win32_PhysicalMemory | ForEach-Object { $_.Capacity }; \
+             '{0} {1}' -f $slots, ($sizes -join ' ')"
+        )
+        .and_then(|output| parse_windows_memory_slots(&output))
+    } else {
+        None
+    }
+}
+
+// Reads the Physical Memory Array (type 16) and Memory Device (type 17)
+// records of `dmidecode -t memory`. The arrays give the slot count; boards
+// that leave it out get one slot per device record.
+fn parse_dmidecode_memory(output: &str) -> Option<(usize, Vec<u64>)> {
+    let (mut declared_slots, mut devices, mut modules) = (0, 0, Vec::new());
+    for record in output.split("\n\n") {
+        let mut lines = record.lines().skip_while(|line| !line.starts_with("Handle "));
+        lines.next();
+        let kind = lines.next().map(str::trim);
+        let field = |name: &str| {
+            record.lines().find_map(|line| line.trim().strip_prefix(name)?.strip_prefix(": "))
+        };
+        match kind {
+            Some("Physical Memory Array") => {
+                declared_slots += field("Number Of Devices").and_then(|count| count.parse::<usize>().ok()).unwrap_or(0);
+            }
+            Some("Memory Device") => {
+                devices += 1;
+                modules.extend(field("Size").and_then(parse_dmidecode_size));
+            }
+            _ => {}
+        }
+    }
+    (devices > 0).then(|| (declared_slots.max(devices), modules))
+}
+
+// "16 GB" or "16384 MB" (dmidecode means binary units); empty slots say
+// "No Module Installed"
+fn parse_dmidecode_size(size: &str) -> Option<u64> {
+    let (number, unit) = size.split_once(' ')?;
+    let number: u64 = number.parse().ok()?;
+    let shift = match unit {
+        "kB" => 10,
+        "MB" => 20,
+        "GB" => 30,
+        "TB" => 40,
+        _ => return None,
+    };
+    (number > 0).then(|| number << shift)
+}
+
+// "<slots> <capacity> <capacity> ..." from the WMI query. Virtual machines
+// may report no slots at all, in which case each module counts as one.
+fn parse_windows_memory_slots(output: &str) -> Option<(usize, Vec<u64>)> {
+    let mut fields = output.split_whitespace();
+    let slots: usize = fields.next()?.parse().unwrap_or(0);
+    let modules: Vec<u64> = fields.filter_map(|capacity| capacity.parse().ok()).filter(|&capacity| capacity > 0).collect();
+    (slots > 0 || !modules.is_empty()).then(|| (slots.max(modules.len()), modules))
+}
+
+// "2/4 used, 32 GiB (2x16 GiB)", with mixed kits listed largest first:
+// "3/4 used, 40 GiB (2x16 GiB + 1x8 GiB)"
+fn format_ram_slots(slots: usize, modules: &[u64]) -> String {
+    let size = |bytes: u64| match bytes % (1 << 30) {
+        0 => format!("{} GiB", bytes >> 30),
+        _ => format!("{} MiB", bytes >> 20),
+    };
+    let mut sizes = modules.to_vec();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    let mut groups: Vec<(usize, u64)> = Vec::new();
+    for bytes in sizes {
+        match groups.last_mut() {
+            Some((count, last)) if *last == bytes => *count += 1,
+            _ => groups.push((1, bytes)),
+        }
+    }
+    let mut line = format!("{}/{} used, {}", modules.len(), slots, size(modules.iter().sum()));
+    if !groups.is_empty() {
+        let kits: Vec<String> = groups.iter().map(|&(count, bytes)| format!("{}x{}", count, size(bytes))).collect();
+        line.push_str(&format!(" ({})", kits.join(" + ")));
+    }
+    line
+}
+
+pub fn get_swap_info(zram_ratio: bool, precision: Precision) -> String {
+    if cfg!(target_os = "linux") {
+        if let Some(mut swap) = read_file("/proc/meminfo")
+            .ok()
+            .and_then(|meminfo| format_meminfo_swap(&parse_meminfo(&meminfo), precision))
+        {
+            // zswap compresses pages on their way to swap, so it only
+            // matters when there's a swap device behind it
+            let zswap = read_file("/sys/module/zswap/parameters/enabled")
+                .is_ok_and(|enabled| zswap_enabled(&enabled));
+            if zswap && swap != "No swap" {
+                swap.push_str(" (zswap active)");
+            }
+            if let Some(ratio) = zram_ratio.then(get_zram_ratio).flatten() {
+                swap.push_str(&format!(" (zram, {:.1}:1)", ratio));
+            }
+            return swap;
+        }
+        match kernel_sysinfo() {
+            Some(counts) if counts.total_swap == 0 => "No swap".to_string(),
+            Some(counts) => format_usage(counts.total_swap.saturating_sub(counts.free_swap), counts.total_swap, precision),
+            None => "unknown".to_string(),
+        }
+    } else if cfg!(target_os = "windows") {
+        // Page file sizes are in MiB
+        powershell_command(
+            "$pf = Get-CimInstance -ClassName Win32_PageFileUsage; \
+             if ($pf) { \
+                 $used = ($pf.CurrentUsage | Measure-Object -Sum).Sum; \
+                 $total = ($pf.AllocatedBaseSize | Measure-Object -Sum).Sum; \
+                 '{0} {1}' -f $used, $total \
+             } else { '0 0' }"
+        )
+            .and_then(|output| parse_byte_pair(&output))
+            .map(|(used, total)| match total {
+                0 => "No swap".to_string(),
+                _ => format_usage(used << 20, total << 20, precision),
+            })
+            .unwrap_or_else(|| "unknown".to_string())
+    } else if cfg!(target_os = "macos") {
+        sysctl("vm.swapusage")
+            .and_then(|output| parse_swapusage(&output))
+            .map(|(used, total)| if total == 0 { "No swap".to_string() } else { format_usage(used, total, precision) })
+            .unwrap_or_else(|| "unknown".to_string())
+    } else {
+        "unknown".to_string()
+    }
+}
+
+// Parses macOS `sysctl vm.swapusage` output, e.g.
+// "total = 2048.00M  used = 512.00M  free = 1536.00M  (encrypted)",
+// into (used, total) bytes
+pub fn parse_swapusage(output: &str) -> Option<(u64, u64)> {
+    let field = |name: &str| -> Option<u64> {
+        let mut tokens = output.split_whitespace();
+        tokens.find(|token| *token == name)?;
+        tokens.next().filter(|token| *token == "=")?;
+        parse_sysctl_size(tokens.next()?)
+    };
+    Some((field("used")?, field("total")?))
+}
+
+// Reads a single value with `sysctl -n` (macOS and the BSDs)
+fn sysctl(name: &str) -> Option<String> {
+    shell_command("sysctl", &["-n", name])
+}
+
+// Windows has no load average (its processor queue length is a snapshot,
+// not an average), so the module is left out there
+fn get_load_average() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        parse_load_average(&read_file("/proc/loadavg").ok()?)
+    } else if cfg!(target_os = "macos") || cfg!(target_os = "freebsd") || cfg!(target_os = "netbsd") {
+        parse_load_average(&sysctl("vm.loadavg")?)
+    } else {
+        None
+    }
+}
+
+// The first three numbers of /proc/loadavg ("0.52 0.61 0.48 1/523 4242")
+// or of sysctl's vm.loadavg ("{ 0.52 0.61 0.48 }", or without the braces
+// on NetBSD)
+fn parse_load_average(contents: &str) -> Option<String> {
+    let averages: Vec<f64> = contents.split_whitespace()
+        .filter(|word| *word != "{")
+        .take(3)
+        .map(|word| word.parse().ok())
+        .collect::<Option<_>>()?;
+    (averages.len() == 3).then(|| {
+        averages.iter().map(|average| format!("{:.2}", average)).collect::<Vec<_>>().join(", ")
+    })
+}
+
+// Parses sizes like "512.00M" or "2.00G" as printed by sysctl
+fn parse_sysctl_size(size: &str) -> Option<u64> {
+    let (number, unit) = size.split_at(size.find(|c: char| c.is_ascii_alphabetic())?);
+    let multiplier = match unit {
+        "K" => 1024.0,
+        "M" => 1024.0 * 1024.0,
+        "G" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    number.parse::<f64>().ok().map(|n| (n * multiplier) as u64)
+}
+
+pub fn get_disk_info(precision: Precision) -> Vec<String> {
+    if cfg!(target_os = "windows") {
+        if let Some(output) = powershell_command(
+            "Get-CimInstance -ClassName Win32_LogicalDisk | Where-Object {$_.DriveType -eq 3} | ForEach-Object { \
+                '{0}|{1}|{2}|{3}' -f $_.DeviceID, ($_.Size - $_.FreeSpace), $_.Size, $_.FileSystem \
+            }"
+        ) {
+            return output.lines()
+                .filter_map(|line| format_windows_disk(line.trim(), precision))
+                .collect();
+        }
+    }
+    
+    vec!["Unknown disk".to_string()]
+}
+
+// Turns one "<drive>|<used>|<size>|<filesystem>" line from the disk query
+// into "Disk (C:): X GiB / Y GiB (N%) - NTFS"
+fn format_windows_disk(line: &str, precision: Precision) -> Option<String> {
+    let mut fields = line.split('|');
+    let drive = fields.next().filter(|drive| !drive.is_empty())?;
+    let (used, size) = (fields.next()?.parse().ok()?, fields.next()?.parse().ok()?);
+    let usage = format_usage(used, size, precision);
+    Some(match fields.next().filter(|filesystem| !filesystem.is_empty() && size > 0) {
+        Some(filesystem) => format!("Disk ({}): {} - {}", drive, usage, filesystem),
+        None => format!("Disk ({}): {}", drive, usage),
+    })
+}
+
+// When the OS was installed, as "YYYY-MM-DD". On Linux and macOS that's
+// the birth time of files created during installation; filesystems that
+// don't record birth times leave it unknown.
+fn get_install_date() -> Option<String> {
+    if cfg!(target_os = "windows") {
+        return powershell_command("(Get-CimInstance -ClassName Win32_OperatingSystem).InstallDate.ToString('yyyy-MM-dd')");
+    }
+    let candidates: &[&str] = if cfg!(target_os = "macos") {
+        &["/var/db/.AppleSetupDone"]
+    } else if cfg!(target_os = "linux") {
+        &["/", "/lost+found", "/etc/machine-id"]
+    } else {
+        &[]
+    };
+    // The earliest wins: / may have been recreated by a restore, and
+    // machine-id regenerated
+    candidates.iter()
+        .filter_map(|path| fs::metadata(path).and_then(|metadata| metadata.created()).ok())
+        .filter_map(|created| created.duration_since(UNIX_EPOCH).ok())
+        // Some filesystems (and image builders) report the epoch itself
+        .filter(|since_epoch| since_epoch.as_secs() > 0)
+        .min()
+        .map(|since_epoch| format_date(since_epoch.as_secs()))
+}
+
+// Formats seconds since the Unix epoch as a UTC "YYYY-MM-DD", using the
+// days-to-civil algorithm from Howard Hinnant's date library
+fn format_date(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Filesystem of `/` (or the system drive on Windows), noting compression
+// where btrfs or ZFS have it turned on
+fn get_root_fs() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        let mounts = read_file("/proc/mounts").ok()?;
+        let root = parse_root_mount(&mounts)?;
+        let compression = match root.fs_type.as_str() {
+            "btrfs" => btrfs_compression(&root.options),
+            // ZFS keeps compression as a dataset property rather than a
+            // mount option
+            "zfs" => shell_command("zfs", &["get", "-H", "-o", "value", "compression", &root.source])
+                .filter(|value| value != "off"),
+            _ => None,
+        };
+        Some(match compression {
+            Some(algorithm) => format!("{} ({} compression)", root.fs_type, algorithm),
+            None => root.fs_type,
+        })
+    } else if cfg!(target_os = "windows") {
+        powershell_command("(Get-Volume -DriveLetter $env:SystemDrive[0]).FileSystemType")
+    } else {
+        None
+    }
+}
+
+struct RootMount {
+    source: String,
+    fs_type: String,
+    options: String,
+}
+
+// Finds `/` in /proc/mounts. The last entry wins, since a later mount on
+// the same point hides the earlier ones.
+fn parse_root_mount(mounts: &str) -> Option<RootMount> {
+    mounts.lines()
+        .rev()
+        .find_map(|line| {
+            let mut fields = line.split_whitespace();
+            let source = fields.next()?;
+            let mount_point = fields.next()?;
+            let fs_type = fields.next()?;
+            let options = fields.next().unwrap_or("");
+            (mount_point == "/").then(|| RootMount {
+                source: source.to_string(),
+                fs_type: fs_type.to_string(),
+                options: options.to_string(),
+            })
+        })
+}
+
+// The algorithm from a btrfs `compress=` or `compress-force=` mount option,
+// e.g. "zstd" from "compress=zstd:3"
+fn btrfs_compression(options: &str) -> Option<String> {
+    options.split(',')
+        .find_map(|option| option.strip_prefix("compress=").or_else(|| option.strip_prefix("compress-force=")))
+        .map(|value| value.split(':').next().unwrap_or(value).to_string())
+        .filter(|algorithm| algorithm != "no" && !algorithm.is_empty())
+}
+
+fn get_local_ip(attempts: usize) -> String {
+    if cfg!(target_os = "windows") {
+        with_retry(attempts, || powershell_command(
+            "$adapter = Get-NetAdapter | Where-Object {$_.Status -eq 'Up'} | Select-Object -First 1; \
+             if ($adapter) { \
+                 $ip = Get-NetIPAddress -InterfaceIndex $adapter.InterfaceIndex -AddressFamily IPv4 | Where-Object {$_.IPAddress -notlike '169.254.*'} | Select-Object -First 1; \
+                 'Local IP ({0}): {1}/{2}' -f $adapter.Name, $ip.IPAddress, $ip.PrefixLength \
+             } else { 'No active network connection' }"
+        ).ok_or(Failure::Transient)).unwrap_or_else(|| "unknown".to_string())
+    } else {
+        ipv4_interfaces()
+            .map(|interfaces| format_local_ip(&interfaces))
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+// An IPv4 address of a network interface, with its prefix length
+#[derive(Debug, PartialEq)]
+struct InterfaceAddress {
+    name: String,
+    up: bool,
+    loopback: bool,
+    address: std::net::Ipv4Addr,
+    prefix: u32,
+}
+
+// The IPv4 address of every interface, from getifaddrs(3)
+#[cfg(unix)]
+fn ipv4_interfaces() -> Option<Vec<InterfaceAddress>> {
+    let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: getifaddrs allocates the list, which stays valid (and is only
+    // read) until freeifaddrs below
+    unsafe {
+        if libc::getifaddrs(&mut list) != 0 {
+            return None;
+        }
+        let mut interfaces = Vec::new();
+        let mut entry = list;
+        while let Some(ifa) = entry.as_ref() {
+            entry = ifa.ifa_next;
+            if ifa.ifa_addr.is_null() || ifa.ifa_netmask.is_null() || (*ifa.ifa_addr).sa_family as libc::c_int != libc::AF_INET {
+                continue;
+            }
+            let address = (*(ifa.ifa_addr as *const libc::sockaddr_in)).sin_addr.s_addr;
+            let netmask = (*(ifa.ifa_netmask as *const libc::sockaddr_in)).sin_addr.s_addr;
+            let flags = ifa.ifa_flags as libc::c_int;
+            interfaces.push(InterfaceAddress {
+                name: std::ffi::CStr::from_ptr(ifa.ifa_name).to_string_lossy().into_owned(),
+                up: flags & libc::IFF_UP != 0,
+                loopback: flags & libc::IFF_LOOPBACK != 0,
+                address: u32::from_be(address).into(),
+                prefix: netmask.count_ones(),
+            });
+        }
+        libc::freeifaddrs(list);
+        Some(interfaces)
+    }
+}
+
+#[cfg(not(unix))]
+fn ipv4_interfaces() -> Option<Vec<InterfaceAddress>> {
+    None
+}
+
+// "Local IP (eth0): 192.168.1.20/24" for the first interface that is up,
+// skipping loopback and link-local (169.254.x.x) addresses, as on Windows
+fn format_local_ip(interfaces: &[InterfaceAddress]) -> String {
+    interfaces.iter()
+        .find(|interface| interface.up && !interface.loopback && !interface.address.is_link_local())
+        .map(|interface| format!("Local IP ({}): {}/{}", interface.name, interface.address, interface.prefix))
+        .unwrap_or_else(|| "No active network connection".to_string())
+}
+
+fn get_battery_info() -> String {
+    if cfg!(target_os = "windows") {
+        powershell_command(
+            "$battery = Get-CimInstance -ClassName Win32_Battery; \
+             if ($battery) { \
+                 $status = switch ($battery.BatteryStatus) { \
+                     1 { '[On Battery]' } \
+                     2 { '[AC Connected, Charging]' } \
+                     default { '[AC Connected]' } \
+                 }; \
+                 'Battery ({0}): {1}% {2}' -f $battery.Name, $battery.EstimatedChargeRemaining, $status \
+             } else { 'No battery detected' }"
+        ).unwrap_or_else(|| "No battery detected".to_string())
+    } else {
+        "No battery detected".to_string()
+    }
+}
+
+// "AC" or "Battery". Machines without a battery are taken to be on AC.
+fn get_power_source() -> Option<&'static str> {
+    if cfg!(target_os = "linux") {
+        let supplies = fs::read_dir("/sys/class/power_supply")
+            .map(|entries| {
+                entries.flatten()
+                    .map(|entry| {
+                        let read = |file: &str| read_file(entry.path().join(file)).unwrap_or_default().trim().to_string();
+                        PowerSupply { kind: read("type"), online: read("online") == "1", status: read("status") }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        Some(power_source(&supplies))
+    } else if cfg!(target_os = "windows") {
+        // BatteryStatus 1 means discharging; no battery prints nothing
+        let status = powershell_command("(Get-CimInstance -ClassName Win32_Battery | Select-Object -First 1).BatteryStatus");
+        Some(if status.as_deref() == Some("1") { "Battery" } else { "AC" })
+    } else {
+        None
+    }
+}
+
+// One entry of /sys/class/power_supply
+struct PowerSupply {
+    // "Mains" for AC adapters, "Battery", "USB", ...
+    kind: String,
+    online: bool,
+    status: String,
+}
+
+// Goes by the AC adapters when there are any; some laptops only have a
+// battery entry, whose status then tells whether it is being drained
+fn power_source(supplies: &[PowerSupply]) -> &'static str {
+    let mut adapters = supplies.iter().filter(|supply| supply.kind == "Mains").peekable();
+    if adapters.peek().is_some() {
+        return if adapters.any(|adapter| adapter.online) { "AC" } else { "Battery" };
+    }
+    let discharging = supplies.iter()
+        .any(|supply| supply.kind == "Battery" && supply.status == "Discharging");
+    if discharging { "Battery" } else { "AC" }
+}
+
+// Name of the default audio output device (not the sound server itself)
+fn get_audio_device() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        // PipeWire's own tool reports the friendly name directly
+        shell_command("wpctl", &["inspect", "@DEFAULT_AUDIO_SINK@"])
+            .and_then(|output| parse_wpctl_description(&output))
+            .or_else(|| {
+                // PulseAudio (or pipewire-pulse): look the default sink up by name
+                let sink = shell_command("pactl", &["get-default-sink"])?;
+                let sinks = shell_command("pactl", &["list", "sinks"])?;
+                parse_pactl_sink_description(&sinks, &sink)
+            })
+    } else if cfg!(target_os = "windows") {
+        // Get-AudioDevice comes from the AudioDeviceCmdlets module; without
+        // it, settle for the first sound device WMI knows about
+        powershell_command(
+            "try { (Get-AudioDevice -Playback -ErrorAction Stop).Name } \
+             catch { (Get-CimInstance -ClassName Win32_SoundDevice | Select-Object -First 1).Name }"
+        )
+    } else {
+        None
+    }
+}
+
+// Extracts `node.description` from `wpctl inspect` output, whose property
+// lines look like `  * node.description = "Built-in Audio Analog Stereo"`
+fn parse_wpctl_description(output: &str) -> Option<String> {
+    output.lines()
+        .map(|line| line.trim().trim_start_matches('*').trim())
+        .find_map(|line| line.strip_prefix("node.description"))
+        .and_then(|rest| rest.trim().strip_prefix('='))
+        .map(|value| value.trim().trim_matches('"').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// Finds the `Description:` of the sink named `sink` in `pactl list sinks`
+fn parse_pactl_sink_description(output: &str, sink: &str) -> Option<String> {
+    let mut in_sink = false;
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("Name:") {
+            in_sink = name.trim() == sink.trim();
+        } else if in_sink {
+            if let Some(description) = line.strip_prefix("Description:") {
+                return Some(description.trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+// Secure Boot state, or None on BIOS/non-UEFI systems
+fn get_secure_boot() -> Option<bool> {
+    if cfg!(target_os = "linux") {
+        if !Path::new("/sys/firmware/efi").exists() {
+            return None;
+        }
+        // The GUID suffix is fixed (EFI global variable), but look it up
+        // rather than hard-coding the full file name
+        fs::read_dir("/sys/firmware/efi/efivars")
+            .ok()
+            .and_then(|entries| {
+                entries.flatten()
+                    .find(|entry| entry.file_name().to_string_lossy().starts_with("SecureBoot-"))
+            })
+            .and_then(|entry| fs::read(entry.path()).ok())
+            .and_then(|data| parse_secure_boot_efivar(&data))
+            .or_else(|| {
+                shell_command("mokutil", &["--sb-state"])
+                    .and_then(|output| parse_mokutil_sb_state(&output))
+            })
+    } else if cfg!(target_os = "windows") {
+        // Throws on BIOS systems, which leaves the output empty
+        powershell_command("Confirm-SecureBootUEFI")
+            .and_then(|output| match output.trim() {
+                "True" => Some(true),
+                "False" => Some(false),
+                _ => None,
+            })
+    } else {
+        None
+    }
+}
+
+// efivarfs files start with four bytes of variable attributes, followed by
+// the value itself: a single byte that is 1 when Secure Boot is on
+fn parse_secure_boot_efivar(data: &[u8]) -> Option<bool> {
+    data.get(4).map(|&value| value == 1)
+}
+
+// `mokutil --sb-state` prints "SecureBoot enabled" or "SecureBoot disabled"
+fn parse_mokutil_sb_state(output: &str) -> Option<bool> {
+    output.lines()
+        .find_map(|line| line.trim().strip_prefix("SecureBoot "))
+        .and_then(|state| match state.trim() {
+            "enabled" => Some(true),
+            "disabled" => Some(false),
+            _ => None,
+        })
+}
+
+// "UEFI" or "BIOS", by how the running OS was booted
+fn get_boot_mode() -> Option<&'static str> {
+    if cfg!(target_os = "linux") {
+        // The kernel only creates this directory when booted through EFI
+        Some(if Path::new("/sys/firmware/efi").exists() { "UEFI" } else { "BIOS" })
+    } else if cfg!(target_os = "windows") {
+        powershell_command("$env:firmware_type").and_then(|output| parse_firmware_type(&output))
+    } else {
+        None
+    }
+}
+
+// Windows' %firmware_type% is "UEFI" or "Legacy" (and "Unknown" when
+// the firmware couldn't be identified)
+fn parse_firmware_type(output: &str) -> Option<&'static str> {
+    match output.trim() {
+        "UEFI" => Some("UEFI"),
+        "Legacy" => Some("BIOS"),
+        _ => None,
+    }
+}
+
+// TPM spec version, or None when the machine has no TPM
+fn get_tpm() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        read_file("/sys/class/tpm/tpm0/tpm_version_major")
+            .ok()
+            .and_then(|major| tpm_version(&major))
+    } else if cfg!(target_os = "windows") {
+        // Get-Tpm only says whether one is there; the version comes from WMI
+        powershell_command(
+            "if ((Get-Tpm).TpmPresent) { \
+             (Get-CimInstance -Namespace root/cimv2/Security/MicrosoftTpm -ClassName Win32_Tpm).SpecVersion }"
+        )
+        .and_then(|spec| parse_tpm_spec_version(&spec))
+    } else {
+        None
+    }
+}
+
+// /sys/class/tpm/*/tpm_version_major holds "1" for TPM 1.2 and "2" for 2.0
+fn tpm_version(major: &str) -> Option<String> {
+    match major.trim() {
+        "1" => Some("1.2".to_string()),
+        "2" => Some("2.0".to_string()),
+        _ => None,
+    }
+}
+
+// Win32_Tpm's SpecVersion lists the spec version first, e.g. "2.0, 0, 1.59"
+fn parse_tpm_spec_version(spec: &str) -> Option<String> {
+    spec.split(',')
+        .next()
+        .map(str::trim)
+        .filter(|version| !version.is_empty())
+        .map(str::to_string)
+}
+
+fn get_locale() -> String {
+    if cfg!(target_os = "windows") {
+        powershell_command("Get-Culture | Select-Object -ExpandProperty Name")
+            .unwrap_or_else(|| env::var("LANG").unwrap_or_else(|_| "unknown".to_string()))
+    } else {
+        env::var("LANG").unwrap_or_else(|_| "unknown".to_string())
+    }
+}
+
+// Interface name prefixes of tunnels: OpenVPN and friends (tun/tap),
+// WireGuard (wg, and NordVPN's nordlynx), Tailscale and ProtonVPN
+const VPN_INTERFACE_PREFIXES: &[&str] = &["tun", "tap", "wg", "nordlynx", "tailscale", "proton"];
+
+// Adapter descriptions of VPN clients on Windows, where interfaces are
+// named by the user rather than by the driver
+const WINDOWS_VPN_ADAPTERS: &str = "TAP-Windows|WireGuard|Wintun|OpenVPN|Tailscale|AnyConnect|Fortinet|PANGP";
+
+// VPN interfaces that are up. macOS is left out: its utun interfaces exist
+// whether or not a VPN is connected.
+fn get_vpn_interfaces() -> Option<Vec<String>> {
+    if cfg!(target_os = "linux") {
+        let entries = fs::read_dir("/sys/class/net").ok()?;
+        let mut interfaces: Vec<String> = entries.flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| is_vpn_interface(name))
+            .filter(|name| {
+                read_file(format!("/sys/class/net/{}/flags", name)).is_ok_and(|flags| interface_is_up(&flags))
+            })
+            .collect();
+        interfaces.sort();
+        Some(interfaces)
+    } else if cfg!(target_os = "windows") {
+        // The closing marker tells "no VPN adapters" apart from a failed query
+        powershell_command(&format!(
+            "Get-NetAdapter -ErrorAction Stop | Where-Object {{ $_.Status -eq 'Up' -and $_.InterfaceDescription -match '{}' }} | ForEach-Object {{ $_.Name }}; '{}'",
+            WINDOWS_VPN_ADAPTERS, WINDOWS_VPN_END
+        ))
+        .and_then(|output| parse_windows_vpn_adapters(&output))
+    } else {
+        None
+    }
+}
+
+const WINDOWS_VPN_END: &str = "--end--";
+
+// Adapter names, one per line, up to the marker the script prints last;
+// None when it never got there
+fn parse_windows_vpn_adapters(output: &str) -> Option<Vec<String>> {
+    let (names, _) = output.rsplit_once(WINDOWS_VPN_END)?;
+    Some(names.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect())
+}
+
+fn is_vpn_interface(name: &str) -> bool {
+    VPN_INTERFACE_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+// sysfs `flags` is the interface's IFF_* bits in hex; IFF_UP is bit 0.
+// (operstate is no help: tun devices report "unknown" even when up.)
+fn interface_is_up(flags: &str) -> bool {
+    u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).is_ok_and(|flags| flags & 0x1 != 0)
+}
+
+// The VM tunables people adjust most, from /proc/sys/vm (Linux only; the
+// directory doesn't exist elsewhere)
+fn get_swappiness(vm_dir: &Path) -> Option<(u32, Option<u32>)> {
+    let read = |name: &str| read_file(vm_dir.join(name)).ok()?.trim().parse::<u32>().ok();
+    Some((read("swappiness")?, read("vfs_cache_pressure")))
+}
+
+// Size of a glibc `struct utmp` record, the same on 32 and 64-bit Linux
+const UTMP_RECORD_SIZE: usize = 384;
+
+// `ut_type` of a login session
+const UTMP_USER_PROCESS: i16 = 7;
+
+// How long `who` or `query user` gets; a hung NSS/LDAP lookup can stall them
+const USERS_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Number of login sessions: from utmp on glibc Linux, `who` on macOS, the
+// BSDs and other Linux systems (musl's utmp layout differs, and many such
+// systems keep none), and `query user` (Pro editions and up) on Windows
+fn get_logged_in_users() -> Option<usize> {
+    if cfg!(target_os = "windows") {
+        let mut command = Command::new("query");
+        command.arg("user");
+        command_with_timeout(command, USERS_TIMEOUT).map(|output| count_query_user(&output))
+    } else if let Some(utmp) = cfg!(all(target_os = "linux", target_env = "gnu")).then(|| fs::read("/var/run/utmp").ok()).flatten() {
+        Some(count_utmp_sessions(&utmp))
+    } else {
+        command_with_timeout(Command::new("who"), USERS_TIMEOUT).map(|output| count_nonempty_lines(&output, 0))
+    }
+}
+
+// utmp records whose type (the first field, native-endian) is a user session
+fn count_utmp_sessions(utmp: &[u8]) -> usize {
+    utmp.chunks_exact(UTMP_RECORD_SIZE)
+        .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == UTMP_USER_PROCESS)
+        .count()
+}
+
+// `query user` prints a header line, then one line per session
+fn count_query_user(output: &str) -> usize {
+    count_nonempty_lines(output, 1)
+}
+
+// How long `git status` gets; large repositories can take a while
+const GIT_STATUS_TIMEOUT: Duration = Duration::from_secs(2);
+
+// "main *" for the repository the working directory is in. The branch comes
+// straight from HEAD, so it shows even without git installed; only the
+// dirty marker needs `git status`.
+fn get_git_status() -> Option<String> {
+    let cwd = env::current_dir().ok()?;
+    let git_dir = find_git_dir(&cwd)?;
+    let mut status = git_head_name(&read_file(git_dir.join("HEAD")).ok()?)?;
+    
+    let mut command = Command::new("git");
+    command.args(["status", "--porcelain", "--untracked-files=normal"]).current_dir(&cwd);
+    if command_with_timeout(command, GIT_STATUS_TIMEOUT).is_some_and(|changes| !changes.is_empty()) {
+        status.push_str(" *");
+    }
+    Some(status)
+}
+
+// The `.git` directory of the repository containing `dir`. In worktrees and
+// submodules `.git` is a file pointing elsewhere ("gitdir: <path>").
+fn find_git_dir(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().find_map(|dir| {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git);
+        }
+        let pointer = read_file(&dot_git).ok()?;
+        let target = Path::new(pointer.strip_prefix("gitdir:")?.trim());
+        Some(dir.join(target))
+    })
+}
+
+// The branch a HEAD file names, or the short commit hash when detached
+fn git_head_name(head: &str) -> Option<String> {
+    let head = head.trim();
+    if let Some(reference) = head.strip_prefix("ref:") {
+        let reference = reference.trim();
+        return Some(reference.strip_prefix("refs/heads/").unwrap_or(reference).to_string());
+    }
+    (head.len() >= 7 && head.chars().all(|c| c.is_ascii_hexdigit())).then(|| head[..7].to_string())
+}
+
+// "Europe/London (BST, +01:00)": the zone's name, then its abbreviation
+// and UTC offset right now (Windows has no abbreviations)
+fn get_timezone() -> Option<String> {
+    if cfg!(target_os = "windows") {
+        let output = powershell_command(
+            "$tz = Get-TimeZone; $tz.Id + [char]9 + $tz.GetUtcOffset((Get-Date)).TotalMinutes"
+        )?;
+        let (name, minutes) = output.split_once('\t')?;
+        let minutes: f64 = minutes.trim().parse().ok()?;
+        return Some(format_timezone(name.trim(), None, minutes as i64 * 60));
+    }
+    let name = env::var("TZ")
+        .ok()
+        .map(|tz| tz.trim_start_matches(':').to_string())
+        .filter(|tz| !tz.is_empty())
+        .or_else(|| fs::read_link("/etc/localtime").ok().and_then(|target| zone_name(&target.to_string_lossy())))
+        .or_else(|| read_file("/etc/timezone").ok().map(|zone| zone.trim().to_string()).filter(|zone| !zone.is_empty()))?;
+    let (offset, abbreviation) = local_utc_offset()?;
+    Some(format_timezone(&name, Some(&abbreviation), offset))
+}
+
+// "/usr/share/zoneinfo/Europe/London" (or macOS's
+// "/var/db/timezone/zoneinfo/...") -> "Europe/London"
+fn zone_name(localtime_target: &str) -> Option<String> {
+    let (_, name) = localtime_target.split_once("zoneinfo/")?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+// Seconds east of UTC and the zone abbreviation in effect now
+#[cfg(unix)]
+fn local_utc_offset() -> Option<(i64, String)> {
+    // SAFETY: localtime_r only writes the `tm` we pass; tm_zone then points
+    // at a static string (or is null)
+    unsafe {
+        let now = libc::time(std::ptr::null_mut());
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&now, &mut tm).is_null() {
+            return None;
+        }
+        let abbreviation = if tm.tm_zone.is_null() {
+            String::new()
+        } else {
+            std::ffi::CStr::from_ptr(tm.tm_zone).to_string_lossy().into_owned()
+        };
+        Some((tm.tm_gmtoff as i64, abbreviation))
+    }
+}
+
+#[cfg(not(unix))]
+fn local_utc_offset() -> Option<(i64, String)> {
+    None
+}
+
+// Zones without a real abbreviation report their offset ("+03") in its
+// place, which would only repeat the offset
+fn format_timezone(name: &str, abbreviation: Option<&str>, offset_seconds: i64) -> String {
+    let sign = if offset_seconds < 0 { '-' } else { '+' };
+    let minutes = offset_seconds.abs() / 60;
+    let offset = format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60);
+    match abbreviation.filter(|abbreviation| abbreviation.starts_with(|c: char| c.is_ascii_alphabetic())) {
+        Some(abbreviation) => format!("{} ({}, {})", name, abbreviation, offset),
+        None => format!("{} ({})", name, offset),
+    }
+}
+
+fn get_kernel_modules(watch: &[String]) -> Option<String> {
+    if cfg!(target_os = "linux") {
+        read_file("/proc/modules")
+            .ok()
+            .map(|contents| format_kernel_modules(&contents, watch))
+    } else {
+        None
+    }
+}
+
+fn get_cmdline(flags: &[String], max_width: usize) -> Option<String> {
+    if cfg!(target_os = "linux") {
+        read_file("/proc/cmdline")
+            .ok()
+            .map(|contents| format_cmdline(&contents, flags, max_width))
+            .filter(|cmdline| !cmdline.is_empty())
+    } else {
+        None
+    }
+}
+
+// Keeps the parameters named in `flags` (matching `mitigations` against
+// `mitigations=off`), or the whole command line when `flags` is empty,
+// and cuts the result off at `max_width` columns
+fn format_cmdline(contents: &str, flags: &[String], max_width: usize) -> String {
+    let params: Vec<&str> = contents.split_whitespace()
+        .filter(|param| {
+            let name = param.split_once('=').map_or(*param, |(name, _)| name);
+            flags.is_empty() || flags.iter().any(|flag| flag == name)
+        })
+        .collect();
+    text::truncate_to_width(&params.join(" "), max_width)
+}
+
+// Counts the loaded modules in /proc/modules (one per line, name first) and
+// notes which of the watched modules are among them, e.g. "184 (nvidia, zfs)"
+fn format_kernel_modules(contents: &str, watch: &[String]) -> String {
+    let loaded: Vec<&str> = contents.lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    let present: Vec<&str> = watch.iter()
+        .map(String::as_str)
+        .filter(|name| loaded.contains(name))
+        .collect();
+    
+    if present.is_empty() {
+        loaded.len().to_string()
+    } else {
+        format!("{} ({})", loaded.len(), present.join(", "))
+    }
+}
+
+fn format_bytes_gib(bytes: u64, decimals: usize) -> String {
+    format!("{:.*} GiB", decimals, bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+// Rewrites a "X GiB / Y GiB (N%)" usage (whatever its source, including
+// PowerShell) for `memory_format`. Anything after the percentage, like
+// " (zswap active)" or a disk's " - NTFS", is kept; values that aren't a
+// usage ("No swap", "unknown") pass through untouched.
+pub fn format_memory(usage: &str, format: MemoryFormat) -> String {
+    if format == MemoryFormat::Verbose {
+        return usage.to_string();
+    }
+    let shorten = || -> Option<String> {
+        let (used, rest) = usage.split_once(" / ")?;
+        let (total, rest) = rest.split_once(" (")?;
+        let (_, suffix) = rest.split_once("%)")?;
+        let (used, used_unit) = used.split_once(' ')?;
+        let (total, total_unit) = total.split_once(' ')?;
+        let trim = |number: &str| -> Option<String> {
+            number.parse::<f64>().ok()?;
+            Some(if number.contains('.') {
+                number.trim_end_matches('0').trim_end_matches('.').to_string()
+            } else {
+                number.to_string()
+            })
+        };
+        let (used, total) = (trim(used)?, trim(total)?);
+        Some(if used_unit == total_unit {
+            format!("{}/{} {}{}", used, total, total_unit, suffix)
+        } else {
+            format!("{} {}/{} {}{}", used, used_unit, total, total_unit, suffix)
+        })
+    };
+    shorten().unwrap_or_else(|| usage.to_string())
+}
+
+// The GPU module's lines, with the name filters applied to all of them: the
+// GPU list, the active GPU, and the display lines (which only name the
+// vendor, so they go when that vendor is only in hidden GPUs' names). If
+// the filters would leave no GPU at all they're ignored, rather than the
+// line going empty.
+fn gpu_lines(info: &SystemInfo, include: &[String], exclude: &[String]) -> Vec<String> {
+    let any_shown = info.gpu.iter().any(|gpu| gpu_matches(gpu, include, exclude));
+    let shown = |gpu: &str| !any_shown || gpu_matches(gpu, include, exclude);
+    let display_shown = |line: &str| {
+        let Some((_, rest)) = line.split_once(" (") else {
+            return true;
+        };
+        let vendor = rest.split_once("):").map_or(rest, |(vendor, _)| vendor).to_ascii_lowercase();
+        let mut named_by = info.gpu.iter().filter(|gpu| gpu.to_ascii_lowercase().contains(&vendor)).peekable();
+        named_by.peek().is_none() || named_by.any(|gpu| shown(gpu))
+    };
+    
+    info.gpu.iter()
+        .filter(|gpu| shown(gpu))
+        .map(|gpu| format!("GPU: {}", gpu))
+        .chain(info.active_gpu.iter().filter(|gpu| shown(gpu)).map(|gpu| format!("GPU (active): {}", gpu)))
+        .chain(info.gpu_displays.iter().filter(|line| display_shown(line)).cloned())
+        .collect()
+}
+
+// Renders the info lines for one module from the registry, or for a
+// user-defined `[[module]]`. With `hide_unknown`, placeholder lines are
+// dropped, so a lone "Unknown GPU" leaves no GPU line at all.
+fn module_lines(info: &SystemInfo, config: &Config, module: &str) -> Vec<String> {
+    let mut lines = render_module(info, config, module);
+    if config.hide_unknown {
+        lines.retain(|line| !is_unknown_line(line));
+    }
+    lines
+}
+
+fn render_module(info: &SystemInfo, config: &Config, module: &str) -> Vec<String> {
+    if let Some(custom) = config.custom_module(module) {
+        return match (info.custom.get(module).cloned().flatten(), custom.on_error) {
+            (Some(value), _) => vec![format!("{}: {}", custom.name, value)],
+            (None, OnError::Unknown) => vec![format!("{}: unknown", custom.name)],
+            (None, OnError::Hide) => Vec::new(),
+        };
+    }
+    
+    match module {
+        "os" => vec![format!("OS: {}", info.os)],
+        "host" => vec![format!("Host: {}", info.host)],
+        "kernel" => vec![format!("Kernel: {}", info.kernel)],
+        "uptime" => vec![format!("Uptime: {}", info.uptime)],
+        "load" => info.load.iter().map(|load| format!("Load: {}", load)).collect(),
+        "packages" => vec![format!("Packages: {}", info.packages)],
+        "shell" => vec![format!("Shell: {}", info.shell)],
+        "display" => info.display.clone(),
+        "display_count" => info.display_count.iter().map(|count| format!("Displays: {}", count)).collect(),
+        "de" => vec![format!("DE: {}", info.de)],
+        "wm" => vec![format!("WM: {}", info.wm)],
+        "wm_theme" => vec![format!("WM Theme: {}", info.wm_theme)],
+        "icons" => vec![format!("Icons: {}", info.icons)],
+        "font" => vec![format!("Font: {}", info.font)],
+        "cursor" => vec![format!("Cursor: {}", info.cursor)],
+        "terminal" => vec![format!("Terminal: {}", info.terminal)],
+        "cpu" => vec![format!("CPU: {}", info.cpu)],
+        "cpu_cache" => info.cpu_cache.iter().map(|cache| format!("CPU Cache: {}", cache)).collect(),
+        "gpu" => gpu_lines(info, &config.gpu_include, &config.gpu_exclude),
+        "cpu_temp" => info.cpu_temp.iter().map(|celsius| format!("CPU Temp: {:.1}°C", celsius)).collect(),
+        "gpu_vram_used" => info.gpu_vram_used.iter()
+            .map(|&(used, total)| format!("GPU VRAM: {}", format_vram(used, total)))
+            .collect(),
+        "gpu_clock" => info.gpu_clock.iter().map(|clock| format!("GPU Clock: {}", clock)).collect(),
+        "memory" => vec![format!("Memory: {}", format_memory(&info.memory, config.memory_format))],
+        "swap" => vec![format!("Swap: {}", format_memory(&info.swap, config.memory_format))],
+        "ram_slots" => info.ram_slots.iter()
+            .map(|(slots, modules)| format!("RAM Slots: {}", format_ram_slots(*slots, modules)))
+            .collect(),
+        "root_fs" => info.root_fs.iter().map(|fs_type| format!("Filesystem: {}", fs_type)).collect(),
+        "disk" => info.disk.iter()
+            .map(|disk| match disk.split_once(": ") {
+                Some((label, usage)) => format!("{}: {}", label, format_memory(usage, config.memory_format)),
+                None => disk.clone(),
+            })
+            .collect(),
+        "local_ip" => vec![info.local_ip.clone()],
+        "battery" => vec![info.battery.clone()],
+        "workspaces" => info.workspaces.iter()
+            .map(|(count, active)| format!("Workspaces: {} (active: {})", count, active))
+            .collect(),
+        "updates" => info.updates.iter().map(|count| format!("Updates: {} available", count)).collect(),
+        "swappiness" => info.swappiness.iter()
+            .flat_map(|&(swappiness, cache_pressure)| {
+                std::iter::once(format!("Swappiness: {}", swappiness))
+                    .chain(cache_pressure.map(|pressure| format!("VFS Cache Pressure: {}", pressure)))
+            })
+            .collect(),
+        "keyboard_layout" => info.keyboard_layout.iter().map(|layout| format!("Keyboard: {}", layout)).collect(),
+        "users" => info.users.iter().map(|count| format!("Users: {} logged in", count)).collect(),
+        "git" => info.git.iter().map(|status| format!("Git: {}", status)).collect(),
+        "vpn" => info.vpn.iter()
+            .map(|interfaces| if interfaces.is_empty() {
+                "VPN: none".to_string()
+            } else {
+                format!("VPN: {} (active)", interfaces.join(", "))
+            })
+            .collect(),
+        "timezone" => info.timezone.iter().map(|timezone| format!("Timezone: {}", timezone)).collect(),
+        "install_date" => info.install_date.iter().map(|date| format!("Installed: {}", date)).collect(),
+        "power_source" => info.power_source.iter().map(|source| format!("Power Source: {}", source)).collect(),
+        "locale" => vec![format!("Locale: {}", info.locale)],
+        "audio_device" => info.audio_device.iter().map(|device| format!("Audio Device: {}", device)).collect(),
+        "tearing" => info.tearing.iter()
+            .map(|&allowed| format!("Tearing: {}", if allowed { "allowed" } else { "vsync" }))
+            .collect(),
+        "secure_boot" => info.secure_boot.iter()
+            .map(|&enabled| format!("Secure Boot: {}", if enabled { "Enabled" } else { "Disabled" }))
+            .collect(),
+        "boot_mode" => info.boot_mode.iter().map(|mode| format!("Boot Mode: {}", mode)).collect(),
+        "tpm" => info.tpm.iter().map(|version| format!("TPM: {} (present)", version)).collect(),
+        "terminal_theme" => info.terminal_theme.iter()
+            .map(|background| format!("Terminal Theme: {}", background.name()))
+            .collect(),
+        "terminal_size" => info.terminal_size.iter().map(|size| format!("Terminal Size: {}", size)).collect(),
+        "cmdline" => info.cmdline.iter().map(|cmdline| format!("Cmdline: {}", cmdline)).collect(),
+        "kernel_modules" => info.kernel_modules.iter().map(|count| format!("Modules: {}", count)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Shortest underline sized from the title, so it never shrinks to nothing
+const MIN_SEPARATOR_LENGTH: usize = 3;
+
+// "user@host", with "unknown" standing in for a name that came back empty
+// (or all whitespace) so the title never reads "@host" or "user@"
+fn title(username: &str, hostname: &str) -> String {
+    let or_unknown = |name: &str| if name.trim().is_empty() { "unknown".to_string() } else { name.trim().to_string() };
+    format!("{}@{}", or_unknown(username), or_unknown(hostname))
+}
+
+// Builds the underline for the title, sized to its display width (not its
+// byte length) and then lengthened or shortened by the configured offset
+fn title_separator(title: &str, offset: Option<i64>) -> String {
+    let width = text::visible_width(title).max(MIN_SEPARATOR_LENGTH) as i64;
+    "─".repeat((width + offset.unwrap_or(0)).max(0) as usize)
+}
+
+// Logo IDs to try, most specific first: the os-release ID and the
+// distros it says it's like on Linux and the BSDs, else the OS name
+fn logo_ids() -> Vec<String> {
+    if cfg!(target_os = "windows") {
+        return vec!["windows".to_string()];
+    }
+    if cfg!(target_os = "macos") {
+        return vec!["macos".to_string()];
+    }
+    read_file("/etc/os-release")
+        .or_else(|_| read_file("/usr/lib/os-release"))
+        .map(|contents| os_release_ids(&parse_os_release(&contents)))
+        .unwrap_or_default()
+}
+
+fn os_release_ids(os_release: &HashMap<String, String>) -> Vec<String> {
+    let id = os_release.get("ID").map(String::as_str).unwrap_or("");
+    let like = os_release.get("ID_LIKE").map(String::as_str).unwrap_or("");
+    std::iter::once(id)
+        .chain(like.split_whitespace())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Widest line of a column (the logo, or the info beside a right-hand
+// logo) in terminal columns
+fn column_width<S: AsRef<str>>(lines: &[S]) -> usize {
+    lines.iter().map(|line| text::visible_width(line.as_ref())).max().unwrap_or(0)
+}
+
+// Writes the whole fetch in one go, so nothing (a log line, another
+// thread) can land in the middle of it
+fn display_info(info: &SystemInfo, config: &Config) {
+    let output = render_output(info, config);
+    let mut stdout = std::io::stdout().lock();
+    // A closed pipe (`rustfetch | head`) isn't worth reporting
+    let _ = stdout.write_all(output.as_bytes()).and_then(|_| stdout.flush());
+}
+
+// The full output: logo and info side by side, framed by blank lines
+fn render_output(info: &SystemInfo, config: &Config) -> String {
+    let user_host = title(&info.username, &info.hostname);
+    let separator = title_separator(&user_host, config.separator_length);
+    
+    let mut info_lines = vec![user_host, separator];
+    
+    for (index, group) in config.layout().iter().enumerate() {
+        // Blank line between groups; the logo keeps flowing alongside
+        if index > 0 {
+            info_lines.push(String::new());
+        }
+        for module in group {
+            info_lines.extend(module_lines(info, config, module));
+        }
+    }
+    
+    let truecolor = config.truecolor.unwrap_or_else(colors::supports_truecolor);
+    let paint = |color: Option<Color>| match color {
+        Some(color) if config.color => color.escape(truecolor),
+        _ => String::new(),
+    };
+    let title_color = paint(Some(config.colors.title));
+    let separator_color = paint(Some(config.colors.separator));
+    let label_color = paint(Some(config.colors.label));
+    let bold = config.colors.bold_escape(config.color);
+    let reset = if config.color { RESET } else { "" };
+    
+    let info_lines: Vec<String> = info_lines.iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                // Username@hostname
+                format!("{}{}{}{}", bold, title_color, line, reset)
+            } else if i == 1 {
+                // Separator line
+                format!("{}{}{}", separator_color, line, reset)
+            } else if let Some((label, value)) = line.split_once(':') {
+                // Color the labels
+                let warning = config.battery_warning(label, value).or_else(|| config.disk_warning(label, value));
+                let (label_color, value_color) = match warning {
+                    Some(warning) => (paint(Some(warning)), paint(Some(warning))),
+                    None => (label_color.clone(), paint(config.colors.value_for(label, value))),
+                };
+                // Colors are picked from the whole value; only what's shown is cut
+                let value = match config.max_value_width {
+                    Some(max_width) => {
+                        let (space, text) = value.split_at(value.len() - value.trim_start().len());
+                        format!("{}{}", space, text::truncate_to_width(text, max_width))
+                    }
+                    None => value.to_string(),
+                };
+                format!("{}{}{}:{}{}{}{}", bold, label_color, label, reset, value_color, value, reset)
+            } else {
+                line.clone()
+            }
+        })
+        .collect();
+    let info_lines: Vec<String> = std::iter::repeat_n(String::new(), config.info_padding_top)
+        .chain(info_lines)
+        .collect();
+    
+    // The header and footer share the logo's palette for their color
+    // markers, so the logo is looked up even when it isn't drawn
+    let needs_logo = config.logo_position != LogoPosition::None || config.header.is_some() || config.footer.is_some();
+    let logo = needs_logo.then(|| pick_logo(info, config));
+    // `[colors] logo` stands in for the logo's main color
+    let logo_colors: Vec<String> = logo.iter()
+        .flat_map(|logo| logo.colors.iter().enumerate())
+        .map(|(index, &color)| paint(Some(if index == 0 { config.colors.logo.unwrap_or(color) } else { color })))
+        .collect();
+    // Escapes written into the text itself are dropped along with ours when
+    // color is off. Each line ends in a reset so colors can't leak past it.
+    let extra_lines = |text: &Option<String>| -> Vec<String> {
+        let Some(text) = text else {
+            return Vec::new();
+        };
+        let text = if config.color { text.clone() } else { text::strip_ansi(text) };
+        logos::render(&text, &logo_colors)
+            .into_iter()
+            .map(|line| if line.ends_with(reset) { line } else { format!("{}{}", line, reset) })
+            .collect()
+    };
+    
+    let logo_lines: Vec<String> = match logo.filter(|_| config.logo_position != LogoPosition::None) {
+        None => Vec::new(),
+        Some(logo) => {
+            let mut lines: Vec<String> = logos::render(&logo.art, &logo_colors)
+                .into_iter()
+                .map(|line| format!("{}{}", line, reset))
+                .collect();
+            // An image takes the logo's place at the logo's size. It needs
+            // truecolor escapes, so without color the logo stays.
+            if let Some(path) = config.image.as_deref().filter(|_| config.color) {
+                match image::load(path) {
+                    Ok(picture) => {
+                        lines = match config.image_mode {
+                            ImageMode::Blocks => image::render_blocks(&picture, column_width(&lines), lines.len()),
+                        };
+                    }
+                    Err(err) => eprintln!("rustfetch: {}", err),
+                }
+            }
+            std::iter::repeat_n(String::new(), config.logo_padding_top).chain(lines).collect()
+        }
+    };
+    
+    let mut output = String::from("\n");
+    for line in extra_lines(&config.header) {
+        output.push_str(&line);
+        output.push('\n');
+    }
+    
+    match config.logo_position {
+        LogoPosition::Left => {
+            // Size the logo column from what's actually visible, so narrow
+            // and wide logos both sit `logo_gap` spaces away from the info
+            let logo_width = column_width(&logo_lines);
+            for i in 0..logo_lines.len().max(info_lines.len()) {
+                let logo_line = logo_lines.get(i).map(String::as_str).unwrap_or(reset);
+                output.push_str(&text::pad_right(logo_line, logo_width + config.logo_gap));
+                output.push_str(info_lines.get(i).map(String::as_str).unwrap_or(""));
+                output.push('\n');
+            }
+        }
+        LogoPosition::Right => {
+            let info_width = column_width(&info_lines);
+            for i in 0..logo_lines.len().max(info_lines.len()) {
+                let info_line = info_lines.get(i).map(String::as_str).unwrap_or("");
+                match logo_lines.get(i) {
+                    Some(logo_line) => {
+                        output.push_str(&text::pad_right(info_line, info_width + config.logo_gap));
+                        output.push_str(logo_line);
+                    }
+                    None => output.push_str(info_line),
+                }
+                output.push('\n');
+            }
+        }
+        LogoPosition::Top | LogoPosition::None => {
+            for line in &logo_lines {
+                output.push_str(line);
+                output.push('\n');
+            }
+            if !logo_lines.is_empty() {
+                output.push('\n');
+            }
+            for line in &info_lines {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+    }
+    
+    for line in extra_lines(&config.footer) {
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output.push('\n');
+    output
+}
+
+// The user's chosen logo, or the one for the detected OS (or CPU)
+fn pick_logo(info: &SystemInfo, config: &Config) -> Logo {
+    let logos_dir = config::logos_dir();
+    let named = config.logo.as_deref().and_then(|name| {
+        let logo = logos::find_named(name, logos_dir.as_deref());
+        if logo.is_none() {
+            eprintln!("rustfetch: no logo named `{}`, using the detected one", name);
+        }
+        logo
+    });
+    named.unwrap_or_else(|| {
+        let mut ids = logo_ids();
+        if config.logo_type == LogoType::Cpu {
+            // The OS logo stays as the fallback for unrecognised vendors
+            ids.splice(0..0, logos::cpu_vendor(&info.cpu).map(str::to_string));
+        }
+        logos::find(&ids, logos_dir.as_deref())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    macro_rules! fixture {
+        ($distro:literal, $file:literal) => {
+            include_str!(concat!("../tests/fixtures/", $distro, "/", $file))
+        };
+    }
+
+    #[test]
+    fn parses_cpu_model() {
+        assert_eq!(
+            parse_cpu_model(fixture!("arch", "cpuinfo")).as_deref(),
+            Some("AMD Ryzen 9 7950X 16-Core Processor")
+        );
+        assert_eq!(
+            parse_cpu_model(fixture!("debian", "cpuinfo")).as_deref(),
+            Some("Intel(R) Xeon(R) Processor")
+        );
+        assert_eq!(
+            parse_cpu_model(fixture!("fedora", "cpuinfo")).as_deref(),
+            Some("12th Gen Intel(R) Core(TM) i7-1260P")
+        );
+        // ARM kernels don't expose a `model name` field
+        assert_eq!(parse_cpu_model(fixture!("raspbian", "cpuinfo")), None);
+    }
+
+    #[test]
+    fn finds_notable_cpu_features() {
+        let features = |cpuinfo| notable_cpu_features(parse_cpu_flags(cpuinfo));
+        assert_eq!(features(fixture!("arch", "cpuinfo")), ["AVX-512"]);
+        assert_eq!(features(fixture!("fedora", "cpuinfo")), ["AVX2"]);
+        assert!(features(fixture!("raspbian", "cpuinfo")).is_empty());
+        assert_eq!(notable_cpu_features("fp asimd sve sve2 i8mm"), ["SVE2"]);
+        assert_eq!(notable_cpu_features("fp asimd sve"), ["SVE"]);
+        // Substrings of other flags must not match
+        assert!(notable_cpu_features("avx512_bf16 avx").is_empty());
+    }
+
+    #[test]
+    fn formats_kernel_modules() {
+        let modules = fixture!("arch", "modules");
+        let watch = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        assert_eq!(format_kernel_modules(modules, &[]), "12");
+        assert_eq!(format_kernel_modules(modules, &watch(&["zfs", "nvidia", "vfio"])), "12 (zfs, nvidia)");
+        assert_eq!(format_kernel_modules("", &watch(&["zfs"])), "0");
+    }
+
+    #[test]
+    fn parses_audio_devices() {
+        assert_eq!(
+            parse_wpctl_description(fixture!("arch", "wpctl-inspect")).as_deref(),
+            Some("Built-in Audio Analog Stereo")
+        );
+        assert_eq!(parse_wpctl_description("id 0, type PipeWire:Interface:Node"), None);
+
+        let sinks = fixture!("fedora", "pactl-list-sinks");
+        assert_eq!(
+            parse_pactl_sink_description(sinks, "alsa_output.usb-Focusrite_Scarlett_2i2-00.analog-stereo").as_deref(),
+            Some("Scarlett 2i2 USB Analog Stereo")
+        );
+        assert_eq!(
+            parse_pactl_sink_description(sinks, "alsa_output.pci-0000_00_1f.3.analog-stereo\n").as_deref(),
+            Some("Built-in Audio Analog Stereo")
+        );
+        assert_eq!(parse_pactl_sink_description(sinks, "missing"), None);
+    }
+
+    #[test]
+    fn parses_secure_boot_state() {
+        assert_eq!(parse_secure_boot_efivar(&[0x06, 0x00, 0x00, 0x00, 0x01]), Some(true));
+        assert_eq!(parse_secure_boot_efivar(&[0x06, 0x00, 0x00, 0x00, 0x00]), Some(false));
+        assert_eq!(parse_secure_boot_efivar(&[0x06, 0x00]), None);
+
+        assert_eq!(parse_mokutil_sb_state("SecureBoot enabled\n"), Some(true));
+        assert_eq!(parse_mokutil_sb_state("SecureBoot disabled\nPlatform is in Setup Mode\n"), Some(false));
+        assert_eq!(parse_mokutil_sb_state("EFI variables are not supported on this system\n"), None);
+    }
+
+    #[test]
+    fn sums_cpu_caches() {
+        let cache = |level, kind: &str, size_kib, shared_cpus: &str| CpuCache {
+            level,
+            kind: kind.to_string(),
+            size_kib,
+            shared_cpus: shared_cpus.to_string(),
+        };
+        // Two cores with their own L1/L2, sharing one L3; the L3 is listed
+        // under both
+        let caches = [
+            cache(1, "Data", 48, "0"),
+            cache(1, "Instruction", 32, "0"),
+            cache(2, "Unified", 1280, "0"),
+            cache(3, "Unified", 24576, "0-1"),
+            cache(1, "Data", 48, "1"),
+            cache(1, "Instruction", 32, "1"),
+            cache(2, "Unified", 1280, "1"),
+            cache(3, "Unified", 24576, "0-1"),
+        ];
+        assert_eq!(format_cpu_caches(&caches).as_deref(), Some("L1 160 KiB, L2 2.5 MiB, L3 24 MiB"));
+        assert_eq!(format_cpu_caches(&[]), None);
+        
+        assert_eq!(parse_cache_size("48K\n"), Some(48));
+        assert_eq!(parse_cache_size("32M"), Some(32768));
+        assert_eq!(parse_cache_size("big"), None);
+    }
+
+    #[test]
+    fn formats_package_counts() {
+        assert_eq!(format_package_count(12, "flatpak", None, ""), "12 (flatpak)");
+        assert_eq!(format_package_count(12, "flatpak", Some(3_435_973_837), ","), "12 (flatpak, 3.2 GiB)");
+        assert_eq!(format_package_count(12345, "pacman", None, ","), "12,345 (pacman)");
+        assert_eq!(group_thousands(0, ","), "0");
+        assert_eq!(group_thousands(999, ","), "999");
+        assert_eq!(group_thousands(1000, "."), "1.000");
+        assert_eq!(group_thousands(1234567, " "), "1 234 567");
+        assert_eq!(group_thousands(usize::MAX, ""), usize::MAX.to_string());
+        assert_eq!(parse_du_total("3221225472\t/var/lib/flatpak\n214748364\t/home/alex/.local/share/flatpak\n3435973836\ttotal\n"), Some(3435973836));
+        assert_eq!(parse_du_total(""), None);
+    }
+
+    #[test]
+    fn parses_zram_mm_stat() {
+        let mm_stat = "  3355443200   1082401587   1124073472        0   1142882304     3196    62510   123456\n";
+        assert_eq!(parse_zram_mm_stat(mm_stat), Some((3355443200, 1082401587)));
+        assert_eq!(parse_zram_mm_stat("12"), None);
+        assert_eq!(parse_zram_mm_stat(""), None);
+    }
+
+    #[test]
+    fn counts_packages() {
+        let choco = "Chocolatey v1.4.0\n7zip 23.1.0\ngit 2.45.1\nvscode 1.89.1\n3 packages installed.\n";
+        assert_eq!(count_choco_packages(choco), 3);
+        // Without the summary, the banner is the only line not counted
+        assert_eq!(count_choco_packages("Chocolatey v1.4.0\n7zip 23.1.0\n"), 1);
+        assert_eq!(count_choco_packages("Chocolatey v1.4.0\n"), 0);
+        assert_eq!(count_choco_packages(""), 0);
+        // A summary too large for usize falls back to counting lines
+        assert_eq!(count_choco_packages("Chocolatey v1.4.0\n99999999999999999999999 packages installed.\n"), 0);
+        
+        let winget = "Name       Id              Version\n---------------------------------\n\
+            Git        Git.Git         2.45.1\n7-Zip      7zip.7zip       23.01\n";
+        assert_eq!(count_winget_packages(winget), 2);
+        assert_eq!(count_winget_packages("Name  Id  Version\n-----\n"), 0);
+        
+        let many: String = (0..20_000).map(|n| format!("app.Package{}/x86_64/stable\n", n)).collect();
+        assert_eq!(count_nonempty_lines(&many, 0), 20_000);
+        assert_eq!(count_nonempty_lines("Name Version Rev\n", 1), 0);
+        assert_eq!(count_nonempty_lines("", 1), 0);
+        
+        let dpkg = "Package: bash\nStatus: install ok installed\nVersion: 5.2.15-2\n\n\
+            Package: vim\nStatus: deinstall ok config-files\n\n\
+            Package: zsh\nStatus: install ok installed\n";
+        assert_eq!(count_dpkg_packages(dpkg), 2);
+        assert_eq!(count_dpkg_packages(""), 0);
+        
+        let dir = TestDir::new("counts_packages");
+        fs::create_dir_all(dir.join("bash-5.2.026-2")).unwrap();
+        fs::create_dir_all(dir.join("zsh-5.9-5")).unwrap();
+        fs::write(dir.join("ALPM_DB_VERSION"), "9\n").unwrap();
+        assert_eq!(count_pacman_packages(&dir), Some(2));
+        assert_eq!(count_pacman_packages(&dir.join("missing")), None);
+        assert_eq!(format_package_count(1450, "pacman", None, ""), "1450 (pacman)");
+    }
+
+    #[test]
+    fn counts_pending_updates() {
+        assert_eq!(count_checkupdates("linux 6.9.1.arch1-1 -> 6.9.2.arch1-1\nmesa 1:24.1.0-1 -> 1:24.1.1-1\n"), 2);
+        assert_eq!(count_checkupdates(""), 0);
+        
+        let apt = "\nWARNING: apt does not have a stable CLI interface. Use with caution in scripts.\n\n\
+            Listing...\n\
+            libssl3/stable-security 3.0.13-1~deb12u1 amd64 [upgradable from: 3.0.11-1~deb12u2]\n\
+            openssl/stable-security 3.0.13-1~deb12u1 amd64 [upgradable from: 3.0.11-1~deb12u2]\n";
+        assert_eq!(count_apt_upgradable(apt), 2);
+        assert_eq!(count_apt_upgradable("Listing...\n"), 0);
+        
+        let dnf = "\n\
+            kernel.x86_64                 6.8.10-300.fc40          updates\n\
+            mesa-libGL.x86_64             24.0.7-1.fc40            updates\n\
+            Obsoleting Packages\n\
+            grub2-tools.x86_64            1:2.06-121.fc40          updates\n";
+        assert_eq!(count_dnf_updates(dnf), 2);
+        assert_eq!(count_dnf_updates("Last metadata expiration check: 0:12:03 ago on Mon 13 May 2024.\n"), 0);
+    }
+
+    #[test]
+    fn reads_update_exit_codes() {
+        let arch = "linux 6.9.1.arch1-1 -> 6.9.2.arch1-1\n";
+        assert_eq!(pending_updates("checkupdates", Some(0), arch), Some(1));
+        assert_eq!(pending_updates("checkupdates", Some(2), ""), Some(0));
+        assert_eq!(pending_updates("checkupdates", Some(1), ""), None);
+        
+        let apt = "Listing...\nopenssl/stable-security 3.0.13-1~deb12u1 amd64 [upgradable from: 3.0.11-1~deb12u2]\n";
+        assert_eq!(pending_updates("apt", Some(0), apt), Some(1));
+        assert_eq!(pending_updates("apt", Some(100), ""), None);
+        
+        let dnf = "kernel.x86_64                 6.8.10-300.fc40          updates\n";
+        assert_eq!(pending_updates("dnf", Some(100), dnf), Some(1));
+        assert_eq!(pending_updates("dnf", Some(0), ""), Some(0));
+        assert_eq!(pending_updates("dnf", Some(1), ""), None);
+        
+        // Killed by a signal
+        assert_eq!(pending_updates("checkupdates", None, ""), None);
+    }
+
+    #[test]
+    fn formats_arch_names() {
+        assert_eq!(format_arch("x86_64", ArchFormat::Raw), "x86_64");
+        assert_eq!(format_arch("AMD64", ArchFormat::Raw), "x86_64");
+        assert_eq!(format_arch("x86_64", ArchFormat::Common), "x64");
+        assert_eq!(format_arch("aarch64", ArchFormat::Common), "arm64");
+        assert_eq!(format_arch("arm64", ArchFormat::Raw), "aarch64");
+        assert_eq!(format_arch("i686", ArchFormat::Common), "x86");
+        assert_eq!(format_arch("arm", ArchFormat::Common), "arm32");
+        assert_eq!(format_arch("riscv64", ArchFormat::Common), "riscv64");
+    }
+
+    #[test]
+    fn names_default_terminals() {
+        assert_eq!(terminal_from_path("/usr/bin/gnome-terminal.wrapper").as_deref(), Some("gnome-terminal"));
+        assert_eq!(terminal_from_path("kitty --single-instance").as_deref(), Some("kitty"));
+        assert_eq!(terminal_from_path("").as_deref(), None);
+        assert_eq!(terminal_from_desktop_file("foot.desktop\n").as_deref(), Some("foot"));
+        assert_eq!(terminal_from_desktop_file("org.wezfurlong.wezterm.desktop").as_deref(), Some("wezterm"));
+        assert_eq!(terminal_from_desktop_file("").as_deref(), None);
+    }
+
+    #[test]
+    fn reads_swappiness() {
+        let dir = TestDir::new("reads_swappiness");
+        fs::write(dir.join("swappiness"), "10\n").unwrap();
+        assert_eq!(get_swappiness(&dir), Some((10, None)));
+        fs::write(dir.join("vfs_cache_pressure"), "50\n").unwrap();
+        assert_eq!(get_swappiness(&dir), Some((10, Some(50))));
+        
+        let info = SystemInfo { swappiness: get_swappiness(&dir), ..SystemInfo::default() };
+        assert_eq!(module_lines(&info, &Config::default(), "swappiness"), ["Swappiness: 10", "VFS Cache Pressure: 50"]);
+        assert_eq!(get_swappiness(&dir.join("missing")), None);
+    }
+
+    #[test]
+    fn parses_memory_slots() {
+        let output = "# dmidecode 3.5\nGetting SMBIOS data from sysfs.\nSMBIOS 3.3.0 present.\n\n\
+            Handle 0x0040, DMI type 16, 23 bytes\nPhysical Memory Array\n\tLocation: System Board Or Motherboard\n\
+            \tMaximum Capacity: 128 GB\n\tNumber Of Devices: 4\n\n\
+            Handle 0x0041, DMI type 17, 92 bytes\nMemory Device\n\tSize: 16 GB\n\tLocator: DIMM_A1\n\n\
+            Handle 0x0042, DMI type 17, 92 bytes\nMemory Device\n\tSize: No Module Installed\n\tLocator: DIMM_A2\n\n\
+            Handle 0x0043, DMI type 17, 92 bytes\nMemory Device\n\tSize: 16384 MB\n\tLocator: DIMM_B1\n\n\
+            Handle 0x0044, DMI type 17, 92 bytes\nMemory Device\n\tSize: 8 GB\n\tLocator: DIMM_B2\n";
+        let (slots, modules) = parse_dmidecode_memory(output).unwrap();
+        assert_eq!((slots, modules.as_slice()), (4, [16 << 30, 16 << 30, 8 << 30].as_slice()));
+        assert_eq!(format_ram_slots(slots, &modules), "3/4 used, 40 GiB (2x16 GiB + 1x8 GiB)");
+        // Without root, dmidecode prints its banner and nothing else
+        assert_eq!(parse_dmidecode_memory("# dmidecode 3.5\n"), None);
+
+        assert_eq!(parse_windows_memory_slots("4 17179869184 17179869184"), Some((4, vec![16 << 30, 16 << 30])));
+        assert_eq!(parse_windows_memory_slots("0 4294967296"), Some((1, vec![4 << 30])));
+        assert_eq!(parse_windows_memory_slots(""), None);
+        assert_eq!(format_ram_slots(2, &[16 << 30, 16 << 30]), "2/2 used, 32 GiB (2x16 GiB)");
+        assert_eq!(format_ram_slots(2, &[]), "0/2 used, 0 GiB");
+        assert_eq!(format_ram_slots(1, &[512 << 20]), "1/1 used, 512 MiB (1x512 MiB)");
+
+        let info = SystemInfo { ram_slots: Some((4, vec![16 << 30, 16 << 30])), ..SystemInfo::default() };
+        assert_eq!(module_lines(&info, &Config::default(), "ram_slots"), ["RAM Slots: 2/4 used, 32 GiB (2x16 GiB)"]);
+    }
+
+    #[test]
+    fn parses_keyboard_layouts() {
+        let setxkbmap = "rules:      evdev\nmodel:      pc105\nlayout:     us,de\nvariant:    dvorak,\noptions:    grp:alt_shift_toggle\n";
+        assert_eq!(parse_xkb_settings(setxkbmap, "layout:", "variant:").as_deref(), Some("us (dvorak), de"));
+        let localectl = "System Locale: LANG=en_GB.UTF-8\n    VC Keymap: uk\n   X11 Layout: gb\n    X11 Model: pc105\n";
+        assert_eq!(parse_xkb_settings(localectl, "X11 Layout:", "X11 Variant:").as_deref(), Some("gb"));
+        assert_eq!(parse_xkb_settings("VC Keymap: n/a\n", "X11 Layout:", "X11 Variant:"), None);
+        
+        let hyprctl = "Keyboards:\n\tKeyboard at 5d0e2c40:\n\t\tpower-button\n\t\t\trules: r \"\", m \"\", l \"us\", v \"\", o \"\"\n\t\t\tmain: no\n\
+                       \tKeyboard at 5d0f1b20:\n\t\tat-translated-set-2-keyboard\n\t\t\trules: r \"\", m \"\", l \"us,de\", v \"dvorak,\", o \"\"\n\t\t\tactive keymap: English (Dvorak)\n\t\t\tmain: yes\n";
+        assert_eq!(parse_hyprctl_keyboard(hyprctl).as_deref(), Some("us (dvorak), de"));
+        assert_eq!(parse_hyprctl_keyboard(&hyprctl.replace("main: yes", "main: no")).as_deref(), Some("us"));
+        
+        let sway = r#"[{"identifier": "1:1:AT_Translated_Set_2_keyboard", "type": "keyboard", "xkb_layout_names": ["English (US)", "German"],
+                        "xkb_active_layout_name": "English (Dvorak)", "libinput": {"send_events": "enabled"}},
+                       {"identifier": "2:7:SynPS/2_Synaptics_TouchPad", "type": "touchpad"}]"#;
+        assert_eq!(parse_sway_keyboard(sway).as_deref(), Some("English (Dvorak)"));
+        assert_eq!(parse_sway_keyboard(r#"[{"type": "touchpad"}]"#), None);
+        
+        assert_eq!(format_windows_keyboard("en-US\t0409:00010409"), "en-US (Dvorak)");
+        assert_eq!(format_windows_keyboard("de-DE\t0407:00000407"), "de-DE (German)");
+        assert_eq!(format_windows_keyboard("fi-FI\t040B:0000040B"), "fi-FI");
+    }
+
+    #[test]
+    fn counts_logged_in_users() {
+        let record = |ut_type: i16| {
+            let mut record = vec![0u8; UTMP_RECORD_SIZE];
+            record[..2].copy_from_slice(&ut_type.to_ne_bytes());
+            record
+        };
+        // A boot record, two sessions and a dead one
+        let utmp = [record(2), record(7), record(7), record(8)].concat();
+        assert_eq!(count_utmp_sessions(&utmp), 2);
+        assert_eq!(count_utmp_sessions(&utmp[..UTMP_RECORD_SIZE + 10]), 0);
+        
+        let query = " USERNAME              SESSIONNAME        ID  STATE   IDLE TIME  LOGON TIME\n\
+                     >alex                  console             1  Active      none   5/1/2024 9:02 AM\n\
+                      sam                   rdp-tcp#0           2  Active          .  5/1/2024 10:15 AM\n";
+        assert_eq!(count_query_user(query), 2);
+        assert_eq!(count_query_user(""), 0);
+    }
+
+    #[test]
+    fn parses_load_averages() {
+        assert_eq!(parse_load_average("0.52 0.61 0.48 1/523 4242\n").as_deref(), Some("0.52, 0.61, 0.48"));
+        assert_eq!(parse_load_average("{ 1.5 2.25 10.00 }\n").as_deref(), Some("1.50, 2.25, 10.00"));
+        assert_eq!(parse_load_average("0.52 0.61"), None);
+        assert_eq!(parse_load_average("high"), None);
+    }
+
+    #[test]
+    fn filters_gpus() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let exclude = Config::default().gpu_exclude;
+        assert!(!gpu_matches("Microsoft Basic Display Adapter (Unknown) [Integrated]", &[], &exclude));
+        assert!(!gpu_matches("VMware SVGA 3D (0.12 GiB) [Integrated]", &[], &exclude));
+        assert!(gpu_matches("NVIDIA GeForce RTX 4070 (12.00 GiB) [Discrete] - 552.22", &[], &exclude));
+        assert!(gpu_matches("NVIDIA GeForce RTX 4070", &names(&["nvidia"]), &[]));
+        assert!(!gpu_matches("Intel(R) UHD Graphics 770", &names(&["nvidia"]), &[]));
+        assert!(!gpu_matches("NVIDIA GeForce RTX 4070", &names(&["nvidia"]), &names(&["4070"])));
+    }
+
+    #[test]
+    fn filters_gpu_lines() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let info = SystemInfo {
+            gpu: names(&["VMware SVGA 3D", "NVIDIA GeForce RTX 4070"]),
+            active_gpu: Some("VMware SVGA 3D".to_string()),
+            gpu_displays: names(&["GPU 1 (VMware): Virtual-1", "GPU 2 (NVIDIA): DP-1", "GPU 3 (0x1234): no displays"]),
+            ..SystemInfo::default()
+        };
+        let exclude = Config::default().gpu_exclude;
+        assert_eq!(
+            gpu_lines(&info, &[], &exclude),
+            ["GPU: NVIDIA GeForce RTX 4070", "GPU 2 (NVIDIA): DP-1", "GPU 3 (0x1234): no displays"]
+        );
+        // Filters that match nothing are ignored rather than hiding every GPU
+        assert_eq!(gpu_lines(&info, &names(&["Radeon"]), &[]).len(), 6);
+        let vm = SystemInfo { gpu: names(&["VMware SVGA 3D"]), gpu_displays: Vec::new(), ..info };
+        assert_eq!(gpu_lines(&vm, &[], &exclude), ["GPU: VMware SVGA 3D", "GPU (active): VMware SVGA 3D"]);
+    }
+
+    #[test]
+    fn reads_git_heads() {
+        assert_eq!(git_head_name("ref: refs/heads/main\n").as_deref(), Some("main"));
+        assert_eq!(git_head_name("ref: refs/heads/feature/vpn\n").as_deref(), Some("feature/vpn"));
+        assert_eq!(git_head_name("3f786850e387550fdab836ed7e6dc881de23001b\n").as_deref(), Some("3f78685"));
+        assert_eq!(git_head_name("garbage"), None);
+        
+        let dir = TestDir::new("reads_git_heads");
+        fs::create_dir_all(dir.join("repo/.git")).unwrap();
+        fs::create_dir_all(dir.join("repo/src/nested")).unwrap();
+        fs::create_dir_all(dir.join("worktree")).unwrap();
+        fs::write(dir.join("worktree/.git"), "gitdir: ../repo/.git/worktrees/wt\n").unwrap();
+        assert_eq!(find_git_dir(&dir.join("repo/src/nested")), Some(dir.join("repo/.git")));
+        assert_eq!(find_git_dir(&dir.join("worktree")), Some(dir.join("worktree/../repo/.git/worktrees/wt")));
+    }
+
+    #[test]
+    fn picks_local_ip() {
+        let interface = |name: &str, up, loopback, address: [u8; 4], prefix| InterfaceAddress {
+            name: name.to_string(),
+            up,
+            loopback,
+            address: address.into(),
+            prefix,
+        };
+        let interfaces = [
+            interface("lo", true, true, [127, 0, 0, 1], 8),
+            interface("eth0", false, false, [10, 0, 0, 5], 8),
+            interface("wlan0", true, false, [169, 254, 3, 7], 16),
+            interface("wlan0", true, false, [192, 168, 1, 20], 24),
+        ];
+        assert_eq!(format_local_ip(&interfaces), "Local IP (wlan0): 192.168.1.20/24");
+        assert_eq!(format_local_ip(&interfaces[..3]), "No active network connection");
+    }
+
+    #[test]
+    fn detects_vpn_interfaces() {
+        assert!(is_vpn_interface("wg0"));
+        assert!(is_vpn_interface("tun0"));
+        assert!(is_vpn_interface("tailscale0"));
+        assert!(!is_vpn_interface("eth0"));
+        assert!(!is_vpn_interface("wlp3s0"));
+        assert!(interface_is_up("0x1091\n"));
+        assert!(!interface_is_up("0x1002\n"));
+        assert!(!interface_is_up("garbage"));
+        assert_eq!(parse_windows_vpn_adapters("WireGuard Tunnel\r\nTailscale\r\n--end--"), Some(vec!["WireGuard Tunnel".to_string(), "Tailscale".to_string()]));
+        assert_eq!(parse_windows_vpn_adapters("--end--"), Some(Vec::new()));
+        assert_eq!(parse_windows_vpn_adapters("Get-NetAdapter : Access is denied."), None);
+        
+        let config = Config::default();
+        let info = SystemInfo { vpn: Some(vec!["wg0".to_string(), "tun0".to_string()]), ..SystemInfo::default() };
+        assert_eq!(module_lines(&info, &config, "vpn"), ["VPN: wg0, tun0 (active)"]);
+        let info = SystemInfo { vpn: Some(Vec::new()), ..SystemInfo::default() };
+        assert_eq!(module_lines(&info, &config, "vpn"), ["VPN: none"]);
+    }
+
+    #[test]
+    fn formats_timezones() {
+        assert_eq!(format_timezone("Europe/London", Some("BST"), 3600), "Europe/London (BST, +01:00)");
+        assert_eq!(format_timezone("Asia/Kolkata", Some("IST"), 19800), "Asia/Kolkata (IST, +05:30)");
+        assert_eq!(format_timezone("America/Sao_Paulo", Some("-03"), -10800), "America/Sao_Paulo (-03:00)");
+        assert_eq!(format_timezone("UTC", Some("UTC"), 0), "UTC (UTC, +00:00)");
+        assert_eq!(format_timezone("GMT Standard Time", None, 3600), "GMT Standard Time (+01:00)");
+        assert_eq!(zone_name("/usr/share/zoneinfo/Europe/London").as_deref(), Some("Europe/London"));
+        assert_eq!(zone_name("/var/db/timezone/zoneinfo/America/New_York").as_deref(), Some("America/New_York"));
+        assert_eq!(zone_name("/etc/localtime.bak"), None);
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(1710201600), "2024-03-12");
+        // Leap day, and the last second of a year
+        assert_eq!(format_date(951782400), "2000-02-29");
+        assert_eq!(format_date(1704067199), "2023-12-31");
+    }
+
+    #[test]
+    fn detects_power_source() {
+        let supply = |kind: &str, online, status: &str| PowerSupply {
+            kind: kind.to_string(),
+            online,
+            status: status.to_string(),
+        };
+        assert_eq!(power_source(&[supply("Mains", true, ""), supply("Battery", false, "Charging")]), "AC");
+        assert_eq!(power_source(&[supply("Mains", false, ""), supply("Battery", false, "Discharging")]), "Battery");
+        // Battery only, or nothing at all (a desktop)
+        assert_eq!(power_source(&[supply("Battery", false, "Discharging")]), "Battery");
+        assert_eq!(power_source(&[supply("Battery", false, "Full")]), "AC");
+        assert_eq!(power_source(&[]), "AC");
+    }
+
+    #[test]
+    fn parses_root_mount() {
+        let root = parse_root_mount(fixture!("fedora", "mounts")).unwrap();
+        assert_eq!((root.source.as_str(), root.fs_type.as_str()), ("/dev/nvme0n1p3", "btrfs"));
+        assert_eq!(btrfs_compression(&root.options).as_deref(), Some("zstd"));
+        
+        let root = parse_root_mount(fixture!("debian", "mounts")).unwrap();
+        assert_eq!(root.fs_type, "ext4");
+        
+        // An overlay mounted over / hides the original
+        let root = parse_root_mount("/dev/sda1 / ext4 rw 0 0\noverlay / overlay rw,lowerdir=/a 0 0\n").unwrap();
+        assert_eq!(root.fs_type, "overlay");
+        assert!(parse_root_mount("proc /proc proc rw 0 0\n").is_none());
+        
+        assert_eq!(btrfs_compression("rw,compress-force=lzo").as_deref(), Some("lzo"));
+        assert_eq!(btrfs_compression("rw,compress=no"), None);
+        assert_eq!(btrfs_compression("rw,relatime"), None);
+    }
+
+    #[test]
+    fn detects_window_manager_versions() {
+        let unset = |_: &str| false;
+        let name = |wm: Option<&KnownWm>| wm.map(|wm| wm.name);
+        assert_eq!(name(known_window_manager(|var| var == "SWAYSOCK", Some("GNOME"))), Some("sway"));
+        assert_eq!(name(known_window_manager(unset, Some("Hyprland"))), Some("Hyprland"));
+        assert_eq!(name(known_window_manager(unset, Some("ubuntu:bspwm"))), Some("bspwm"));
+        assert_eq!(name(known_window_manager(unset, Some("KDE"))), None);
+        assert_eq!(name(known_window_manager(unset, None)), None);
+        
+        assert_eq!(
+            parse_shell_version("Hyprland 0.41.2 built from branch  at commit 918d8340afd652b011b937d29d5eea0be08467f5  (flake: 0.41.2).\n").as_deref(),
+            Some("0.41.2")
+        );
+        assert_eq!(parse_shell_version("sway version 1.9\n").as_deref(), Some("1.9"));
+        assert_eq!(parse_shell_version("i3 version 4.23 (2023-10-29) © 2009 Michael Stapelberg and contributors\n").as_deref(), Some("4.23"));
+        assert_eq!(parse_shell_version("0.9.10\n").as_deref(), Some("0.9.10"));
+    }
+
+    #[test]
+    fn formats_windows_gpu_drivers() {
+        assert_eq!(
+            format_windows_gpu("NVIDIA GeForce RTX 4070 (12.00 GiB) [Discrete]\t31.0.15.5222"),
+            "NVIDIA GeForce RTX 4070 (12.00 GiB) [Discrete] - 552.22"
+        );
+        assert_eq!(
+            format_windows_gpu("AMD Radeon(TM) Graphics (0.50 GiB) [Integrated]\t31.0.21912.14"),
+            "AMD Radeon(TM) Graphics (0.50 GiB) [Integrated] - 31.0.21912.14"
+        );
+        // DriverVersion is null for e.g. the Basic Display Adapter
+        assert_eq!(
+            format_windows_gpu("Microsoft Basic Display Adapter (Unknown) [Integrated]\t"),
+            "Microsoft Basic Display Adapter (Unknown) [Integrated]"
+        );
+        assert_eq!(nvidia_driver_version("27.21.14.5671").as_deref(), Some("456.71"));
+        assert_eq!(nvidia_driver_version("1.2"), None);
+    }
+
+    #[test]
+    fn reads_windows_fallbacks() {
+        let current_version = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\r\n\
+            \x20   SystemRoot    REG_SZ    C:\\WINDOWS\r\n\
+            \x20   CurrentBuild    REG_SZ    22631\r\n\
+            \x20   ProductName    REG_SZ    Windows 10 Pro\r\n";
+        let values = parse_reg_values(current_version);
+        assert_eq!(values[1], ("CurrentBuild", "22631"));
+        assert_eq!(windows_product_name(&values).as_deref(), Some("Windows 11 Pro"));
+        assert_eq!(windows_product_name(&[("ProductName", "Windows 10 Home"), ("CurrentBuild", "19045")]).as_deref(), Some("Windows 10 Home"));
+        assert_eq!(windows_product_name(&[]), None);
+        
+        let adapters = "HKEY_LOCAL_MACHINE\\...\\0000\r\n    DriverDesc    REG_SZ    NVIDIA GeForce RTX 4070\r\n\r\n\
+            HKEY_LOCAL_MACHINE\\...\\0001\r\n    DriverDesc    REG_SZ    Intel(R) UHD Graphics 770\r\n\r\nEnd of search: 2 match(es) found.\r\n";
+        assert_eq!(
+            parse_reg_values(adapters),
+            [("DriverDesc", "NVIDIA GeForce RTX 4070"), ("DriverDesc", "Intel(R) UHD Graphics 770")]
+        );
+        
+        let wmic = "\r\r\n\r\r\nFreePhysicalMemory=8388608\r\r\nTotalVisibleMemorySize=16777216\r\r\n\r\r\n";
+        assert_eq!(parse_wmic_memory(wmic), Some((8 << 30, 16 << 30)));
+        assert_eq!(parse_wmic_memory("FreePhysicalMemory=1\r\n"), None);
+    }
+
+    #[test]
+    fn parses_firmware_type() {
+        assert_eq!(parse_firmware_type("UEFI\r\n"), Some("UEFI"));
+        assert_eq!(parse_firmware_type("Legacy"), Some("BIOS"));
+        assert_eq!(parse_firmware_type("Unknown"), None);
+        assert_eq!(parse_firmware_type(""), None);
+    }
+
+    #[test]
+    fn parses_tpm_versions() {
+        assert_eq!(tpm_version("2\n").as_deref(), Some("2.0"));
+        assert_eq!(tpm_version("1\n").as_deref(), Some("1.2"));
+        assert_eq!(tpm_version(""), None);
+
+        assert_eq!(parse_tpm_spec_version("2.0, 0, 1.59").as_deref(), Some("2.0"));
+        assert_eq!(parse_tpm_spec_version("1.2, 2, 3").as_deref(), Some("1.2"));
+        assert_eq!(parse_tpm_spec_version(""), None);
+    }
+
+    #[test]
+    fn parses_kern_boottime() {
+        assert_eq!(
+            parse_kern_boottime("{ sec = 1700000000, usec = 123456 } Tue Nov 14 22:13:20 2023"),
+            Some(1700000000)
+        );
+        assert_eq!(parse_kern_boottime("{ sec = 1700000000 }"), Some(1700000000));
+        assert_eq!(parse_kern_boottime("Tue Nov 14 22:13:20 2023"), None);
+    }
+
+    #[test]
+    fn formats_cmdline() {
+        let cmdline = "BOOT_IMAGE=/vmlinuz-linux root=UUID=1234 rw quiet splash nvidia-drm.modeset=1 mitigations=off\n";
+        let flags = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            format_cmdline(cmdline, &flags(&["quiet", "splash", "nvidia-drm.modeset", "init"]), 60),
+            "quiet splash nvidia-drm.modeset=1"
+        );
+        assert_eq!(format_cmdline(cmdline, &flags(&["mitigations"]), 60), "mitigations=off");
+        assert_eq!(format_cmdline(cmdline, &[], 200), cmdline.trim());
+        assert_eq!(format_cmdline(cmdline, &[], 20), "BOOT_IMAGE=/vmlinuz…");
+        assert_eq!(format_cmdline(cmdline, &flags(&["init"]), 60), "");
+    }
+
+    #[test]
+    fn parses_workspaces() {
+        let hyprland = "workspace ID 1 (1) on monitor eDP-1:\n\tmonitorID: 0\n\twindows: 2\n\n\
+            workspace ID 2 (web) on monitor DP-1:\n\tmonitorID: 1\n\twindows: 1\n";
+        assert_eq!(parse_hyprland_workspaces(hyprland), ["1", "web"]);
+        assert!(parse_hyprland_workspaces("").is_empty());
+        
+        // Trimmed i3-msg output; swaymsg's is the same, pretty-printed
+        let i3 = r#"[{"id":94,"num":1,"name":"1","visible":true,"focused":false,"rect":{"x":0,"y":0},"output":"eDP-1"},
+            {"id":95,"num":2,"name":"2: {web}","visible":true,"focused":true,"rect":{"x":0,"y":0},"output":"eDP-1"},
+            {"id":96,"num":3,"name":"3","visible":false,"focused":false,"rect":{"x":0,"y":0},"output":"eDP-1"}]"#;
+        assert_eq!(parse_i3_workspaces(i3), Some((3, "2: {web}".to_string())));
+        let sway = "[\n  {\n    \"name\": \"1\",\n    \"focused\": true,\n    \"rect\": {\n      \"x\": 0\n    }\n  }\n]";
+        assert_eq!(parse_i3_workspaces(sway), Some((1, "1".to_string())));
+        assert_eq!(parse_i3_workspaces("[]"), None);
+        let quoted = r#"[{"num":1,"name":"1: \"dev\"","focused":true}]"#;
+        assert_eq!(parse_i3_workspaces(quoted), Some((1, "1: \"dev\"".to_string())));
+    }
+
+    #[test]
+    fn parses_tearing_settings() {
+        assert_eq!(parse_hyprctl_bool("int: 1\nset: true\n"), Some(true));
+        assert_eq!(parse_hyprctl_bool("bool: false\nset: false\n"), Some(false));
+        assert_eq!(parse_hyprctl_bool("no such option\n"), None);
+
+        assert_eq!(parse_picom_vsync("backend = \"glx\";\nvsync = true;\n"), Some(true));
+        assert_eq!(parse_picom_vsync("vsync=false # games\n"), Some(false));
+        assert_eq!(parse_picom_vsync("# vsync = true;\nshadow = true;\n"), None);
+    }
+
+    #[test]
+    fn parses_vram_use() {
+        assert_eq!(parse_nvidia_smi_memory("3277, 12282\n"), Some((3277 << 20, 12282 << 20)));
+        assert_eq!(parse_nvidia_smi_memory("[N/A], [N/A]"), None);
+        assert_eq!(parse_nvidia_smi_memory("0, 0"), None);
+        assert_eq!(format_vram(3277 << 20, 12288 << 20), "3.2 GiB / 12 GiB (27%)");
+        assert_eq!(format_vram(0, 8 << 30), "0 GiB / 8 GiB (0%)");
+    }
+
+    #[test]
+    fn parses_gpu_clocks() {
+        assert_eq!(parse_nvidia_smi_clocks("2520, 10501\n1755, 7001\n"), Some((2520, 10501)));
+        assert_eq!(parse_nvidia_smi_clocks("[N/A], [N/A]\n"), None);
+
+        assert_eq!(parse_dpm_clock("0: 500Mhz\n1: 1800Mhz\n2: 2520Mhz *\n"), Some(2520));
+        assert_eq!(parse_dpm_clock("0: 96Mhz *\n1: 1000Mhz\n"), Some(96));
+        assert_eq!(parse_dpm_clock("0: 500Mhz\n1: 2100Mhz\n"), None);
+
+        assert_eq!(format_gpu_clock(2520, Some(10500)), "2520 MHz core / 10500 MHz mem");
+        assert_eq!(format_gpu_clock(2520, None), "2520 MHz core");
+    }
+
+    #[test]
+    fn parses_glx_renderer() {
+        assert_eq!(
+            parse_glx_renderer(fixture!("arch", "glxinfo-nvidia")).as_deref(),
+            Some("NVIDIA GeForce RTX 4060 Laptop GPU")
+        );
+        assert_eq!(
+            parse_glx_renderer(fixture!("fedora", "glxinfo-mesa")).as_deref(),
+            Some("AMD Radeon 780M")
+        );
+        assert_eq!(
+            parse_glx_renderer("OpenGL renderer string: AMD Radeon RX 6800/6800 XT (radeonsi, navi21)\n").as_deref(),
+            Some("AMD Radeon RX 6800/6800 XT")
+        );
+        assert_eq!(parse_glx_renderer("Error: unable to open display\n"), None);
+    }
+
+    #[test]
+    fn parses_macos_gpus() {
+        assert_eq!(
+            parse_macos_gpus(fixture!("macos", "SPDisplaysDataType-m1")),
+            ["Apple M1 (8 cores) [Integrated]"]
+        );
+        assert_eq!(
+            parse_macos_gpus(fixture!("macos", "SPDisplaysDataType-m2pro")),
+            ["Apple M2 Pro (19 cores) [Integrated]"]
+        );
+        assert_eq!(
+            parse_macos_gpus(fixture!("macos", "SPDisplaysDataType-m3max")),
+            ["Apple M3 Max (40 cores) [Integrated]"]
+        );
+        assert_eq!(
+            parse_macos_gpus(fixture!("macos", "SPDisplaysDataType-intel")),
+            [
+                "Intel UHD Graphics 630 (1536 MB) [Integrated]",
+                "AMD Radeon Pro 5500M (4 GB) [Discrete]",
+            ]
+        );
+        assert!(parse_macos_gpus("").is_empty());
+    }
+
+    #[test]
+    fn parses_meminfo() {
+        let mem_data = parse_meminfo(fixture!("arch", "meminfo"));
+        assert_eq!(mem_data.get("MemTotal"), Some(&(65007340 * 1024)));
+        assert_eq!(mem_data.get("SwapFree"), Some(&(8388604 * 1024)));
+        // Fields without a unit are still parsed
+        assert_eq!(mem_data.get("HugePages_Total"), Some(&0));
+        
+        // A value that overflows in bytes is dropped, not wrapped or panicked on
+        let mem_data = parse_meminfo(fixture!("debian", "meminfo-overflow"));
+        assert_eq!(mem_data.get("SwapTotal"), None);
+        assert_eq!(mem_data.get("MemTotal"), Some(&(2030696 * 1024)));
+    }
+
+    #[test]
+    fn formats_meminfo_usage() {
+        let usage = |meminfo| format_meminfo_usage(&parse_meminfo(meminfo), Precision::default());
+        assert_eq!(usage(fixture!("arch", "meminfo")).as_deref(), Some("6.70 GiB / 62.00 GiB (10%)"));
+        assert_eq!(usage(fixture!("debian", "meminfo")).as_deref(), Some("0.36 GiB / 1.94 GiB (18%)"));
+        assert_eq!(usage(fixture!("fedora", "meminfo")).as_deref(), Some("6.39 GiB / 15.34 GiB (41%)"));
+        assert_eq!(usage(fixture!("raspbian", "meminfo")).as_deref(), Some("0.46 GiB / 3.70 GiB (12%)"));
+        assert_eq!(usage("MemTotal: 1024 kB\n"), None);
+    }
+
+    #[test]
+    fn excludes_reclaimable_zfs_arc() {
+        let arcstats = fixture!("debian", "arcstats");
+        assert_eq!(parse_arc_reclaimable(arcstats), Some(6442450944 - 1041727488));
+        // An ARC at or below its minimum has nothing to give back
+        assert_eq!(parse_arc_reclaimable("size 4 100\nc_min 4 200\n"), Some(0));
+        assert_eq!(parse_arc_reclaimable("name type data\n"), None);
+
+        let mut mem_data = parse_meminfo("MemTotal: 16777216 kB\nMemAvailable: 2097152 kB\n");
+        assert_eq!(format_meminfo_usage(&mem_data, Precision::default()).as_deref(), Some("14.00 GiB / 16.00 GiB (87%)"));
+        mem_data.entry("MemAvailable").and_modify(|available| *available += parse_arc_reclaimable(arcstats).unwrap());
+        assert_eq!(format_meminfo_usage(&mem_data, Precision::default()).as_deref(), Some("8.97 GiB / 16.00 GiB (56%)"));
+    }
+
+    #[test]
+    fn formats_usage_at_each_precision() {
+        let (used, total) = (8_700_000_000, 16 * 1024 * 1024 * 1024);
+        let precision = |decimals, percentages| Precision { decimals, percentages };
+        let truncated = |decimals| format_usage(used, total, precision(decimals, PercentRounding::Truncate));
+        assert_eq!(truncated(0), "8 GiB / 16 GiB (50%)");
+        assert_eq!(truncated(1), "8.1 GiB / 16.0 GiB (50%)");
+        assert_eq!(truncated(2), "8.10 GiB / 16.00 GiB (50%)");
+        assert_eq!(truncated(3), "8.103 GiB / 16.000 GiB (50%)");
+        assert_eq!(format_usage(used, total, precision(2, PercentRounding::Round)), "8.10 GiB / 16.00 GiB (51%)");
+
+        let mem_data = parse_meminfo("MemTotal: 16384 kB\nMemAvailable: 4000 kB\nSwapTotal: 4096 kB\nSwapFree: 1 kB\n");
+        let rounded = precision(0, PercentRounding::Round);
+        assert_eq!(format_meminfo_usage(&mem_data, rounded).as_deref(), Some("0 GiB / 0 GiB (76%)"));
+        assert_eq!(format_meminfo_swap(&mem_data, rounded).as_deref(), Some("0 GiB / 0 GiB (100%)"));
+        assert_eq!(
+            format_windows_disk("C:|130000000000|511101108224|NTFS", rounded).as_deref(),
+            Some("Disk (C:): 121 GiB / 476 GiB (25%) - NTFS")
+        );
+        assert_eq!(
+            format_windows_disk("D:|0|0|", Precision::default()).as_deref(),
+            Some("Disk (D:): disabled")
+        );
+        assert_eq!(format_windows_disk("", Precision::default()), None);
+        assert_eq!(parse_byte_pair("1024 4096\r\n"), Some((1024, 4096)));
+    }
+
+    #[test]
+    fn formats_zero_totals() {
+        assert_eq!(format_usage(0, 0, Precision::default()), "disabled");
+        assert_eq!(format_usage(512, 0, Precision::default()), "disabled");
+        let mem_data = parse_meminfo("MemTotal: 0 kB\nMemAvailable: 0 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n");
+        assert_eq!(format_meminfo_usage(&mem_data, Precision::default()).as_deref(), Some("disabled"));
+        assert_eq!(format_meminfo_swap(&mem_data, Precision::default()).as_deref(), Some("No swap"));
+        // Nothing for the short format to rewrite either, memory or disk
+        assert_eq!(format_memory("disabled", MemoryFormat::Short), "disabled");
+        let config = Config { memory_format: MemoryFormat::Short, ..Config::default() };
+        let info = SystemInfo { disk: vec!["Disk (D:): disabled".to_string()], ..SystemInfo::default() };
+        assert_eq!(module_lines(&info, &config, "disk"), ["Disk (D:): disabled"]);
+    }
+
+    #[test]
+    fn formats_meminfo_swap() {
+        let swap = |meminfo| format_meminfo_swap(&parse_meminfo(meminfo), Precision::default());
+        assert_eq!(swap(fixture!("arch", "meminfo")).as_deref(), Some("0.00 GiB / 8.00 GiB (0%)"));
+        assert_eq!(swap(fixture!("debian", "meminfo")).as_deref(), Some("No swap"));
+        assert_eq!(swap(fixture!("fedora", "meminfo")).as_deref(), Some("0.50 GiB / 8.00 GiB (6%)"));
+        assert_eq!(swap("SwapTotal: 1024 kB\n"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_kernel_sysinfo() {
+        let counts = kernel_sysinfo().unwrap();
+        assert!(counts.total_ram > 0);
+        assert!(counts.available_ram <= counts.total_ram);
+        assert!(counts.free_swap <= counts.total_swap);
+    }
+
+    #[test]
+    fn notes_missing_proc() {
+        let dir = TestDir::new("notes_missing_proc");
+        assert!(!proc_mounted(&dir));
+        let config = Config { modules: Some(vec!["os".to_string(), "cpu".to_string(), "load".to_string()]), ..Config::default() };
+        let info = SystemInfo { cpu: "unknown".to_string(), load: None, ..SystemInfo::default() };
+        let note = proc_missing_note(&info, &config, &dir);
+        if cfg!(target_os = "linux") {
+            assert_eq!(note.as_deref(), Some("/proc is not mounted, so cpu, load came up unknown; memory, swap and uptime come from sysinfo(2) instead"));
+        } else {
+            assert_eq!(note, None);
+        }
+        
+        fs::create_dir_all(dir.join("self")).unwrap();
+        fs::write(dir.join("self/stat"), "1 (init) S 0\n").unwrap();
+        assert!(proc_mounted(&dir));
+        assert_eq!(proc_missing_note(&info, &config, &dir), None);
+    }
+
+    #[test]
+    fn detects_zswap() {
+        assert!(zswap_enabled("Y\n"));
+        assert!(zswap_enabled("1\n"));
+        assert!(!zswap_enabled("N\n"));
+        assert!(!zswap_enabled(""));
+    }
+
+    #[test]
+    fn fills_in_empty_title_names() {
+        assert_eq!(title("alex", "box"), "alex@box");
+        assert_eq!(title("", "box"), "unknown@box");
+        assert_eq!(title("alex", " \n"), "alex@unknown");
+        assert_eq!(title("", ""), "unknown@unknown");
+        
+        let config = Config { color: false, logo_position: LogoPosition::None, modules: Some(Vec::new()), ..Config::default() };
+        assert_eq!(render_output(&SystemInfo::default(), &config), "\nunknown@unknown\n───────────────\n\n");
+    }
+
+    #[test]
+    fn sizes_separator_to_display_width() {
+        assert_eq!(title_separator("user@host", None), "─".repeat(9));
+        // "José" is 5 bytes but 4 columns; CJK characters are 2 columns each
+        assert_eq!(title_separator("José@host", None), "─".repeat(9));
+        assert_eq!(title_separator("用户@host", None), "─".repeat(9));
+        assert_eq!(title_separator("用户@host", Some(3)), "─".repeat(12));
+        assert_eq!(title_separator("user@host", Some(-4)), "─".repeat(5));
+        assert_eq!(title_separator("user@host", Some(-20)), "");
+        assert_eq!(title_separator("", None), "─".repeat(MIN_SEPARATOR_LENGTH));
+    }
+
+    #[test]
+    fn lists_logo_ids() {
+        assert_eq!(os_release_ids(&parse_os_release(fixture!("arch", "os-release"))), ["arch"]);
+        let os_release = parse_os_release("ID=pop\nID_LIKE=\"ubuntu debian\"\n");
+        assert_eq!(os_release_ids(&os_release), ["pop", "ubuntu", "debian"]);
+        assert!(os_release_ids(&HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn measures_logo_width() {
+        assert_eq!(column_width(&["/", "/////", ""]), 5);
+        assert_eq!(column_width(&["██", "日本語"]), 6);
+        assert_eq!(column_width(&["\x1b[34m🦀🦀\x1b[0m", "abc"]), 4);
+        assert_eq!(column_width::<&str>(&[]), 0);
+    }
+
+    #[test]
+    fn parses_swapusage() {
+        let output = "total = 2048.00M  used = 512.00M  free = 1536.00M  (encrypted)";
+        let (used, total) = parse_swapusage(output).unwrap();
+        assert_eq!((used, total), (512 * 1024 * 1024, 2048 * 1024 * 1024));
+        assert_eq!(format_usage(used, total, Precision::default()), "0.50 GiB / 2.00 GiB (25%)");
+
+        assert_eq!(
+            parse_swapusage("total = 0.00M  used = 0.00M  free = 0.00M  (encrypted)"),
+            Some((0, 0))
+        );
+        assert_eq!(parse_swapusage("vm.swapusage: unknown"), None);
+    }
+
+    #[test]
+    fn parses_shell_version() {
+        assert_eq!(parse_shell_version("zsh 5.9 (x86_64-apple-darwin23.0)").as_deref(), Some("5.9"));
+        assert_eq!(parse_shell_version("zsh 5.9 (arm64-apple-darwin24.0)").as_deref(), Some("5.9"));
+        assert_eq!(
+            parse_shell_version("GNU bash, version 3.2.57(1)-release (arm64-apple-darwin24)\nCopyright (C) 2007").as_deref(),
+            Some("3.2.57")
+        );
+        assert_eq!(parse_shell_version("fish, version 3.7.1").as_deref(), Some("3.7.1"));
+        assert_eq!(parse_shell_version("0.95.0").as_deref(), Some("0.95.0"));
+        assert_eq!(parse_shell_version("sh: 0: Illegal option --"), None);
+    }
+
+    #[test]
+    fn renders_every_registered_module() {
+        let config = Config::default();
+        let info = SystemInfo {
+            display: vec!["Display: 1920x1080".to_string()],
+            gpu: vec!["GPU A".to_string(), "GPU B".to_string()],
+            disk: vec!["Disk (/): 1 GiB".to_string()],
+            kernel_modules: Some("184".to_string()),
+            terminal_size: Some("120x40".to_string()),
+            audio_device: Some("Built-in Speakers".to_string()),
+            secure_boot: Some(true),
+            boot_mode: Some("UEFI"),
+            root_fs: Some("ext4".to_string()),
+            power_source: Some("AC"),
+            install_date: Some("2024-03-12".to_string()),
+            timezone: Some("Europe/London (BST, +01:00)".to_string()),
+            vpn: Some(vec!["wg0".to_string()]),
+            git: Some("main *".to_string()),
+            load: Some("0.52, 0.61, 0.48".to_string()),
+            users: Some(2),
+            keyboard_layout: Some("us".to_string()),
+            swappiness: Some((60, None)),
+            ram_slots: Some((4, vec![16 << 30, 16 << 30])),
+            gpu_vram_used: Some((1 << 30, 8 << 30)),
+            updates: Some(14),
+            tpm: Some("2.0".to_string()),
+            cmdline: Some("quiet splash".to_string()),
+            tearing: Some(true),
+            workspaces: Some((5, "2".to_string())),
+            cpu_cache: Some("L1 512 KiB".to_string()),
+            cpu_temp: Some(54.2),
+            gpu_clock: Some("2520 MHz core".to_string()),
+            terminal_theme: Some(terminal::Background::Dark),
+            display_count: Some(2),
+            ..SystemInfo::default()
+        };
+        for module in modules::MODULES {
+            assert!(!module_lines(&info, &config, module.name).is_empty(), "{} rendered nothing", module.name);
+        }
+        assert_eq!(module_lines(&info, &config, "gpu"), ["GPU: GPU A", "GPU: GPU B"]);
+        
+        let info = SystemInfo { active_gpu: Some("GPU B".to_string()), ..info };
+        assert_eq!(module_lines(&info, &config, "gpu"), ["GPU: GPU A", "GPU: GPU B", "GPU (active): GPU B"]);
+    }
+
+    #[test]
+    fn picks_cpu_sensors() {
+        let dir = TestDir::new("picks_cpu_sensors");
+        let chip = |hwmon: &str, name: &str, inputs: &[(u32, Option<&str>, &str)]| {
+            let chip_dir = dir.join(hwmon);
+            fs::create_dir_all(&chip_dir).unwrap();
+            fs::write(chip_dir.join("name"), format!("{}\n", name)).unwrap();
+            for (n, label, millidegrees) in inputs {
+                fs::write(chip_dir.join(format!("temp{}_input", n)), format!("{}\n", millidegrees)).unwrap();
+                if let Some(label) = label {
+                    fs::write(chip_dir.join(format!("temp{}_label", n)), format!("{}\n", label)).unwrap();
+                }
+            }
+        };
+        chip("hwmon0", "nvme", &[(1, Some("Composite"), "38850")]);
+        chip("hwmon1", "k10temp", &[(1, Some("Tctl"), "64250"), (2, Some("Tdie"), "54250"), (3, Some("Tccd1"), "51000")]);
+        chip("hwmon2", "acpitz", &[(1, None, "27800")]);
+        
+        let sensors = read_sensors(&dir);
+        assert_eq!(sensors.len(), 5);
+        assert_eq!(sensors[4], Sensor { chip: "acpitz".to_string(), label: "temp1".to_string(), celsius: 27.8 });
+        assert_eq!(pick_cpu_temp(&sensors, None).map(|sensor| sensor.celsius), Some(54.25));
+        assert_eq!(pick_cpu_temp(&sensors, Some("acpitz")).map(|sensor| sensor.celsius), Some(27.8));
+        assert_eq!(pick_cpu_temp(&sensors, Some("coretemp")), None);
+        assert_eq!(pick_cpu_temp(&sensors[..1], None), None);
+        
+        let list = format_sensor_list(&sensors);
+        assert!(list.starts_with("nvme     Composite  38.9°C\n"));
+        assert!(list.ends_with("acpitz   temp1      27.8°C\n"));
+        assert!(read_sensors(&dir.join("missing")).is_empty());
+    }
+
+    #[test]
+    fn maps_gpus_to_displays() {
+        let dir = TestDir::new("maps_gpus_to_displays");
+        let card = |name: &str, vendor: &str| {
+            fs::create_dir_all(dir.join(name).join("device")).unwrap();
+            fs::write(dir.join(name).join("device").join("vendor"), format!("{}\n", vendor)).unwrap();
+        };
+        let connector = |name: &str, status: &str| {
+            fs::create_dir_all(dir.join(name)).unwrap();
+            fs::write(dir.join(name).join("status"), format!("{}\n", status)).unwrap();
+        };
+        card("card0", "0x8086");
+        card("card1", "0x10de");
+        connector("card0-eDP-1", "connected");
+        connector("card1-DP-1", "connected");
+        connector("card1-HDMI-A-1", "connected");
+        connector("card1-DP-2", "disconnected");
+        fs::create_dir_all(dir.join("renderD128")).unwrap();
+        
+        assert_eq!(get_gpu_displays(&dir), ["GPU 1 (Intel): eDP-1", "GPU 2 (NVIDIA): DP-1, HDMI-A-1"]);
+        fs::remove_dir_all(dir.join("card0-eDP-1")).unwrap();
+        assert_eq!(get_gpu_displays(&dir)[0], "GPU 1 (Intel): no displays");
+        assert!(get_gpu_displays(&dir.join("missing")).is_empty());
+        assert_eq!(pci_vendor_name("0x1234"), "0x1234");
+    }
+
+    #[test]
+    fn renders_custom_modules() {
+        let custom = |name: &str, on_error| config::CustomModule {
+            name: name.to_string(),
+            command: String::new(),
+            timeout_ms: None,
+            on_error,
+        };
+        let config = Config {
+            custom_modules: vec![
+                custom("Weather", OnError::Hide),
+                custom("Todo", OnError::Hide),
+                custom("Mail", OnError::Unknown),
+            ],
+            ..Config::default()
+        };
+        let info = SystemInfo {
+            custom: HashMap::from([
+                ("Weather".to_string(), Some("London: +12°C".to_string())),
+                ("Todo".to_string(), None),
+                ("Mail".to_string(), None),
+            ]),
+            ..SystemInfo::default()
+        };
+        assert_eq!(module_lines(&info, &config, "Weather"), ["Weather: London: +12°C"]);
+        assert!(module_lines(&info, &config, "Todo").is_empty());
+        assert_eq!(module_lines(&info, &config, "Mail"), ["Mail: unknown"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_user_commands_with_timeout() {
+        let timeout = Duration::from_secs(5);
+        assert_eq!(user_command("printf 'first\\nsecond'", timeout).as_deref(), Some("first\nsecond"));
+        assert_eq!(user_command("exit 1", timeout), None);
+        assert_eq!(user_command("sleep 5", Duration::from_millis(50)), None);
+    }
+
+    // Builds a minimal EDID with the given video input byte and, optionally,
+    // a CTA-861 extension carrying an HDR static metadata block
+    fn edid(input: u8, hdr_eotf: Option<u8>) -> Vec<u8> {
+        let mut edid = vec![0u8; 128];
+        edid[..8].copy_from_slice(&[0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00]);
+        edid[20] = input;
+        if let Some(eotf) = hdr_eotf {
+            let mut cta = vec![0u8; 128];
+            cta[0] = 0x02;
+            cta[1] = 0x03;
+            // Video data block (tag 2, 2 bytes) followed by the HDR block
+            cta[4..7].copy_from_slice(&[0x42, 0x10, 0x04]);
+            cta[7..11].copy_from_slice(&[0xe3, 0x06, eotf, 0x01]);
+            cta[2] = 11;
+            edid.extend(cta);
+        }
+        edid
+    }
+
+    #[test]
+    fn parses_edid_details() {
+        let details = parse_edid_details(&edid(0xb5, Some(0x05)));
+        assert_eq!((details.depth, details.hdr), (Some(10), true));
+
+        let details = parse_edid_details(&edid(0xa5, Some(0x03)));
+        assert_eq!((details.depth, details.hdr), (Some(8), false));
+
+        // Analog input and EDID 1.3 panels don't report a depth
+        let details = parse_edid_details(&edid(0x80, None));
+        assert_eq!((details.depth, details.hdr), (None, false));
+        let details = parse_edid_details(&edid(0x0e, None));
+        assert_eq!((details.depth, details.hdr), (None, false));
+
+        // Empty or garbage EDIDs are common on virtual outputs
+        let details = parse_edid_details(&[]);
+        assert_eq!((details.depth, details.hdr), (None, false));
+        let details = parse_edid_details(&[0u8; 128]);
+        assert_eq!((details.depth, details.hdr), (None, false));
+        
+        // No timing descriptor: the size comes from the base block's centimetres
+        assert_eq!(parse_edid_details(&edid(0xb5, None)).size_mm, None);
+        let mut base = edid(0xb5, None);
+        base[21..23].copy_from_slice(&[60, 34]);
+        assert_eq!(parse_edid_details(&base).size_mm, Some((600, 340)));
+        // 597x336 mm in the first detailed timing descriptor wins
+        base[54..56].copy_from_slice(&[0x08, 0xe8]);
+        base[66..69].copy_from_slice(&[0x55, 0x50, 0x21]);
+        assert_eq!(parse_edid_details(&base).size_mm, Some((597, 336)));
+    }
+
+    #[test]
+    fn reads_edid_preferred_mode() {
+        // 1920x1080 with 280/45 blanking at 148.5 MHz is 60 Hz
+        let mut base = edid(0xb5, None);
+        base[54..62].copy_from_slice(&[0x02, 0x3a, 0x80, 0x18, 0x71, 0x38, 0x2d, 0x40]);
+        assert_eq!(edid_preferred_mode(&base), Some((1920, 1080, 60)));
+        // 2560x1440 at 241.5 MHz (CVT reduced blanking) is 59.95 Hz
+        base[54..62].copy_from_slice(&[0x56, 0x5e, 0x00, 0xa0, 0xa0, 0xa0, 0x29, 0x50]);
+        assert_eq!(edid_preferred_mode(&base), Some((2560, 1440, 60)));
+
+        assert_eq!(edid_preferred_mode(&edid(0xb5, None)), None);
+        assert_eq!(edid_preferred_mode(&[]), None);
+        assert_eq!(edid_preferred_mode(&[0u8; 128]), None);
+    }
+
+    #[test]
+    fn counts_connected_drm_connectors() {
+        let dir = TestDir::new("counts_connected_drm_connectors");
+        for (connector, status) in [("card0-DP-1", Some("connected")), ("card0-HDMI-A-1", Some("disconnected")), ("card0", None)] {
+            fs::create_dir_all(dir.join(connector)).unwrap();
+            if let Some(status) = status {
+                fs::write(dir.join(connector).join("status"), format!("{}\n", status)).unwrap();
+            }
+        }
+        assert_eq!(drm_connected_connectors(&dir), Some(vec![dir.join("card0-DP-1")]));
+        assert_eq!(drm_connected_connectors(&dir.join("missing")), None);
+    }
+
+    #[test]
+    fn parses_macos_displays() {
+        assert_eq!(
+            parse_macos_displays(fixture!("macos", "SPDisplaysDataType-m2pro"), false),
+            ["Display: 3456x2234 (Retina)", "Display: 3840x2160 @ 60 Hz"]
+        );
+        assert_eq!(
+            parse_macos_displays(fixture!("macos", "SPDisplaysDataType-m1"), false),
+            ["Display: 2560x1600 (Retina)"]
+        );
+        assert_eq!(
+            parse_macos_displays(fixture!("macos", "SPDisplaysDataType-intel"), true),
+            ["Display: 3072x1920 (Retina, 10-bit)"]
+        );
+        let sonoma = "      Displays:\n        Color LCD:\n          Resolution: 3024 x 1964 Retina\n          UI Looks like: 1512 x 982 @ 120.00Hz\n";
+        assert_eq!(parse_macos_displays(sonoma, false), ["Display: 3024x1964 @ 120 Hz (Retina)"]);
+        assert!(parse_macos_displays("", false).is_empty());
+    }
+
+    #[test]
+    fn renders_output_in_one_piece() {
+        let config = Config { color: false, ..Config::default() };
+        let info = SystemInfo {
+            username: "alex".to_string(),
+            hostname: "box".to_string(),
+            os: "Arch Linux".to_string(),
+            ..SystemInfo::default()
+        };
+        let output = render_output(&info, &config);
+        let lines: Vec<&str> = output.lines().collect();
+        // Framed by blank lines, with the title and OS alongside the logo
+        assert_eq!(lines.first(), Some(&""));
+        assert_eq!(lines.last(), Some(&""));
+        assert!(output.ends_with('\n'));
+        assert!(lines[1].ends_with("alex@box"));
+        assert!(lines.iter().any(|line| line.ends_with("OS: Arch Linux")));
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    fn retries_until_success() {
+        let mut calls = 0;
+        assert_eq!(with_retry(3, || { calls += 1; (calls == 2).then_some(calls).ok_or(Failure::Transient) }), Some(2));
+        assert_eq!(calls, 2);
+        
+        calls = 0;
+        assert_eq!(with_retry(1, || { calls += 1; Err::<(), _>(Failure::Transient) }), None);
+        assert_eq!(calls, 1);
+        
+        calls = 0;
+        assert_eq!(with_retry(2, || { calls += 1; Err::<(), _>(Failure::Transient) }), None);
+        assert_eq!(calls, 2);
+        
+        calls = 0;
+        assert_eq!(with_retry(3, || { calls += 1; Err::<(), _>(Failure::Permanent) }), None);
+        assert_eq!(calls, 1);
+        assert_eq!(try_shell_command("rustfetch-no-such-program", &[]), Err(Failure::Permanent));
+    }
+
+    #[test]
+    fn truncates_long_values() {
+        let info = SystemInfo {
+            username: "alex".to_string(),
+            hostname: "box".to_string(),
+            gpu: vec!["NVIDIA GeForce RTX 4070 Laptop GPU".to_string()],
+            cpu: "AMD Ryzen 7".to_string(),
+            ..SystemInfo::default()
+        };
+        let render = |max_value_width| {
+            let config = Config {
+                color: false,
+                logo_position: LogoPosition::None,
+                modules: Some(vec!["gpu".to_string(), "cpu".to_string()]),
+                max_value_width,
+                ..Config::default()
+            };
+            render_output(&info, &config)
+        };
+        assert!(render(Some(12)).contains("\nGPU: NVIDIA GeFo…\nCPU: AMD Ryzen 7\n"));
+        assert!(render(None).contains("\nGPU: NVIDIA GeForce RTX 4070 Laptop GPU\n"));
+    }
+
+    #[test]
+    fn renders_mock_info() {
+        let config = Config {
+            color: false,
+            logo: Some("arch".to_string()),
+            ..Config::default()
+        };
+        for module in modules::MODULES {
+            assert!(!module_lines(&mock::system_info(), &config, module.name).is_empty(), "mock has no {}", module.name);
+        }
+        let output = render_output(&mock::system_info(), &config);
+        let lines: Vec<&str> = output.lines().collect();
+        // Every info line starts in the same column, past the widest logo line
+        let logo = logos::render(&logos::find_named("arch", None).unwrap().art, &[]);
+        let column = logo.iter().map(|line| text::visible_width(line)).max().unwrap() + config.logo_gap;
+        for expected in ["alex@framework", "OS: Arch Linux x86_64", "Uptime: 3 hours, 12 mins", "Battery (BAT1): 87% [Discharging]"] {
+            let line = lines.iter().find(|line| line.ends_with(expected)).unwrap();
+            assert_eq!(text::visible_width(&line[..line.len() - expected.len()]), column, "{} is out of line", expected);
+        }
+        
+        let config = Config { color: true, truecolor: Some(false), disk_warn_threshold: Some(20.0), ..config };
+        let output = render_output(&mock::system_info(), &config);
+        let disk = output.lines().find(|line| line.contains("Disk (/)")).unwrap();
+        assert!(disk.contains("\x1b[33mDisk (/)"), "disk line isn't yellow: {:?}", disk);
+        assert_eq!(text::strip_ansi(&output).lines().count(), lines.len());
+    }
+
+    #[test]
+    fn renders_logo_positions() {
+        let info = SystemInfo {
+            username: "alex".to_string(),
+            hostname: "box".to_string(),
+            os: "Arch Linux".to_string(),
+            ..SystemInfo::default()
+        };
+        let render = |logo_position| {
+            let config = Config {
+                color: false,
+                logo: Some("debian".to_string()),
+                logo_position,
+                modules: Some(vec!["os".to_string()]),
+                ..Config::default()
+            };
+            render_output(&info, &config)
+        };
+        let logo = logos::find_named("debian", None).unwrap().art;
+        let first_logo_line = logo.lines().next().unwrap();
+        
+        let left = render(LogoPosition::Left);
+        assert!(left.lines().nth(1).unwrap().starts_with(first_logo_line));
+        assert!(left.lines().nth(1).unwrap().ends_with("alex@box"));
+        
+        let right = render(LogoPosition::Right);
+        assert!(right.lines().nth(1).unwrap().starts_with("alex@box"));
+        assert!(right.lines().nth(1).unwrap().trim_end().ends_with(first_logo_line.trim_end()));
+        
+        let top = render(LogoPosition::Top);
+        let top_lines: Vec<&str> = top.lines().collect();
+        assert_eq!(top_lines[1], first_logo_line);
+        assert_eq!(&top_lines[top_lines.len() - 4..], ["alex@box", "────────", "OS: Arch Linux", ""]);
+        
+        assert_eq!(render(LogoPosition::None), "\nalex@box\n────────\nOS: Arch Linux\n\n");
+    }
+
+    #[test]
+    fn renders_header_and_footer() {
+        let info = SystemInfo {
+            username: "alex".to_string(),
+            hostname: "box".to_string(),
+            ..SystemInfo::default()
+        };
+        let config = Config {
+            color: false,
+            logo: Some("debian".to_string()),
+            logo_position: LogoPosition::None,
+            modules: Some(Vec::new()),
+            header: Some("${c1}stay \x1b[1mcurious\x1b[0m".to_string()),
+            footer: Some("--\nbye".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(render_output(&info, &config), "\nstay curious\nalex@box\n────────\n--\nbye\n\n");
+        
+        let config = Config { color: true, truecolor: Some(false), footer: None, ..config };
+        let output = render_output(&info, &config);
+        // Like the logo, the text starts out in the logo's first color
+        assert!(output.starts_with("\n\x1b[31mstay \x1b[1mcurious\x1b[0m\n"), "{:?}", output);
+    }
+
+    #[test]
+    fn renders_top_padding() {
+        let info = SystemInfo {
+            username: "alex".to_string(),
+            hostname: "box".to_string(),
+            ..SystemInfo::default()
+        };
+        let render = |info_padding_top, logo_padding_top| {
+            let config = Config {
+                color: false,
+                logo: Some("debian".to_string()),
+                modules: Some(Vec::new()),
+                info_padding_top,
+                logo_padding_top,
+                ..Config::default()
+            };
+            render_output(&info, &config)
+        };
+        let first_logo_line = logos::find_named("debian", None).unwrap().art.lines().next().unwrap().to_string();
+        
+        let output = render(2, 0);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[1].starts_with(&first_logo_line) && !lines[1].contains("alex@box"));
+        assert!(lines[3].ends_with("alex@box"));
+        
+        let output = render(0, 1);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[1].trim_start(), "alex@box");
+        assert!(lines[2].starts_with(&first_logo_line));
+    }
+
+    #[test]
+    fn compares_versions() {
+        assert!(version_is_newer("0.2.0", "0.1.0"));
+        assert!(version_is_newer("0.1.10", "0.1.9"));
+        assert!(version_is_newer("1.0.0", "0.9.9"));
+        assert!(!version_is_newer("0.1.0", "0.1.0"));
+        assert!(!version_is_newer("0.1.0", "0.2.0"));
+        assert!(!version_is_newer("garbage", "0.1.0"));
+        
+        let response = r#"{"crate":{"id":"rustfetch","max_version":"0.3.0-beta.1","max_stable_version":"0.2.1","name":"rustfetch"}}"#;
+        assert_eq!(json_value(response, "max_stable_version"), Some("\"0.2.1\""));
+        
+        let escaped = r#"{"name":"say \"hi\" C:\\ws","num":"3"}"#;
+        assert_eq!(json_value(escaped, "name"), Some(r#""say \"hi\" C:\\ws""#));
+        assert_eq!(json_value(escaped, "name").and_then(json_string).as_deref(), Some(r#"say "hi" C:\ws"#));
+        assert_eq!(json_string(r#""caf\u00e9\/bar""#).as_deref(), Some("café/bar"));
+        assert_eq!(json_string("true"), None);
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let info = SystemInfo {
+            username: "alex".to_string(),
+            hostname: "box".to_string(),
+            os: "Arch Linux".to_string(),
+            kernel: "6.9.1".to_string(),
+            ..SystemInfo::default()
+        };
+        let config = Config { modules: Some(vec!["os".to_string()]), ..Config::default() };
+        let dump = Config { modules: Some(vec!["os".to_string(), "kernel".to_string()]), ..Config::default() };
+        assert_eq!(
+            render_markdown(&info, &config, &dump),
+            "```text\nalex@box\nOS: Arch Linux\n```\n\n\
+             <details>\n<summary>All modules</summary>\n\n```text\nOS: Arch Linux\nKernel: 6.9.1\n```\n\n</details>\n"
+        );
+        
+        let dump = markdown_dump_config(&config);
+        let modules = dump.modules.as_deref().unwrap();
+        assert_eq!(modules[0], "os");
+        assert!(modules.iter().any(|name| name == "cpu_cache"));
+        assert!(!modules.iter().any(|name| name == "updates"));
+        assert!(!modules.iter().any(|name| name == "ram_slots" || name == "terminal_theme"));
+        
+        let config = Config { modules: Some(vec!["os".to_string(), "ram_slots".to_string()]), ..Config::default() };
+        assert!(markdown_dump_config(&config).modules.unwrap().iter().any(|name| name == "ram_slots"));
+    }
+
+    #[test]
+    fn finds_unknown_modules() {
+        let config = Config::default();
+        let info = SystemInfo {
+            os: "Arch Linux".to_string(),
+            host: "Unknown".to_string(),
+            kernel: "unknown".to_string(),
+            gpu: vec!["Unknown GPU".to_string()],
+            disk: vec!["Disk (/): 1 GiB".to_string()],
+            ..SystemInfo::default()
+        };
+        assert_eq!(
+            unknown_modules(&info, &config, &["os", "host", "kernel", "gpu", "disk", "shell"]),
+            ["host", "kernel", "gpu", "shell"]
+        );
+    }
+
+    #[test]
+    fn picks_logo_override() {
+        let some = |logo: &str| Some(logo.to_string());
+        assert_eq!(logo_override(some("arch"), some("debian")), some("arch"));
+        assert_eq!(logo_override(None, some("debian")), some("debian"));
+        assert_eq!(logo_override(None, some("")), None);
+        assert_eq!(logo_override(None, None), None);
+    }
+
+    #[test]
+    fn detects_preemption_models() {
+        assert_eq!(preemption_model("#1 SMP PREEMPT_DYNAMIC Thu, 13 Jun 2024 16:46:39 +0000"), Some("PREEMPT_DYNAMIC"));
+        assert_eq!(preemption_model("#1 SMP PREEMPT_RT Fri Jun 14 10:00:00 UTC 2024"), Some("PREEMPT_RT"));
+        assert_eq!(preemption_model("#1 SMP PREEMPT RT Mon Jan 8 12:00:00 UTC 2018"), Some("PREEMPT_RT"));
+        assert_eq!(preemption_model("#77-Ubuntu SMP PREEMPT Wed May 1 2024"), Some("PREEMPT"));
+        assert_eq!(preemption_model("#1 SMP Tue Apr 2 2024"), None);
+    }
+
+    #[test]
+    fn decodes_powershell_output() {
+        assert_eq!(decode_powershell_output("Microsoft Windows 11 Famille\r\n".as_bytes()), "Microsoft Windows 11 Famille\r\n");
+        assert_eq!(decode_powershell_output("\u{feff}José".as_bytes()), "José");
+        let utf16: Vec<u8> = [0xfeff].into_iter().chain("Björn".encode_utf16()).flat_map(u16::to_le_bytes).collect();
+        assert_eq!(decode_powershell_output(&utf16), "Björn");
+        assert_eq!(decode_powershell_output(b""), "");
+    }
+
+    #[test]
+    fn hides_unknown_lines() {
+        let info = SystemInfo {
+            kernel: "unknown".to_string(),
+            cpu: "AMD Ryzen 7 7840U (16)".to_string(),
+            gpu: vec!["Unknown GPU".to_string()],
+            ..SystemInfo::default()
+        };
+        let config = Config::default();
+        assert_eq!(module_lines(&info, &config, "gpu"), ["GPU: Unknown GPU"]);
+        assert_eq!(module_lines(&info, &config, "kernel"), ["Kernel: unknown"]);
+        
+        let config = Config { hide_unknown: true, ..Config::default() };
+        assert!(module_lines(&info, &config, "gpu").is_empty());
+        assert!(module_lines(&info, &config, "kernel").is_empty());
+        assert_eq!(module_lines(&info, &config, "cpu"), ["CPU: AMD Ryzen 7 7840U (16)"]);
+        let info = SystemInfo { gpu: vec!["Unknown GPU".to_string(), "AMD Radeon 780M".to_string()], ..info };
+        assert_eq!(module_lines(&info, &config, "gpu"), ["GPU: AMD Radeon 780M"]);
+    }
+
+    #[test]
+    fn empty_lists_emit_no_labels() {
+        let info = SystemInfo::default();
+        for config in [Config::default(), Config { hide_unknown: true, ..Config::default() }] {
+            for module in ["display", "gpu", "disk"] {
+                assert!(module_lines(&info, &config, module).is_empty(), "{} printed a label", module);
+            }
+        }
+    }
+
+    #[test]
+    fn picks_hidden_modules() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        assert_eq!(disabled_modules(Some(" gpu, uptime,,".to_string())), names(&["gpu", "uptime"]));
+        assert_eq!(disabled_modules(None), Vec::<String>::new());
+        
+        // `--hide` adds to RUSTFETCH_DISABLE rather than replacing it
+        let mut config = Config::default();
+        assert!(config.hide(&names(&["gpu"])).is_empty());
+        assert!(config.hide(&names(&["uptime"])).is_empty());
+        assert!(!config.is_shown("gpu") && !config.is_shown("uptime") && config.is_shown("cpu"));
+        // Unknown names come back, and the known ones beside them still apply
+        assert_eq!(config.hide(&names(&["weather", "disk"])), names(&["weather"]));
+        assert!(!config.is_shown("disk"));
+    }
+
+    #[test]
+    fn formats_memory() {
+        let verbose = |usage| format_memory(usage, MemoryFormat::Verbose);
+        let short = |usage| format_memory(usage, MemoryFormat::Short);
+        assert_eq!(verbose("8.10 GiB / 16.00 GiB (50%)"), "8.10 GiB / 16.00 GiB (50%)");
+        assert_eq!(short("8.10 GiB / 16.00 GiB (50%)"), "8.1/16 GiB");
+        assert_eq!(short("0.00 GiB / 8.00 GiB (0%) (zswap active)"), "0/8 GiB (zswap active)");
+        // Windows reports swap usage in MiB
+        assert_eq!(short("512.00 MiB / 4.00 GiB (12%)"), "512 MiB/4 GiB");
+        assert_eq!(short("No swap"), "No swap");
+        assert_eq!(short("unknown"), "unknown");
+        
+        let config = Config { memory_format: MemoryFormat::Short, ..Config::default() };
+        let info = SystemInfo {
+            disk: vec!["Disk (C:): 120.50 GiB / 476.00 GiB (25%) - NTFS".to_string(), "Unknown disk".to_string()],
+            ..SystemInfo::default()
+        };
+        assert_eq!(module_lines(&info, &config, "disk"), ["Disk (C:): 120.5/476 GiB - NTFS", "Unknown disk"]);
+    }
+
+    #[test]
+    fn formats_display() {
+        let none = DisplayDetails::default();
+        assert_eq!(format_display(Some("DP-1"), "3840x2160", None, &none), "Display (DP-1): 3840x2160");
+        assert_eq!(format_display(None, "1920x1080", Some(60), &none), "Display: 1920x1080 @ 60 Hz");
+        assert_eq!(format_display(None, "1920x1080", Some(0), &none), "Display: 1920x1080");
+        assert_eq!(
+            format_display(Some("DP-1"), "3840x2160", Some(120), &DisplayDetails { depth: Some(10), hdr: true, ..DisplayDetails::default() }),
+            "Display (DP-1): 3840x2160 @ 120 Hz (10-bit, HDR)"
+        );
+        assert_eq!(
+            format_display(None, "2560x1440", None, &DisplayDetails { depth: Some(8), ..DisplayDetails::default() }),
+            "Display: 2560x1440 (8-bit)"
+        );
+        assert_eq!(
+            format_display(Some("HDMI-A-1"), "1920x1080", None, &DisplayDetails { preferred_refresh: Some(60), depth: Some(8), ..DisplayDetails::default() }),
+            "Display (HDMI-A-1): 1920x1080 (preferred 60 Hz, 8-bit)"
+        );
+        assert_eq!(windows_color_depth(32), Some(8));
+        assert_eq!(windows_color_depth(24), Some(8));
+        assert_eq!(windows_color_depth(30), Some(10));
+        assert_eq!(windows_color_depth(16), None);
+    }
+
+    #[test]
+    fn computes_display_ppi() {
+        // 13.3" 16:10 laptop panel and a 27" 4K monitor
+        assert_eq!(display_ppi("2560x1600", 287, 180), Some(226));
+        assert_eq!(display_ppi("3840x2160", 597, 336), Some(163));
+        assert_eq!(display_ppi("1920x1080", 0, 0), None);
+        assert_eq!(display_ppi("garbage", 597, 336), None);
+        assert_eq!(
+            format_display(Some("eDP-1"), "2560x1600", None, &DisplayDetails { size_mm: Some((287, 180)), ..DisplayDetails::default() }),
+            "Display (eDP-1): 2560x1600 (226 PPI)"
+        );
+        assert_eq!(parse_windows_monitor_size("60 34"), Some((600, 340)));
+        assert_eq!(parse_windows_monitor_size("0 0"), None);
+    }
+
+    #[test]
+    fn parses_uptime() {
+        assert_eq!(parse_uptime(fixture!("arch", "uptime")), Some(273912));
+        assert_eq!(parse_uptime(fixture!("debian", "uptime")), Some(59));
+        assert_eq!(parse_uptime(fixture!("fedora", "uptime")), Some(3547));
+        assert_eq!(parse_uptime(fixture!("raspbian", "uptime")), Some(1209664));
+        assert_eq!(parse_uptime(""), None);
+        assert_eq!(parse_uptime("-1.00 0.00"), None);
+        assert_eq!(parse_uptime("NaN 0.00"), None);
+    }
+
+    #[test]
+    fn formats_uptime() {
+        assert_eq!(format_uptime(273912, UptimeFormat::Verbose), "3 days, 4 hours, 5 mins");
+        assert_eq!(format_uptime(3547, UptimeFormat::Verbose), "59 mins");
+        assert_eq!(format_uptime(3660, UptimeFormat::Verbose), "1 hour, 1 min");
+        assert_eq!(format_uptime(59, UptimeFormat::Verbose), "less than a minute");
+    }
+
+    #[test]
+    fn formats_uptime_edge_cases() {
+        assert_eq!(format_uptime(0, UptimeFormat::Verbose), "less than a minute");
+        assert_eq!(format_uptime(60, UptimeFormat::Verbose), "1 min");
+        assert_eq!(format_uptime(3600, UptimeFormat::Verbose), "1 hour");
+        assert_eq!(format_uptime(86400, UptimeFormat::Verbose), "1 day");
+        assert_eq!(format_uptime(86399, UptimeFormat::Verbose), "23 hours, 59 mins");
+        assert_eq!(format_uptime(u64::MAX, UptimeFormat::Verbose), "213503982334601 days, 7 hours");
+    }
+
+    #[test]
+    fn formats_compact_uptime() {
+        assert_eq!(format_uptime(273912, UptimeFormat::Compact), "3d 4h 5m");
+        assert_eq!(format_uptime(3660, UptimeFormat::Compact), "1h 1m");
+        assert_eq!(format_uptime(86400, UptimeFormat::Compact), "1d");
+        assert_eq!(format_uptime(59, UptimeFormat::Compact), "0m");
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn format_uptime_invariants(seconds in proptest::num::u64::ANY) {
+            let formatted = format_uptime(seconds, UptimeFormat::Verbose);
+            proptest::prop_assert!(!formatted.is_empty());
+
+            if seconds < 60 {
+                proptest::prop_assert_eq!(formatted, "less than a minute");
+                return Ok(());
+            }
+
+            // Every part is "<count> <unit>" with the unit pluralized unless
+            // the count is exactly one, and the parts add back up to the
+            // input rounded down to the minute
+            let mut total: u128 = 0;
+            for part in formatted.split(", ") {
+                let (count, unit) = part.split_once(' ').unwrap();
+                let count: u128 = count.parse().unwrap();
+                proptest::prop_assert!(count > 0);
+                let (singular, scale) = match unit.trim_end_matches('s') {
+                    "day" => ("day", 86400),
+                    "hour" => ("hour", 3600),
+                    "min" => ("min", 60),
+                    other => panic!("unexpected unit {other:?}"),
+                };
+                proptest::prop_assert_eq!(unit == singular, count == 1);
+                total += count * scale;
+            }
+            proptest::prop_assert_eq!(total, (seconds - seconds % 60) as u128);
+        }
+
+        #[test]
+        fn parse_uptime_never_panics(contents in "\\PC*") {
+            let _ = parse_uptime(&contents);
+        }
+    }
+
+    #[test]
+    fn parses_boot_time() {
+        assert_eq!(parse_boot_time(fixture!("arch", "stat")), Some(1728649381));
+        assert_eq!(parse_boot_time(fixture!("debian", "stat")), Some(1791959830));
+        assert_eq!(parse_boot_time(fixture!("fedora", "stat")), Some(1728733002));
+        assert_eq!(parse_boot_time(fixture!("raspbian", "stat")), Some(1727523731));
+        assert_eq!(parse_boot_time("cpu  1 2 3\n"), None);
+    }
+
+    #[test]
+    fn parses_machine_info() {
+        let machine_info = parse_os_release(fixture!("fedora", "machine-info"));
+        assert_eq!(machine_info["PRETTY_HOSTNAME"], "Alex's Laptop");
+        assert_eq!(machine_info["CHASSIS"], "laptop");
+    }
+
+    #[test]
+    fn parses_os_release() {
+        let arch = parse_os_release(fixture!("arch", "os-release"));
+        assert_eq!(arch["PRETTY_NAME"], "Arch Linux");
+        assert_eq!(arch["ID"], "arch");
+
+        let debian = parse_os_release(fixture!("debian", "os-release"));
+        assert_eq!(debian["PRETTY_NAME"], "Debian GNU/Linux 12 (bookworm)");
+        assert_eq!(debian["VERSION_CODENAME"], "bookworm");
+
+        let fedora = parse_os_release(fixture!("fedora", "os-release"));
+        assert_eq!(fedora["PRETTY_NAME"], "Fedora Linux 40 (Workstation Edition)");
+        assert_eq!(fedora["VERSION_ID"], "40");
+        assert_eq!(fedora["VERSION_CODENAME"], "");
+    }
+}
@@ -0,0 +1,276 @@
+// ASCII logos, picked by distro ID. Users can drop their own into
+// `<config dir>/rustfetch/logos/<id>.txt`, which take precedence over the
+// built-ins. In both, `${c1}`..`${c9}` switch to the logo's Nth color;
+// the current color carries over from one line to the next.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::colors::Color;
+
+pub struct Logo {
+    pub art: String,
+    // Colors `${c1}`, `${c2}`, ... refer to; the art starts out in the first
+    pub colors: Vec<Color>,
+}
+
+struct Builtin {
+    id: &'static str,
+    art: &'static str,
+    colors: &'static [Color],
+}
+
+const BUILTINS: &[Builtin] = &[
+    Builtin { id: "arch", art: include_str!("logos/arch.txt"), colors: &[Color::Named(36), Color::Named(36)] },
+    Builtin { id: "debian", art: include_str!("logos/debian.txt"), colors: &[Color::Named(31)] },
+    Builtin { id: "fedora", art: include_str!("logos/fedora.txt"), colors: &[Color::Named(34)] },
+    Builtin { id: "ubuntu", art: include_str!("logos/ubuntu.txt"), colors: &[Color::Rgb(0xe9, 0x54, 0x20)] },
+    Builtin { id: "macos", art: include_str!("logos/macos.txt"), colors: APPLE_COLORS },
+    Builtin {
+        id: "linux",
+        art: include_str!("logos/linux.txt"),
+        colors: &[Color::Named(37), Color::Named(97), Color::Named(33)],
+    },
+    Builtin { id: "windows", art: include_str!("logos/windows.txt"), colors: &[Color::Named(34)] },
+    // CPU vendors, for `--logo-type cpu`
+    Builtin { id: "intel", art: include_str!("logos/intel.txt"), colors: &[Color::Rgb(0x00, 0x71, 0xc5)] },
+    Builtin { id: "amd", art: include_str!("logos/amd.txt"), colors: &[Color::Named(31)] },
+    Builtin { id: "arm", art: include_str!("logos/arm.txt"), colors: &[Color::Rgb(0x00, 0x91, 0xbd)] },
+    Builtin { id: "apple", art: include_str!("logos/macos.txt"), colors: APPLE_COLORS },
+];
+
+const APPLE_COLORS: &[Color] = &[
+    Color::Named(32),
+    Color::Named(33),
+    Color::Named(31),
+    Color::Named(31),
+    Color::Named(35),
+    Color::Named(34),
+];
+
+// Which logo is shown: the OS's, or the CPU vendor's
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogoType {
+    Os,
+    Cpu,
+}
+
+// Where the logo goes relative to the info column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogoPosition {
+    Left,
+    Right,
+    // Above the info, for narrow terminals
+    Top,
+    None,
+}
+
+impl LogoPosition {
+    pub const NAMES: &'static str = "`left`, `right`, `top` or `none`";
+
+    pub fn from_name(name: &str) -> Option<LogoPosition> {
+        match name {
+            "left" => Some(LogoPosition::Left),
+            "right" => Some(LogoPosition::Right),
+            "top" => Some(LogoPosition::Top),
+            "none" => Some(LogoPosition::None),
+            _ => None,
+        }
+    }
+}
+
+// Logo ID for the vendor of a CPU, judged by its model string
+pub fn cpu_vendor(cpu: &str) -> Option<&'static str> {
+    let cpu = cpu.to_ascii_lowercase();
+    if cpu.contains("intel") {
+        Some("intel")
+    } else if cpu.contains("amd") || cpu.contains("ryzen") || cpu.contains("epyc") {
+        Some("amd")
+    } else if cpu.starts_with("apple") {
+        Some("apple")
+    } else if ["arm", "cortex", "neoverse", "snapdragon", "qualcomm"].iter().any(|name| cpu.contains(name)) {
+        Some("arm")
+    } else {
+        None
+    }
+}
+
+// Colors for user logos that don't share an ID with a built-in
+const DEFAULT_COLORS: &[Color] = &[
+    Color::Named(34),
+    Color::Named(37),
+    Color::Named(36),
+    Color::Named(32),
+    Color::Named(33),
+    Color::Named(31),
+];
+
+// Finds the logo for the first of `ids` that has one, trying the user's
+// logo directory before the built-ins for each ID. Falls back to the
+// generic logo for the platform.
+pub fn find(ids: &[String], user_dir: Option<&Path>) -> Logo {
+    if let Some(logo) = ids.iter().find_map(|id| find_id(id, user_dir)) {
+        return logo;
+    }
+    let fallback = builtin(if cfg!(target_os = "windows") { "windows" } else { "linux" })
+        .expect("fallback logos are built in");
+    Logo { art: fallback.art.to_string(), colors: fallback.colors.to_vec() }
+}
+
+fn find_id(id: &str, user_dir: Option<&Path>) -> Option<Logo> {
+    if let Some(art) = user_dir.and_then(|dir| fs::read_to_string(dir.join(format!("{}.txt", id))).ok()) {
+        let colors = builtin(id).map_or(DEFAULT_COLORS, |logo| logo.colors);
+        return Some(Logo { art, colors: colors.to_vec() });
+    }
+    builtin(id).map(|logo| Logo { art: logo.art.to_string(), colors: logo.colors.to_vec() })
+}
+
+// A logo asked for by the user (`--logo`, RUSTFETCH_LOGO or `logo =`):
+// the ID of a user or built-in logo, or a path to a logo file. Only names
+// with a path separator (or a leading `~`) are paths, so a file called
+// "arch" in the working directory doesn't hide the built-in logo.
+pub fn find_named(name: &str, user_dir: Option<&Path>) -> Option<Logo> {
+    if !name.starts_with('~') && !name.contains(std::path::is_separator) {
+        return find_id(&name.to_ascii_lowercase(), user_dir);
+    }
+    let art = fs::read_to_string(expand_home(name)?).ok()?;
+    Some(Logo { art, colors: DEFAULT_COLORS.to_vec() })
+}
+
+// `path` with a leading `~` (but not `~user`) replaced by the home
+// directory, as a shell would for an unquoted value
+fn expand_home(path: &str) -> Option<PathBuf> {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(std::path::is_separator) => {
+            Some(dirs::home_dir()?.join(rest.trim_start_matches(std::path::is_separator)))
+        }
+        _ => Some(PathBuf::from(path)),
+    }
+}
+
+fn builtin(id: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|logo| logo.id == id)
+}
+
+// Replaces the color markers with `escapes[N - 1]` (empty strings when
+// color is off), starting every line with whatever color is in effect. A
+// marker right after the same escape (as in a line starting with the color
+// already in effect) adds nothing.
+pub fn render(art: &str, escapes: &[String]) -> Vec<String> {
+    let escape = |index: usize| escapes.get(index).map(String::as_str).unwrap_or("");
+    let mut current = escape(0);
+
+    art.lines()
+        .map(|line| {
+            let mut rendered = current.to_string();
+            // Where the last escape ended, to spot a repeat of it
+            let mut escape_end = rendered.len();
+            let mut rest = line;
+            while let Some(start) = rest.find("${c") {
+                rendered.push_str(&rest[..start]);
+                let marker = &rest[start + 3..];
+                match (marker.chars().next().and_then(|c| c.to_digit(10)), marker.get(1..2)) {
+                    (Some(n @ 1..=9), Some("}")) => {
+                        let next = escape(n as usize - 1);
+                        if !(next == current && rendered.len() == escape_end) {
+                            rendered.push_str(next);
+                            escape_end = rendered.len();
+                        }
+                        current = next;
+                        rest = &marker[2..];
+                    }
+                    // Not a marker after all; keep it as literal text
+                    _ => {
+                        rendered.push_str("${c");
+                        rest = marker;
+                    }
+                }
+            }
+            rendered.push_str(rest);
+            rendered
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn escapes() -> Vec<String> {
+        vec!["<1>".to_string(), "<2>".to_string()]
+    }
+
+    #[test]
+    fn renders_color_markers() {
+        assert_eq!(render("ab${c2}cd\nef${c1}gh\nij", &escapes()), ["<1>ab<2>cd", "<2>ef<1>gh", "<1>ij"]);
+        // Selecting the color that's already in effect doesn't repeat it
+        assert_eq!(render("${c1}ab${c1}${c1}cd\n${c2}${c2}ef", &escapes()), ["<1>ab<1>cd", "<1><2>ef"]);
+        // Markers past the end of the palette render uncolored
+        assert_eq!(render("${c5}x", &escapes()), ["<1>x"]);
+        assert_eq!(render("$${c}x${c", &escapes()), ["<1>$${c}x${c"]);
+        // With color off, markers simply disappear
+        assert_eq!(render("a${c2}b", &[]), ["ab"]);
+    }
+
+    #[test]
+    fn built_in_logos_are_well_formed() {
+        for logo in BUILTINS {
+            for line in render(logo.art, &[]) {
+                assert!(!line.contains("${c"), "stray marker in {} logo", logo.id);
+            }
+            let highest = (1..=9).rev().find(|n| logo.art.contains(&format!("${{c{}}}", n))).unwrap_or(1);
+            assert!(logo.colors.len() >= highest, "{} logo uses c{} without a color for it", logo.id, highest);
+        }
+    }
+
+    #[test]
+    fn detects_cpu_vendors() {
+        assert_eq!(cpu_vendor("Intel(R) Core(TM) i7-12700H (20)"), Some("intel"));
+        assert_eq!(cpu_vendor("AMD Ryzen 9 7950X 16-Core Processor (32)"), Some("amd"));
+        assert_eq!(cpu_vendor("Apple M2 Pro (12)"), Some("apple"));
+        assert_eq!(cpu_vendor("Cortex-A72 (4)"), Some("arm"));
+        assert_eq!(cpu_vendor("Unknown (8 cores)"), None);
+    }
+
+    #[test]
+    fn prefers_user_logos() {
+        let dir = TestDir::new("prefers_user_logos");
+        fs::write(dir.join("arch.txt"), "custom arch\n").unwrap();
+        fs::write(dir.join("gentoo.txt"), "${c2}gentoo\n").unwrap();
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        let logo = find(&ids(&["arch"]), Some(&dir));
+        assert_eq!(logo.art, "custom arch\n");
+        assert_eq!(logo.colors, builtin("arch").unwrap().colors);
+
+        let logo = find(&ids(&["gentoo", "arch"]), Some(&dir));
+        assert_eq!(logo.art, "${c2}gentoo\n");
+        assert_eq!(logo.colors, DEFAULT_COLORS);
+
+        assert_eq!(find(&ids(&["debian"]), Some(&dir)).art, builtin("debian").unwrap().art);
+        assert_eq!(find(&ids(&["debian"]), None).art, builtin("debian").unwrap().art);
+    }
+
+    #[test]
+    fn finds_named_logos() {
+        let dir = TestDir::new("finds_named_logos");
+        let path = dir.join("mine.txt");
+        fs::write(&path, "mine\n").unwrap();
+
+        let logo = find_named(path.to_str().unwrap(), None).unwrap();
+        assert_eq!(logo.art, "mine\n");
+        assert_eq!(logo.colors, DEFAULT_COLORS);
+        assert_eq!(find_named("Fedora", None).unwrap().art, builtin("fedora").unwrap().art);
+        assert_eq!(find_named("mine", Some(&dir)).unwrap().art, "mine\n");
+        // A bare name is never a path, even when the working directory
+        // (the crate root under `cargo test`) has a file by that name
+        assert!(find_named("Cargo.toml", None).is_none());
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home("~/logos/mine.txt"), Some(home.join("logos/mine.txt")));
+        assert_eq!(expand_home("~"), Some(home));
+        assert_eq!(expand_home("logos/mine.txt"), Some(PathBuf::from("logos/mine.txt")));
+        assert_eq!(expand_home("~alex/mine.txt"), Some(PathBuf::from("~alex/mine.txt")));
+        assert!(find_named("no-such-logo", Some(&dir)).is_none());
+    }
+}
@@ -0,0 +1,69 @@
+// Canned system info for `--mock` / RUSTFETCH_MOCK: the same machine every
+// time, so themes, logos and layouts can be previewed (and the display code
+// tested) without depending on what the host reports. Only in debug builds.
+
+use std::collections::HashMap;
+
+use crate::terminal::Background;
+use crate::SystemInfo;
+
+// An Arch laptop with every module filled in, optional ones included
+pub(crate) fn system_info() -> SystemInfo {
+    let text = |value: &str| value.to_string();
+    SystemInfo {
+        username: text("alex"),
+        hostname: text("framework"),
+        os: text("Arch Linux x86_64"),
+        host: text("Framework Laptop 13 (AMD Ryzen 7040Series)"),
+        kernel: text("6.9.7-arch1-1"),
+        uptime: text("3 hours, 12 mins"),
+        load: Some(text("0.52, 0.61, 0.48")),
+        packages: text("1284 (pacman), 12 (flatpak)"),
+        shell: text("zsh 5.9"),
+        display: vec![text("Display (eDP-1): 2880x1920 @ 120 Hz")],
+        de: text("Hyprland"),
+        wm: text("Wayland"),
+        wm_theme: text("Adwaita-dark"),
+        icons: text("Papirus-Dark"),
+        font: text("Inter 11"),
+        cursor: text("Bibata-Modern-Classic"),
+        terminal: text("kitty"),
+        cpu: text("AMD Ryzen 7 7840U (16)"),
+        gpu: vec![text("AMD Radeon 780M [Integrated]")],
+        active_gpu: None,
+        gpu_displays: Vec::new(),
+        gpu_clock: Some(text("800 MHz core / 2800 MHz mem")),
+        gpu_vram_used: Some((1288 << 20, 4 << 30)),
+        cpu_cache: Some(text("L1 512 KiB, L2 8 MiB, L3 16 MiB")),
+        cpu_temp: Some(54.2),
+        memory: text("6.70 GiB / 30.64 GiB (22%)"),
+        swap: text("0.00 GiB / 4.00 GiB (0%)"),
+        disk: vec![text("Disk (/): 212.40 GiB / 931.51 GiB (23%) - btrfs")],
+        local_ip: text("Local IP (wlan0): 192.168.1.42/24"),
+        vpn: Some(vec![text("wg0")]),
+        git: Some(text("main *")),
+        battery: text("Battery (BAT1): 87% [Discharging]"),
+        locale: text("en_GB.UTF-8"),
+        timezone: Some(text("Europe/London (BST, +01:00)")),
+        kernel_modules: Some(text("184")),
+        terminal_size: Some(text("120x40")),
+        terminal_theme: Some(Background::Dark),
+        display_count: Some(1),
+        audio_device: Some(text("Family 17h/19h HD Audio Controller Speaker")),
+        secure_boot: Some(true),
+        boot_mode: Some("UEFI"),
+        root_fs: Some(text("btrfs (zstd)")),
+        power_source: Some("Battery"),
+        install_date: Some(text("2024-03-12")),
+        updates: Some(14),
+        tpm: Some(text("2.0")),
+        cmdline: Some(text("quiet splash")),
+        tearing: Some(false),
+        workspaces: Some((5, text("2"))),
+        users: Some(1),
+        keyboard_layout: Some(text("gb")),
+        swappiness: Some((60, Some(100))),
+        ram_slots: Some((2, vec![16 << 30, 16 << 30])),
+        custom: HashMap::new(),
+    }
+}
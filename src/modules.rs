@@ -0,0 +1,111 @@
+// Registry of every info module, in default display order. The config's
+// `modules` list and `[[group]]` tables refer to modules by these names,
+// and `<name> = true/false` turns a module on or off.
+pub struct Module {
+    pub name: &'static str,
+    pub default_enabled: bool,
+    // One line for `--list-modules`
+    pub description: &'static str,
+}
+
+pub const MODULES: &[Module] = &[
+    Module { name: "os", default_enabled: true, description: "Distribution or OS name and architecture" },
+    Module { name: "host", default_enabled: true, description: "Machine vendor and model" },
+    Module { name: "kernel", default_enabled: true, description: "Kernel version" },
+    Module { name: "uptime", default_enabled: true, description: "Time since boot" },
+    Module { name: "load", default_enabled: false, description: "1, 5 and 15 minute load averages" },
+    Module { name: "packages", default_enabled: true, description: "Number of installed packages" },
+    Module { name: "updates", default_enabled: false, description: "Pending package updates (may use the network)" },
+    Module { name: "shell", default_enabled: true, description: "Login shell and its version" },
+    Module { name: "display", default_enabled: true, description: "Resolution and refresh rate of each display" },
+    Module { name: "display_count", default_enabled: false, description: "Number of connected displays" },
+    Module { name: "de", default_enabled: true, description: "Desktop environment" },
+    Module { name: "wm", default_enabled: true, description: "Window manager or session type" },
+    Module { name: "wm_theme", default_enabled: true, description: "Window manager theme" },
+    Module { name: "tearing", default_enabled: false, description: "Whether the compositor allows tearing (Hyprland, picom)" },
+    Module { name: "workspaces", default_enabled: false, description: "Workspace count and the active one (Hyprland, sway, i3)" },
+    Module { name: "icons", default_enabled: true, description: "Icon theme" },
+    Module { name: "font", default_enabled: true, description: "System font" },
+    Module { name: "cursor", default_enabled: true, description: "Cursor theme" },
+    Module { name: "terminal", default_enabled: true, description: "Terminal emulator" },
+    Module { name: "terminal_size", default_enabled: false, description: "Terminal size in columns and rows" },
+    Module { name: "terminal_theme", default_enabled: false, description: "Whether the terminal background is dark or light" },
+    Module { name: "cpu", default_enabled: true, description: "CPU model and thread count" },
+    Module { name: "cpu_cache", default_enabled: false, description: "Total L1, L2 and L3 cache" },
+    Module { name: "cpu_temp", default_enabled: false, description: "CPU package temperature (Linux hwmon)" },
+    Module { name: "gpu", default_enabled: true, description: "Graphics card(s)" },
+    Module { name: "gpu_clock", default_enabled: false, description: "Current GPU core and memory clocks (NVIDIA, AMD)" },
+    Module { name: "gpu_vram_used", default_enabled: false, description: "VRAM in use on the GPU (NVIDIA, AMD)" },
+    Module { name: "memory", default_enabled: true, description: "RAM in use" },
+    Module { name: "ram_slots", default_enabled: false, description: "Memory slots in use and module sizes" },
+    Module { name: "swap", default_enabled: true, description: "Swap in use" },
+    Module { name: "disk", default_enabled: true, description: "Usage of each disk" },
+    Module { name: "swappiness", default_enabled: false, description: "vm.swappiness and vm.vfs_cache_pressure (Linux)" },
+    Module { name: "root_fs", default_enabled: false, description: "Filesystem of the root mount" },
+    Module { name: "local_ip", default_enabled: true, description: "Address of the active network interface" },
+    Module { name: "git", default_enabled: false, description: "Branch of the repository in the working directory, * when dirty" },
+    Module { name: "vpn", default_enabled: false, description: "Active VPN interfaces (WireGuard, OpenVPN, Tailscale, ...)" },
+    Module { name: "battery", default_enabled: true, description: "Battery charge and state" },
+    Module { name: "power_source", default_enabled: false, description: "Running on AC or battery" },
+    Module { name: "audio_device", default_enabled: false, description: "Default audio output device" },
+    Module { name: "locale", default_enabled: true, description: "System locale" },
+    Module { name: "keyboard_layout", default_enabled: false, description: "Keyboard layouts in use" },
+    Module { name: "timezone", default_enabled: false, description: "Time zone and its current UTC offset" },
+    Module { name: "users", default_enabled: false, description: "Number of logged-in sessions" },
+    Module { name: "kernel_modules", default_enabled: false, description: "Number of loaded kernel modules" },
+    Module { name: "cmdline", default_enabled: false, description: "Kernel command line" },
+    Module { name: "secure_boot", default_enabled: false, description: "Whether Secure Boot is on" },
+    Module { name: "install_date", default_enabled: false, description: "When the OS was installed" },
+    Module { name: "boot_mode", default_enabled: false, description: "UEFI or BIOS" },
+    Module { name: "tpm", default_enabled: false, description: "TPM version" },
+];
+
+// Modules that may wait on the network, skipped by `--motd` and `--offline`
+pub const NETWORK_MODULES: &[&str] = &["local_ip", "updates"];
+
+// Modules that run slow tools (nvidia-smi, git status), need root
+// (dmidecode, mokutil) or wait on the terminal to answer a query
+pub const COSTLY_MODULES: &[&str] = &["terminal_theme", "gpu_clock", "gpu_vram_used", "ram_slots", "git", "secure_boot"];
+
+pub fn find(name: &str) -> Option<&'static Module> {
+    MODULES.iter().find(|module| module.name == name)
+}
+
+// The layout used when the config doesn't define one: every default-enabled
+// module in registry order, as a single group
+pub fn default_layout() -> Vec<Vec<String>> {
+    vec![
+        MODULES.iter()
+            .filter(|module| module.default_enabled)
+            .map(|module| module.name.to_string())
+            .collect(),
+    ]
+}
+
+// The table printed by `--list-modules`: each module's name, whether it is
+// on by default, and what it shows
+pub fn list() -> String {
+    let width = MODULES.iter().map(|module| module.name.len()).max().unwrap_or(0);
+    MODULES.iter()
+        .map(|module| {
+            let default = if module.default_enabled { "on" } else { "off" };
+            format!("{:width$}  {:3}  {}\n", module.name, default, module.description, width = width)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_module() {
+        let list = list();
+        assert_eq!(list.lines().count(), MODULES.len());
+        assert!(list.lines().next().unwrap().starts_with("os "));
+        // The defaults and descriptions line up in columns
+        let width = MODULES.iter().map(|module| module.name.len()).max().unwrap();
+        assert!(list.contains(&format!("\n{:width$}  off  TPM version\n", "tpm", width = width)));
+        assert!(list.lines().all(|line| line[width..].starts_with("  on   ") || line[width..].starts_with("  off  ")));
+    }
+}
@@ -0,0 +1,142 @@
+// Asks the terminal about itself with escape sequences. Terminals that
+// don't understand a query simply never answer, so every query gives up
+// after a short wait.
+
+use std::sync::OnceLock;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Background {
+    Dark,
+    Light,
+}
+
+impl Background {
+    pub fn name(self) -> &'static str {
+        match self {
+            Background::Dark => "Dark",
+            Background::Light => "Light",
+        }
+    }
+}
+
+// How long to wait for the terminal to answer a query
+#[cfg(unix)]
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+// Whether the terminal has a dark or light background, from its answer to
+// OSC 11 ("what is your background color?"). Asked at most once, since
+// both `theme = "auto"` and the terminal_theme module want to know.
+pub fn background() -> Option<Background> {
+    static BACKGROUND: OnceLock<Option<Background>> = OnceLock::new();
+    *BACKGROUND.get_or_init(|| {
+        let response = query("\x1b]11;?\x07")?;
+        parse_osc11_response(&response).map(|(r, g, b)| classify(r, g, b))
+    })
+}
+
+// Writes `request` to the controlling terminal and collects the reply,
+// which ends in BEL or ST (ESC \). The terminal is switched out of
+// canonical mode meanwhile so the reply isn't echoed or line-buffered.
+#[cfg(unix)]
+fn query(request: &str) -> Option<String> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let fd = tty.as_raw_fd();
+
+    // SAFETY: tcgetattr/tcsetattr only read and write the termios we pass
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return None;
+    }
+    let mut raw = original;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+        return None;
+    }
+
+    let mut response = Vec::new();
+    if tty.write_all(request.as_bytes()).and_then(|_| tty.flush()).is_ok() {
+        let deadline = Instant::now() + QUERY_TIMEOUT;
+        let mut buffer = [0u8; 64];
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let mut poll = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+            // SAFETY: poll reads one valid pollfd
+            let ready = unsafe { libc::poll(&mut poll, 1, remaining.as_millis() as libc::c_int) };
+            if ready <= 0 {
+                break;
+            }
+            match tty.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => response.extend_from_slice(&buffer[..n]),
+            }
+            if response.ends_with(b"\x07") || response.ends_with(b"\x1b\\") {
+                break;
+            }
+        }
+    }
+
+    // SAFETY: as above; puts the terminal back the way we found it
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    Some(String::from_utf8_lossy(&response).into_owned()).filter(|response| !response.is_empty())
+}
+
+#[cfg(not(unix))]
+fn query(_request: &str) -> Option<String> {
+    None
+}
+
+// Parses "\x1b]11;rgb:RRRR/GGGG/BBBB\x07" into 8-bit channels. Terminals
+// send 1 to 4 hex digits per channel, so each is scaled by its own width.
+fn parse_osc11_response(response: &str) -> Option<(u8, u8, u8)> {
+    let (_, color) = response.split_once("rgb:")?;
+    let color = color.trim_end_matches(['\x07', '\\']).trim_end_matches('\x1b');
+    let channel = |hex: &str| -> Option<u8> {
+        if hex.is_empty() || hex.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let max = 16u32.pow(hex.len() as u32) - 1;
+        Some((value * 255 / max) as u8)
+    };
+    let mut channels = color.split('/');
+    let rgb = (channel(channels.next()?)?, channel(channels.next()?)?, channel(channels.next()?)?);
+    channels.next().is_none().then_some(rgb)
+}
+
+// Dark when the color's relative luminance is below the midpoint
+fn classify(r: u8, g: u8, b: u8) -> Background {
+    let luminance = 0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64;
+    if luminance < 128.0 {
+        Background::Dark
+    } else {
+        Background::Light
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_osc11_responses() {
+        assert_eq!(parse_osc11_response("\x1b]11;rgb:1e1e/1e1e/2e2e\x07"), Some((0x1e, 0x1e, 0x2e)));
+        // ST-terminated, as sent by e.g. kitty and foot
+        assert_eq!(parse_osc11_response("\x1b]11;rgb:ffff/ffff/ffff\x1b\\"), Some((255, 255, 255)));
+        assert_eq!(parse_osc11_response("\x1b]11;rgb:f/8/0\x07"), Some((255, 136, 0)));
+        assert_eq!(parse_osc11_response("\x1b]11;rgb:ffff/ffff\x07"), None);
+        assert_eq!(parse_osc11_response(""), None);
+    }
+
+    #[test]
+    fn classifies_backgrounds() {
+        assert_eq!(classify(0x1e, 0x1e, 0x2e), Background::Dark);
+        assert_eq!(classify(0xfd, 0xf6, 0xe3), Background::Light);
+        // Saturated blue is dark despite its bright channel
+        assert_eq!(classify(0, 0, 255), Background::Dark);
+    }
+}
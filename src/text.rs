@@ -0,0 +1,97 @@
+// Terminal-width-aware string helpers. Byte and char counts both go wrong
+// for CJK text (two columns per character), emoji and combining marks, and
+// color escapes take up bytes without taking up any columns at all.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// Drops ANSI escape sequences: CSI sequences like "\x1b[1;32m" run up to
+// a final byte in '@'..='~'; any other escape covers one more character
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+// Number of terminal columns `s` occupies once printed
+pub fn visible_width(s: &str) -> usize {
+    strip_ansi(s).width()
+}
+
+// Appends spaces so `s` fills `width` columns; never shortens it
+pub fn pad_right(s: &str, width: usize) -> String {
+    let padding = width.saturating_sub(visible_width(s));
+    format!("{}{}", s, " ".repeat(padding))
+}
+
+// Shortens plain text to at most `max_width` columns, ending in "…" when
+// something had to be cut. Wide characters are never split in half.
+pub fn truncate_to_width(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    let mut truncated = String::new();
+    let mut width = 0;
+    for c in s.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if width + char_width + 1 > max_width {
+            break;
+        }
+        truncated.push(c);
+        width += char_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_ansi_escapes() {
+        assert_eq!(strip_ansi("\x1b[1m\x1b[32muser@host\x1b[0m"), "user@host");
+        assert_eq!(strip_ansi("\x1b[38;2;23;147;209m██\x1b[0m"), "██");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn measures_visible_width() {
+        assert_eq!(visible_width("user@host"), 9);
+        assert_eq!(visible_width("用户@主机"), 9);
+        assert_eq!(visible_width("🦀 rust"), 7);
+        // "e" followed by a combining acute accent is a single column
+        assert_eq!(visible_width("Jose\u{301}"), 4);
+        assert_eq!(visible_width("\x1b[34m日本\x1b[0m"), 4);
+    }
+
+    #[test]
+    fn pads_to_visible_width() {
+        assert_eq!(pad_right("abc", 5), "abc  ");
+        assert_eq!(pad_right("日本", 5), "日本 ");
+        assert_eq!(pad_right("🦀", 4), "🦀  ");
+        assert_eq!(pad_right("\x1b[34m//\x1b[0m", 4), "\x1b[34m//\x1b[0m  ");
+        assert_eq!(pad_right("too long", 3), "too long");
+    }
+
+    #[test]
+    fn truncates_to_width() {
+        assert_eq!(truncate_to_width("quiet splash", 20), "quiet splash");
+        assert_eq!(truncate_to_width("quiet splash", 8), "quiet s…");
+        // A two-column character that doesn't fit is left out entirely
+        assert_eq!(truncate_to_width("日本語テキスト", 6), "日本…");
+        assert_eq!(truncate_to_width("🦀🦀🦀", 4), "🦀…");
+    }
+}
@@ -0,0 +1,90 @@
+// Built-in color themes for `theme = "..."` / `--theme`. Each one sets the
+// title, separator, label, value and logo colors; `[colors]` entries are
+// applied on top, so a preset can be tweaked without copying it whole.
+
+use crate::colors::{Color, Colors};
+
+struct Preset {
+    name: &'static str,
+    colors: fn() -> Colors,
+}
+
+const PRESETS: &[Preset] = &[
+    Preset { name: "dark", colors: Colors::dark },
+    Preset { name: "light", colors: Colors::light },
+    Preset { name: "gruvbox", colors: gruvbox },
+    Preset { name: "nord", colors: nord },
+    Preset { name: "dracula", colors: dracula },
+    Preset { name: "monochrome", colors: monochrome },
+];
+
+// The preset called `name`, if there is one. "auto" isn't a preset: it
+// picks between dark and light at run time.
+pub fn preset(name: &str) -> Option<Colors> {
+    PRESETS.iter().find(|preset| preset.name == name).map(|preset| (preset.colors)())
+}
+
+// Preset names, in the order they're listed in errors and --help
+pub fn names() -> impl Iterator<Item = &'static str> {
+    PRESETS.iter().map(|preset| preset.name)
+}
+
+fn gruvbox() -> Colors {
+    Colors {
+        title: Color::Rgb(0xfa, 0xbd, 0x2f),
+        separator: Color::Rgb(0x92, 0x83, 0x74),
+        label: Color::Rgb(0xfe, 0x80, 0x19),
+        value: Some(Color::Rgb(0xeb, 0xdb, 0xb2)),
+        logo: Some(Color::Rgb(0xb8, 0xbb, 0x26)),
+        ..Colors::default()
+    }
+}
+
+fn nord() -> Colors {
+    Colors {
+        title: Color::Rgb(0x88, 0xc0, 0xd0),
+        separator: Color::Rgb(0x4c, 0x56, 0x6a),
+        label: Color::Rgb(0x81, 0xa1, 0xc1),
+        value: Some(Color::Rgb(0xd8, 0xde, 0xe9)),
+        logo: Some(Color::Rgb(0x5e, 0x81, 0xac)),
+        ..Colors::default()
+    }
+}
+
+fn dracula() -> Colors {
+    Colors {
+        title: Color::Rgb(0xbd, 0x93, 0xf9),
+        separator: Color::Rgb(0x62, 0x72, 0xa4),
+        label: Color::Rgb(0xff, 0x79, 0xc6),
+        value: Some(Color::Rgb(0xf8, 0xf8, 0xf2)),
+        logo: Some(Color::Rgb(0xbd, 0x93, 0xf9)),
+        ..Colors::default()
+    }
+}
+
+// Shades of gray only, for terminals (or people) that prefer no hues
+fn monochrome() -> Colors {
+    Colors {
+        title: Color::Named(97),
+        separator: Color::Named(90),
+        label: Color::Named(37),
+        value: None,
+        logo: Some(Color::Named(37)),
+        ..Colors::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_presets() {
+        assert_eq!(preset("dark"), Some(Colors::dark()));
+        assert_eq!(preset("light"), Some(Colors::light()));
+        assert_eq!(preset("nord").unwrap().label, Color::Rgb(0x81, 0xa1, 0xc1));
+        assert_eq!(preset("auto"), None);
+        assert_eq!(preset("Nord"), None);
+        assert_eq!(names().collect::<Vec<_>>(), ["dark", "light", "gruvbox", "nord", "dracula", "monochrome"]);
+    }
+}